@@ -13,8 +13,12 @@ use crate::networks::ChainConfig;
 use crate::rpc::eth;
 use crate::shim::clock::ChainEpoch;
 use crate::shim::{
-    address::Address, econ::TokenAmount, executor::Receipt, message::Message,
-    state_tree::StateTree, version::NetworkVersion,
+    address::Address,
+    econ::TokenAmount,
+    executor::{get_events, Entry, Receipt},
+    message::Message,
+    state_tree::{ActorID, StateTree},
+    version::NetworkVersion,
 };
 use crate::utils::db::{BlockstoreExt, CborStoreExt};
 use ahash::{HashMap, HashMapExt, HashSet};
@@ -27,6 +31,8 @@ use itertools::Itertools;
 use nunny::vec as nonempty;
 use parking_lot::Mutex;
 use serde::{de::DeserializeOwned, Serialize};
+use serde_tuple::{self, Deserialize_tuple, Serialize_tuple};
+use std::collections::{btree_map, BTreeMap};
 use tokio::sync::broadcast::{self, Sender as Publisher};
 use tracing::{debug, info, trace, warn};
 
@@ -35,8 +41,11 @@ use super::{
     tipset_tracker::TipsetTracker,
     Error,
 };
-use crate::db::setting_keys::HEAD_KEY;
-use crate::db::{EthMappingsStore, EthMappingsStoreExt, SettingsStore, SettingsStoreExt};
+use crate::db::setting_keys::{ETH_MAPPINGS_RANGE_KEY, HEAD_KEY};
+use crate::db::{
+    EthMappingsStore, EthMappingsStoreExt, EventsStore, EventsStoreExt, SettingsStore,
+    SettingsStoreExt,
+};
 
 // A cap on the size of the future_sink
 const SINK_CAP: usize = 200;
@@ -78,6 +87,24 @@ pub struct ChainStore<DB> {
 
     /// Ethereum mappings store
     eth_mappings: Arc<dyn EthMappingsStore + Sync + Send>,
+
+    /// Indexed actor events store
+    events: Arc<dyn EventsStore + Sync + Send>,
+}
+
+/// An actor event emitted during the execution of a tipset, as persisted in the events store.
+#[derive(Clone, Debug, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct IndexedEvent {
+    /// Actor ID of the emitter.
+    pub emitter: ActorID,
+    /// Delegated (`f4`) address of the emitter at the time of the event, if any.
+    pub delegated_address: Option<Address>,
+    /// Position of the event among all the events emitted by the tipset messages.
+    pub event_index: u64,
+    /// Position of the emitting message in the tipset.
+    pub message_index: u64,
+    pub message_cid: Cid,
+    pub entries: Vec<Entry>,
 }
 
 impl<DB> BitswapStoreRead for ChainStore<DB>
@@ -112,6 +139,7 @@ where
         db: Arc<DB>,
         settings: Arc<dyn SettingsStore + Sync + Send>,
         eth_mappings: Arc<dyn EthMappingsStore + Sync + Send>,
+        events: Arc<dyn EventsStore + Sync + Send>,
         chain_config: Arc<ChainConfig>,
         genesis_block_header: CachingBlockHeader,
     ) -> anyhow::Result<Self> {
@@ -137,6 +165,7 @@ where
             genesis_block_header,
             validated_blocks,
             eth_mappings,
+            events,
         };
 
         Ok(cs)
@@ -194,6 +223,184 @@ where
         Ok(())
     }
 
+    /// Reads the delegated message [`Cid`] mapped to the given `Hash`, if any.
+    pub fn get_mapping(&self, hash: &eth::Hash) -> Result<Option<Cid>, Error> {
+        Ok(self
+            .eth_mappings
+            .read_obj::<(Cid, u64)>(hash)?
+            .map(|(cid, _)| cid))
+    }
+
+    /// Reads the disjoint, inclusive epoch ranges covered by the Ethereum mappings index, in
+    /// ascending order.
+    pub fn eth_mappings_ranges(&self) -> Result<Vec<(ChainEpoch, ChainEpoch)>, Error> {
+        Ok(self
+            .settings
+            .read_obj(ETH_MAPPINGS_RANGE_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Marks `[lowest, highest]` as covered by the Ethereum mappings index, merging it with the
+    /// overlapping and adjacent ranges, and reports the highest range through the metrics.
+    pub fn extend_eth_mappings_range(
+        &self,
+        lowest: ChainEpoch,
+        highest: ChainEpoch,
+    ) -> Result<Vec<(ChainEpoch, ChainEpoch)>, Error> {
+        let mut ranges = self.eth_mappings_ranges()?;
+        ranges.push((lowest, highest));
+        ranges.sort_unstable();
+        let mut merged: Vec<(ChainEpoch, ChainEpoch)> = Vec::with_capacity(ranges.len());
+        for (lo, hi) in ranges {
+            match merged.last_mut() {
+                Some((_, last_hi)) if lo <= *last_hi + 1 => *last_hi = (*last_hi).max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        self.settings.write_obj(ETH_MAPPINGS_RANGE_KEY, &merged)?;
+        if let Some(&(lo, hi)) = merged.last() {
+            crate::metrics::ETH_MAPPINGS_LOWEST_EPOCH.set(lo);
+            crate::metrics::ETH_MAPPINGS_HIGHEST_EPOCH.set(hi);
+        }
+        Ok(merged)
+    }
+
+    /// Persists the actor events emitted by the messages of the given tipset, read from the
+    /// events AMTs referenced by the receipts at `receipts_root`. Events are keyed by tipset and
+    /// emitter, and emitters are resolved against `state_root`, the state the tipset produced.
+    /// Indexing an already indexed tipset is a no-op.
+    pub fn index_tipset_events(
+        &self,
+        ts: &Tipset,
+        state_root: &Cid,
+        receipts_root: &Cid,
+    ) -> anyhow::Result<()> {
+        let tipset_cid = ts.key().cid()?;
+        let tipset_key = tipset_cid.to_bytes();
+        if self.events.exists(&tipset_key)? {
+            return Ok(());
+        }
+
+        let messages = self.messages_for_tipset(ts)?;
+        let receipts = Receipt::get_receipts(self.blockstore(), *receipts_root)?;
+        anyhow::ensure!(
+            messages.len() == receipts.len(),
+            "tipset has {} messages but {} receipts",
+            messages.len(),
+            receipts.len()
+        );
+
+        // Actors deleted by the tipset itself are only found in the state it was executed on.
+        let states = [
+            StateTree::new_from_root(Arc::clone(&self.db), state_root)?,
+            StateTree::new_from_root(Arc::clone(&self.db), ts.parent_state())?,
+        ];
+        let resolve = |emitter: ActorID| -> anyhow::Result<Option<Address>> {
+            for state in &states {
+                if let Some(actor) = state.get_actor(&Address::new_id(emitter))? {
+                    return Ok(actor.delegated_address.map(Address::from));
+                }
+            }
+            Ok(None)
+        };
+
+        let mut by_emitter: BTreeMap<ActorID, (Option<Address>, Vec<IndexedEvent>)> =
+            BTreeMap::new();
+        let mut event_index = 0;
+        for (message_index, (message, receipt)) in messages.iter().zip(receipts).enumerate() {
+            let Some(events_root) = receipt.events_root() else {
+                continue;
+            };
+            for stamped in get_events(self.blockstore(), &events_root)? {
+                let (delegated_address, events) = match by_emitter.entry(stamped.emitter) {
+                    btree_map::Entry::Occupied(entry) => entry.into_mut(),
+                    btree_map::Entry::Vacant(entry) => {
+                        entry.insert((resolve(stamped.emitter)?, vec![]))
+                    }
+                };
+                events.push(IndexedEvent {
+                    emitter: stamped.emitter,
+                    delegated_address: *delegated_address,
+                    event_index,
+                    message_index: message_index as u64,
+                    message_cid: message.cid()?,
+                    entries: stamped.event.entries,
+                });
+                event_index += 1;
+            }
+        }
+
+        for (emitter, (_, events)) in &by_emitter {
+            self.events
+                .write_obj(&emitter_events_key(&tipset_key, *emitter), events)?;
+        }
+        // Listed by epoch before being marked as indexed, so that [`prune_events`] finds every
+        // indexed tipset, including the ones that were reorged out.
+        let epoch_key = epoch_events_key(ts.epoch());
+        let mut tipsets = self
+            .events
+            .read_obj::<Vec<Cid>>(&epoch_key)?
+            .unwrap_or_default();
+        if !tipsets.contains(&tipset_cid) {
+            tipsets.push(tipset_cid);
+            self.events.write_obj(&epoch_key, &tipsets)?;
+        }
+        if self
+            .events
+            .read_obj::<ChainEpoch>(EVENTS_LOWEST_EPOCH_KEY)?
+            .map_or(true, |lowest| ts.epoch() < lowest)
+        {
+            self.events
+                .write_obj(EVENTS_LOWEST_EPOCH_KEY, &ts.epoch())?;
+        }
+        // Written last, so that a tipset is only ever considered indexed once all of its events
+        // have been persisted.
+        let emitters = by_emitter
+            .into_iter()
+            .map(|(emitter, (delegated_address, _))| (emitter, delegated_address))
+            .collect_vec();
+        self.events.write_obj(&tipset_key, &emitters)?;
+        Ok(())
+    }
+
+    /// Returns whether the events of the given tipset have been indexed.
+    pub fn tipset_events_indexed(&self, tsk: &TipsetKey) -> anyhow::Result<bool> {
+        self.events.exists(&tsk.cid()?.to_bytes())
+    }
+
+    /// Returns the indexed events of the given tipset, ordered by their position in the tipset
+    /// and optionally restricted to the emitters with the given ID or delegated addresses.
+    /// Returns [`None`] if the tipset has not been indexed yet.
+    pub fn tipset_events(
+        &self,
+        tsk: &TipsetKey,
+        emitters: Option<&[Address]>,
+    ) -> anyhow::Result<Option<Vec<IndexedEvent>>> {
+        let tipset_key = tsk.cid()?.to_bytes();
+        let Some(indexed) = self
+            .events
+            .read_obj::<Vec<(ActorID, Option<Address>)>>(&tipset_key)?
+        else {
+            return Ok(None);
+        };
+        let mut events = vec![];
+        for (emitter, _) in indexed.into_iter().filter(|(emitter, delegated_address)| {
+            emitters.map_or(true, |emitters| {
+                emitters.contains(&Address::new_id(*emitter))
+                    || delegated_address.is_some_and(|address| emitters.contains(&address))
+            })
+        }) {
+            if let Some(emitted) = self
+                .events
+                .read_obj::<Vec<IndexedEvent>>(&emitter_events_key(&tipset_key, emitter))?
+            {
+                events.extend(emitted);
+            }
+        }
+        events.sort_by_key(|event| event.event_index);
+        Ok(Some(events))
+    }
+
     /// Expands tipset to tipset with all other headers in the same epoch using
     /// the tipset tracker.
     fn expand_tipset(&self, header: CachingBlockHeader) -> Result<Tipset, Error> {
@@ -518,6 +725,71 @@ where
         .collect()
 }
 
+/// Key of the lowest epoch that may have indexed events.
+const EVENTS_LOWEST_EPOCH_KEY: &[u8] = b"/events/lowest_epoch";
+
+/// Key of the list of the tipsets at `epoch` whose events have been indexed.
+fn epoch_events_key(epoch: ChainEpoch) -> Vec<u8> {
+    let mut key = b"/events/epoch/".to_vec();
+    key.extend_from_slice(&epoch.to_be_bytes());
+    key
+}
+
+/// Removes the indexed events of all the tipsets below `epoch`, whether they are still part of
+/// the canonical chain or not, and returns the number of tipsets pruned.
+pub fn prune_events(events: &impl EventsStore, epoch: ChainEpoch) -> anyhow::Result<usize> {
+    let pruned = for_each_prunable_event_key(events, epoch, |key| events.remove(key))?;
+    if events
+        .read_obj::<ChainEpoch>(EVENTS_LOWEST_EPOCH_KEY)?
+        .is_some_and(|lowest| lowest < epoch)
+    {
+        events.write_obj(EVENTS_LOWEST_EPOCH_KEY, &epoch)?;
+    }
+    Ok(pruned)
+}
+
+/// Calls `f` with the key of every row [`prune_events`] removes below `epoch`, in removal order,
+/// and returns the number of tipsets they belong to. A row is visited after it has been read, so
+/// `f` may remove it.
+pub fn for_each_prunable_event_key(
+    events: &impl EventsStore,
+    epoch: ChainEpoch,
+    mut f: impl FnMut(&[u8]) -> anyhow::Result<()>,
+) -> anyhow::Result<usize> {
+    let Some(lowest) = events.read_obj::<ChainEpoch>(EVENTS_LOWEST_EPOCH_KEY)? else {
+        return Ok(0);
+    };
+    let mut tipset_count = 0;
+    for epoch in lowest..epoch {
+        let epoch_key = epoch_events_key(epoch);
+        let Some(tipsets) = events.read_obj::<Vec<Cid>>(&epoch_key)? else {
+            continue;
+        };
+        for tipset_cid in tipsets {
+            let tipset_key = tipset_cid.to_bytes();
+            let emitters = events
+                .read_obj::<Vec<(ActorID, Option<Address>)>>(&tipset_key)?
+                .unwrap_or_default();
+            // Visited first, so that a partially pruned tipset is no longer considered indexed.
+            f(&tipset_key)?;
+            for (emitter, _) in emitters {
+                f(&emitter_events_key(&tipset_key, emitter))?;
+            }
+            tipset_count += 1;
+        }
+        f(&epoch_key)?;
+    }
+    Ok(tipset_count)
+}
+
+/// Key of the events emitted by `emitter` in the tipset identified by `tipset_key`.
+fn emitter_events_key(tipset_key: &[u8], emitter: ActorID) -> Vec<u8> {
+    let mut key = Vec::with_capacity(tipset_key.len() + std::mem::size_of::<ActorID>());
+    key.extend_from_slice(tipset_key);
+    key.extend_from_slice(&emitter.to_be_bytes());
+    key
+}
+
 /// Returns parent message receipt given `block_header` and message index.
 pub fn get_parent_receipt(
     db: &impl Blockstore,
@@ -581,8 +853,15 @@ mod tests {
             message_receipts: Cid::new_v1(DAG_CBOR, Identity.digest(&[])),
             ..Default::default()
         });
-        let cs =
-            ChainStore::new(db.clone(), db.clone(), db, chain_config, gen_block.clone()).unwrap();
+        let cs = ChainStore::new(
+            db.clone(),
+            db.clone(),
+            db.clone(),
+            db,
+            chain_config,
+            gen_block.clone(),
+        )
+        .unwrap();
 
         assert_eq!(cs.genesis_block_header(), &gen_block);
     }
@@ -596,7 +875,15 @@ mod tests {
             ..Default::default()
         });
 
-        let cs = ChainStore::new(db.clone(), db.clone(), db, chain_config, gen_block).unwrap();
+        let cs = ChainStore::new(
+            db.clone(),
+            db.clone(),
+            db.clone(),
+            db,
+            chain_config,
+            gen_block,
+        )
+        .unwrap();
 
         let cid = Cid::new_v1(DAG_CBOR, Blake2b256.digest(&[1, 2, 3]));
         assert!(!cs.is_block_validated(&cid));
@@ -604,4 +891,101 @@ mod tests {
         cs.mark_block_as_validated(&cid);
         assert!(cs.is_block_validated(&cid));
     }
+    #[test]
+    fn eth_mappings_range_extends() {
+        let db = Arc::new(crate::db::MemoryDB::default());
+        let chain_config = Arc::new(ChainConfig::default());
+        let gen_block = CachingBlockHeader::new(RawBlockHeader {
+            miner_address: Address::new_id(0),
+            ..Default::default()
+        });
+
+        let cs = ChainStore::new(
+            db.clone(),
+            db.clone(),
+            db.clone(),
+            db,
+            chain_config,
+            gen_block,
+        )
+        .unwrap();
+
+        assert_eq!(cs.eth_mappings_ranges().unwrap(), vec![]);
+        assert_eq!(
+            cs.extend_eth_mappings_range(10, 20).unwrap(),
+            vec![(10, 20)]
+        );
+        assert_eq!(
+            cs.extend_eth_mappings_range(15, 30).unwrap(),
+            vec![(10, 30)]
+        );
+        assert_eq!(cs.extend_eth_mappings_range(5, 12).unwrap(), vec![(5, 30)]);
+        // Disjoint ranges are kept apart, adjacent ones are merged.
+        assert_eq!(
+            cs.extend_eth_mappings_range(40, 50).unwrap(),
+            vec![(5, 30), (40, 50)]
+        );
+        assert_eq!(
+            cs.extend_eth_mappings_range(31, 35).unwrap(),
+            vec![(5, 35), (40, 50)]
+        );
+        assert_eq!(cs.extend_eth_mappings_range(36, 39).unwrap(), vec![(5, 50)]);
+        assert_eq!(cs.eth_mappings_ranges().unwrap(), vec![(5, 50)]);
+    }
+
+    #[test]
+    fn prune_events_below_epoch() {
+        use crate::shim::state_tree::StateTreeVersion;
+
+        let db = Arc::new(crate::db::MemoryDB::default());
+        let cs = ChainStore::new(
+            db.clone(),
+            db.clone(),
+            db.clone(),
+            db.clone(),
+            Arc::new(ChainConfig::default()),
+            CachingBlockHeader::new(RawBlockHeader {
+                miner_address: Address::new_id(0),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        let empty_amt = Amt::<Cid, _>::new(&db).flush().unwrap();
+        let messages = db
+            .put_cbor_default(&TxMeta {
+                bls_message_root: empty_amt,
+                secp_message_root: empty_amt,
+            })
+            .unwrap();
+        let state_root = StateTree::new(db.clone(), StateTreeVersion::V5)
+            .unwrap()
+            .flush()
+            .unwrap();
+        // Two tipsets at epoch 1, as after a reorg, and one at epoch 2.
+        let tipsets = [(1, 1), (1, 2), (2, 1)].map(|(epoch, miner)| {
+            Tipset::from(CachingBlockHeader::new(RawBlockHeader {
+                miner_address: Address::new_id(miner),
+                epoch,
+                state_root,
+                messages,
+                ..Default::default()
+            }))
+        });
+        for ts in &tipsets {
+            assert!(!cs.tipset_events_indexed(ts.key()).unwrap());
+            cs.index_tipset_events(ts, &state_root, &empty_amt).unwrap();
+            assert!(cs.tipset_events_indexed(ts.key()).unwrap());
+            assert_eq!(cs.tipset_events(ts.key(), None).unwrap(), Some(vec![]));
+        }
+
+        assert_eq!(prune_events(db.as_ref(), 2).unwrap(), 2);
+        assert_eq!(cs.tipset_events(tipsets[0].key(), None).unwrap(), None);
+        assert_eq!(cs.tipset_events(tipsets[1].key(), None).unwrap(), None);
+        assert_eq!(
+            cs.tipset_events(tipsets[2].key(), None).unwrap(),
+            Some(vec![])
+        );
+        assert_eq!(prune_events(db.as_ref(), 2).unwrap(), 0);
+    }
 }
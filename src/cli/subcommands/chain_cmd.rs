@@ -54,6 +54,10 @@ pub enum ChainCommands {
         #[arg(short, long, aliases = ["yes", "no-confirm"], short_alias = 'y')]
         force: bool,
     },
+
+    /// Start a database garbage collection run immediately. Use `forest-cli db gc status` to
+    /// follow its progress
+    Prune,
 }
 
 impl ChainCommands {
@@ -110,6 +114,11 @@ impl ChainCommands {
                 .await?;
                 Ok(())
             }
+            Self::Prune => {
+                ChainPrune::call(&client, ()).await?;
+                println!("Garbage collection started");
+                Ok(())
+            }
         }
     }
 }
@@ -0,0 +1,50 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use crate::rpc::{self, prelude::*};
+use clap::Subcommand;
+
+use super::print_pretty_lotus_json;
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommands {
+    /// Inspect or control database garbage collection
+    #[command(subcommand)]
+    Gc(GcCommands),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GcCommands {
+    /// Start a garbage collection run immediately
+    Start,
+    /// Print the progress of the current, or last, garbage collection run
+    Status,
+    /// Cancel the current garbage collection run
+    Cancel,
+}
+
+impl DbCommands {
+    pub async fn run(self, client: rpc::Client) -> anyhow::Result<()> {
+        match self {
+            Self::Gc(cmd) => cmd.run(client).await,
+        }
+    }
+}
+
+impl GcCommands {
+    pub async fn run(self, client: rpc::Client) -> anyhow::Result<()> {
+        match self {
+            Self::Start => {
+                ChainPrune::call(&client, ()).await?;
+                println!("Garbage collection started");
+                Ok(())
+            }
+            Self::Status => print_pretty_lotus_json(ChainPruneStatus::call(&client, ()).await?),
+            Self::Cancel => {
+                ChainPruneCancel::call(&client, ()).await?;
+                println!("Garbage collection cancelled");
+                Ok(())
+            }
+        }
+    }
+}
@@ -9,6 +9,7 @@
 mod auth_cmd;
 mod chain_cmd;
 mod config_cmd;
+mod db_cmd;
 mod info_cmd;
 mod mpool_cmd;
 mod net_cmd;
@@ -29,7 +30,7 @@ use tracing::error;
 
 pub(super) use self::{
     auth_cmd::AuthCommands, chain_cmd::ChainCommands, config_cmd::ConfigCommands,
-    mpool_cmd::MpoolCommands, net_cmd::NetCommands, send_cmd::SendCommand,
+    db_cmd::DbCommands, mpool_cmd::MpoolCommands, net_cmd::NetCommands, send_cmd::SendCommand,
     shutdown_cmd::ShutdownCommand, snapshot_cmd::SnapshotCommands, state_cmd::StateCommands,
     sync_cmd::SyncCommands,
 };
@@ -82,6 +83,10 @@ pub enum Subcommand {
     #[command(subcommand)]
     Snapshot(SnapshotCommands),
 
+    /// Manage the node database
+    #[command(subcommand)]
+    Db(DbCommands),
+
     /// Send funds between accounts
     Send(SendCommand),
 
@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use crate::blocks::Tipset;
-use crate::chain::block_messages;
+use crate::chain::{block_messages, HeadChange};
 use crate::cli_shared::snapshot;
 use crate::db::car::forest::FOREST_CAR_FILE_EXTENSION;
 use crate::db::car::{ForestCar, ManyCar};
 use crate::message::SignedMessage;
 use crate::networks::Height;
+use crate::rpc::chain::{impl_chain_get_path, PathChange};
 use crate::rpc::eth::{self, eth_tx_from_signed_eth_message};
+use crate::shim::clock::ChainEpoch;
 use crate::state_manager::StateManager;
 use crate::utils::db::car_stream::CarStream;
 use crate::utils::io::EitherMmapOrRandomAccessFile;
@@ -19,12 +21,14 @@ use futures::TryStreamExt;
 use std::ffi::OsStr;
 use std::fs;
 use std::io;
+use std::sync::Arc;
 use std::{
     path::{Path, PathBuf},
     time,
 };
 use tokio::io::AsyncWriteExt;
-use tracing::{debug, info};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
 use url::Url;
 use walkdir::WalkDir;
 
@@ -168,33 +172,155 @@ pub fn populate_eth_mappings<DB>(
 where
     DB: fvm_ipld_blockstore::Blockstore,
 {
-    let mut delegated_messages = vec![];
-
     info!("Populating column EthMappings");
 
+    // Hygge is the start of Ethereum support in the FVM (through the FEVM actor).
+    // Before this height, no notion of an Ethereum-like API existed.
+    index_eth_mappings(
+        state_manager,
+        head_ts,
+        state_manager.chain_config().epoch(Height::Hygge),
+    )?;
+
+    Ok(())
+}
+
+/// Indexes the tipsets from `head_ts` down to epoch `from` (or Hygge, if later) into the
+/// Ethereum mappings column, and extends the ranges covered by the index accordingly. Tipsets
+/// whose messages cannot be loaded are skipped and left out of the covered ranges.
+/// When several delegated messages share the same hash, the most recent one is kept.
+///
+/// Returns the number of indexed tipsets.
+pub fn index_eth_mappings<DB>(
+    state_manager: &StateManager<DB>,
+    head_ts: &Tipset,
+    from: ChainEpoch,
+) -> anyhow::Result<usize>
+where
+    DB: fvm_ipld_blockstore::Blockstore,
+{
+    let from = from.max(state_manager.chain_config().epoch(Height::Hygge));
+    let mut delegated_messages = vec![];
+    // Lowest epoch reached by the walk, and the epochs of the tipsets that failed to load.
+    let mut lowest = None;
+    let mut failed = vec![];
+    let mut indexed = 0;
+
     for ts in head_ts
         .clone()
         .chain(&state_manager.chain_store().blockstore())
     {
-        // Hygge is the start of Ethereum support in the FVM (through the FEVM actor).
-        // Before this height, no notion of an Ethereum-like API existed.
-        if ts.epoch() < state_manager.chain_config().epoch(Height::Hygge) {
+        if ts.epoch() < from {
+            // The epochs in between are null rounds.
+            lowest = Some(from);
             break;
         }
+        lowest = Some(ts.epoch());
+        let mut loaded = true;
         for bh in ts.block_headers() {
-            if let Ok((_, secp_cids)) = block_messages(&state_manager.blockstore(), bh) {
-                let mut messages = secp_cids
-                    .into_iter()
-                    .filter(|msg| msg.is_delegated())
-                    .collect();
-                delegated_messages.append(&mut messages);
+            match block_messages(&state_manager.blockstore(), bh) {
+                Ok((_, secp_cids)) => delegated_messages
+                    .extend(secp_cids.into_iter().filter(|msg| msg.is_delegated())),
+                Err(_) => loaded = false,
             }
         }
         state_manager.chain_store().put_tipset_key(ts.key())?;
+        if loaded {
+            indexed += 1;
+        } else {
+            failed.push(ts.epoch());
+        }
     }
     process_signed_messages(state_manager, &delegated_messages)?;
 
-    Ok(())
+    for (lowest, highest) in covered_ranges(head_ts.epoch(), lowest, &failed) {
+        state_manager
+            .chain_store()
+            .extend_eth_mappings_range(lowest, highest)?;
+    }
+
+    Ok(indexed)
+}
+
+/// Splits the epochs from `lowest` up to `highest` into the ranges that do not contain any of
+/// the `failed` epochs, given in descending order.
+fn covered_ranges(
+    highest: ChainEpoch,
+    lowest: Option<ChainEpoch>,
+    failed: &[ChainEpoch],
+) -> Vec<(ChainEpoch, ChainEpoch)> {
+    let Some(lowest) = lowest else {
+        return vec![];
+    };
+    let mut ranges = vec![];
+    let mut highest = highest;
+    for &epoch in failed {
+        if epoch < highest {
+            ranges.push((epoch + 1, highest));
+        }
+        highest = epoch - 1;
+    }
+    if lowest <= highest {
+        ranges.push((lowest, highest));
+    }
+    ranges
+}
+
+/// Keeps the Ethereum mappings up to date by indexing the tipsets as they are applied to the
+/// head of the chain. Every tipset applied since the previous head is indexed, so that no tipset
+/// is missed when the head moves by more than one epoch at a time or switches to another fork.
+/// The first head is indexed down to the highest indexed epoch.
+pub async fn index_eth_mappings_loop<DB>(state_manager: Arc<StateManager<DB>>) -> anyhow::Result<()>
+where
+    DB: fvm_ipld_blockstore::Blockstore + Send + Sync + 'static,
+{
+    if let Some(&(lowest, highest)) = state_manager.chain_store().eth_mappings_ranges()?.last() {
+        crate::metrics::ETH_MAPPINGS_LOWEST_EPOCH.set(lowest);
+        crate::metrics::ETH_MAPPINGS_HIGHEST_EPOCH.set(highest);
+    }
+
+    let mut head_changes = state_manager.chain_store().publisher().subscribe();
+    let mut previous_head: Option<Arc<Tipset>> = None;
+    loop {
+        let head = match head_changes.recv().await {
+            Ok(HeadChange::Apply(ts)) => ts,
+            // Missed heads are covered by the path from the previous head to the next one.
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return Ok(()),
+        };
+        let state_manager = state_manager.clone();
+        let epoch = head.epoch();
+        let previous = previous_head.replace(head.clone());
+        let result = tokio::task::spawn_blocking(move || {
+            let chain_store = state_manager.chain_store();
+            let from = match previous {
+                Some(previous) => {
+                    // The applied tipsets are the ones of the new head chain above the common
+                    // ancestor, which may be below the highest indexed epoch after a re-org.
+                    let path = impl_chain_get_path(chain_store, previous.key(), head.key())?;
+                    let Some(PathChange::Apply(lowest)) = path
+                        .into_iter()
+                        .find(|change| matches!(change, PathChange::Apply(_)))
+                    else {
+                        return Ok(0);
+                    };
+                    let ancestor = chain_store
+                        .chain_index
+                        .load_required_tipset(lowest.parents())?;
+                    ancestor.epoch() + 1
+                }
+                None => match chain_store.eth_mappings_ranges()?.last() {
+                    Some(&(_, highest)) if highest < head.epoch() => highest + 1,
+                    _ => head.epoch(),
+                },
+            };
+            index_eth_mappings(&state_manager, &head, from)
+        })
+        .await?;
+        if let Err(e) = result {
+            warn!("Failed to index Ethereum mappings at epoch {epoch}: {e}");
+        }
+    }
 }
 
 /// Filter [`SignedMessage`]'s to keep only delegated ones and the most recent ones, then write them to the chain store.
@@ -256,6 +382,15 @@ fn filter_lowest_index(values: Vec<(eth::Hash, Cid, usize)>) -> Vec<(eth::Hash,
 mod test {
     use super::*;
 
+    #[test]
+    fn covered_ranges_skip_failed_epochs() {
+        assert_eq!(covered_ranges(10, None, &[]), vec![]);
+        assert_eq!(covered_ranges(10, Some(5), &[]), vec![(5, 10)]);
+        assert_eq!(covered_ranges(10, Some(5), &[8]), vec![(9, 10), (5, 7)]);
+        assert_eq!(covered_ranges(10, Some(5), &[10, 5]), vec![(6, 9)]);
+        assert_eq!(covered_ranges(10, Some(11), &[]), vec![]);
+    }
+
     #[tokio::test]
     async fn import_snapshot_from_file_valid() {
         import_snapshot_from_file("test-snapshots/chain4.car")
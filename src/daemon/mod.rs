@@ -16,7 +16,8 @@ use crate::cli_shared::{
 };
 
 use crate::daemon::db_util::{
-    import_chain_as_forest_car, load_all_forest_cars, populate_eth_mappings,
+    import_chain_as_forest_car, index_eth_mappings_loop, load_all_forest_cars,
+    populate_eth_mappings,
 };
 use crate::db::car::ManyCar;
 use crate::db::db_engine::{db_root, open_db};
@@ -238,11 +239,12 @@ pub(super) async fn start(
         Arc::clone(&db),
         db.writer().clone(),
         db.writer().clone(),
+        db.writer().clone(),
         chain_config.clone(),
         genesis_header.clone(),
     )?);
 
-    if !opts.no_gc {
+    let gc_handle = if !opts.no_gc {
         let mut db_garbage_collector = {
             let chain_store = chain_store.clone();
             let depth = cmp::max(
@@ -259,8 +261,12 @@ pub(super) async fn start(
                 Duration::from_secs(chain_config.block_delay_secs as u64),
             )
         };
+        let gc_handle = db_garbage_collector.handle();
         services.spawn(async move { db_garbage_collector.gc_loop(GC_INTERVAL).await });
-    }
+        Some(gc_handle)
+    } else {
+        None
+    };
 
     let publisher = chain_store.publisher();
 
@@ -345,6 +351,7 @@ pub(super) async fn start(
     let bad_blocks = chain_muxer.bad_blocks_cloned();
     let sync_state = chain_muxer.sync_state_cloned();
     services.spawn(async { Err(anyhow::anyhow!("{}", chain_muxer.await)) });
+    services.spawn(index_eth_mappings_loop(Arc::clone(&state_manager)));
 
     if config.client.enable_health_check {
         let forest_state = crate::health::ForestState {
@@ -394,6 +401,8 @@ pub(super) async fn start(
                     chain_store: rpc_chain_store,
                     shutdown: shutdown_send,
                     tipset_send: tipset_sender,
+                    eth_filters: Default::default(),
+                    gc: gc_handle,
                 },
                 rpc_address,
             )
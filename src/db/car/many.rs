@@ -9,7 +9,7 @@
 //! A single z-frame cache is shared between all read-only stores.
 
 use super::{AnyCar, ZstdFrameCache};
-use crate::db::{EthMappingsStore, MemoryDB, SettingsStore};
+use crate::db::{EthMappingsStore, EventsStore, MemoryDB, SettingsStore};
 use crate::libp2p_bitswap::BitswapStoreReadWrite;
 use crate::rpc::eth;
 use crate::shim::clock::ChainEpoch;
@@ -219,6 +219,24 @@ impl<WriterT: EthMappingsStore> EthMappingsStore for ManyCar<WriterT> {
     }
 }
 
+impl<WriterT: EventsStore> EventsStore for ManyCar<WriterT> {
+    fn read_bin(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        EventsStore::read_bin(self.writer(), key)
+    }
+
+    fn write_bin(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        EventsStore::write_bin(self.writer(), key, value)
+    }
+
+    fn exists(&self, key: &[u8]) -> anyhow::Result<bool> {
+        EventsStore::exists(self.writer(), key)
+    }
+
+    fn remove(&self, key: &[u8]) -> anyhow::Result<()> {
+        EventsStore::remove(self.writer(), key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::AnyCar;
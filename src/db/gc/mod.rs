@@ -32,7 +32,8 @@
 //! 2. Wait at least `chain finality` blocks.
 //! 3. Traverse reachable blocks starting at the current heaviest tipset and remove those from the
 //! marked set, leaving only unreachable entries that are older than `chain finality`.
-//! 4. Sweep, removing all the remaining marked entries from the database.
+//! 4. Sweep, removing all the remaining marked entries from the database, along with the indexed
+//! actor events of the tipsets older than `depth`.
 //!
 //! ## Correctness
 //! This algorithm considers all the blocks that are visited during the `snapshot export` task
@@ -64,6 +65,11 @@
 //! 3. Then, the `sweep` step happens.
 //! 4. Finally, the algorithm waits for a configured amount of time to initiate the next run.
 //!
+//! A run can also be requested on demand through a [`GcHandle`], which skips the wait for the
+//! configured interval. The `filter` step still waits for `chain finality` after the `mark` step.
+//! The handle also reports the progress of the current run, and can cancel it before the `sweep`
+//! step.
+//!
 //! ## Performance
 //! The time complexity of mark and sweep steps is `O(n)`. The filter step is currently utilizing a
 //! depth-first search algorithm, with `O(V+E)` complexity, where V is the number of vertices and E
@@ -72,20 +78,161 @@
 use crate::blocks::Tipset;
 use crate::chain::ChainEpochDelta;
 
-use crate::db::{truncated_hash, GarbageCollectable, SettingsStore};
+use crate::chain::prune_events;
+use crate::db::{truncated_hash, EventsStore, GarbageCollectable, SettingsStore};
 use crate::ipld::stream_graph;
+use crate::lotus_json::lotus_json_with_self;
 use crate::shim::clock::ChainEpoch;
 use ahash::{HashSet, HashSetExt};
+use anyhow::bail;
 use futures::StreamExt;
 use fvm_ipld_blockstore::Blockstore;
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Notify;
 use tokio::time;
 use tracing::info;
 
 const SETTINGS_KEY: &str = "LAST_GC_RUN";
 
+/// Phase of a garbage collection run.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, strum::Display,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum GcPhase {
+    /// No run in progress.
+    #[default]
+    Idle,
+    /// Collecting all the database keys.
+    Mark,
+    /// Waiting for the marked keys to become older than the GC depth.
+    Wait,
+    /// Removing reachable keys from the marked set.
+    Filter,
+    /// Removing the remaining marked keys from the database.
+    Sweep,
+}
+
+/// Progress of the current, or last, garbage collection run.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct GcStatus {
+    pub phase: GcPhase,
+    /// Epoch at which the keys were marked.
+    pub epoch_marked: ChainEpoch,
+    /// Number of keys collected by the `mark` step.
+    pub records_marked: usize,
+    /// Number of keys removed by the `sweep` step.
+    pub records_removed: usize,
+}
+lotus_json_with_self!(GcStatus);
+
+#[derive(Default)]
+struct GcControl {
+    status: RwLock<GcStatus>,
+    requested: AtomicBool,
+    cancelled: AtomicBool,
+    wake: Notify,
+}
+
+/// Shared handle used to observe and control a running [`MarkAndSweep`] instance.
+#[derive(Clone, Default)]
+pub struct GcHandle(Arc<GcControl>);
+
+impl GcHandle {
+    pub fn status(&self) -> GcStatus {
+        self.0.status.read().clone()
+    }
+
+    /// Requests a new run to start immediately.
+    pub fn start(&self) -> anyhow::Result<()> {
+        // The phase is checked and the flag set under the status lock, so that a scheduled run
+        // can't start in between and leave the request to the next run.
+        let status = self.0.status.write();
+        if status.phase != GcPhase::Idle {
+            bail!("a GC run is already in progress ({})", status.phase);
+        }
+        self.0.requested.store(true, Ordering::SeqCst);
+        self.0.wake.notify_one();
+        Ok(())
+    }
+
+    /// Requests the current run to be cancelled. Runs that reached the `sweep` step cannot be
+    /// cancelled.
+    pub fn cancel(&self) -> anyhow::Result<()> {
+        // The phase is checked and the flag set under the status lock, so that the run can't
+        // enter the `sweep` step in between and ignore the request.
+        let status = self.0.status.write();
+        match status.phase {
+            GcPhase::Idle => bail!("no GC run is in progress"),
+            GcPhase::Sweep => bail!("a GC run cannot be cancelled during the sweep step"),
+            _ => {}
+        }
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.wake.notify_one();
+        Ok(())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut GcStatus)) {
+        f(&mut self.0.status.write())
+    }
+
+    fn take_requested(&self) -> bool {
+        self.0.requested.swap(false, Ordering::SeqCst)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Enters the `mark` step of a new run, which serves any pending request. A cancellation
+    /// left over from a previous run doesn't apply to this one.
+    fn begin_run(&self, epoch_marked: ChainEpoch) {
+        let mut status = self.0.status.write();
+        self.0.requested.store(false, Ordering::SeqCst);
+        self.0.cancelled.store(false, Ordering::SeqCst);
+        *status = GcStatus {
+            phase: GcPhase::Mark,
+            epoch_marked,
+            ..Default::default()
+        };
+    }
+
+    /// Enters the `sweep` step, unless the run has been cancelled.
+    fn begin_sweep(&self) -> bool {
+        let mut status = self.0.status.write();
+        if self.is_cancelled() {
+            return false;
+        }
+        status.phase = GcPhase::Sweep;
+        true
+    }
+
+    /// Consumes a cancellation request, if any, moving back to the `idle` phase.
+    fn take_cancelled(&self) -> bool {
+        let mut status = self.0.status.write();
+        let cancelled = self.0.cancelled.swap(false, Ordering::SeqCst);
+        if cancelled {
+            status.phase = GcPhase::Idle;
+        }
+        cancelled
+    }
+
+    // Sleeps for `duration`, or until a run is requested or cancelled.
+    async fn sleep(&self, duration: Duration) {
+        tokio::select! {
+            _ = time::sleep(duration) => {}
+            _ = self.0.wake.notified() => {}
+        }
+    }
+}
+
 /// [`MarkAndSweep`] is a simple garbage collector implementation that traverses all the database
 /// keys writing them to a [`HashSet`], then filters out those that need to be kept and schedules
 /// the rest for removal.
@@ -99,9 +246,12 @@ pub struct MarkAndSweep<DB> {
     epoch_marked: ChainEpoch,
     depth: ChainEpochDelta,
     block_time: Duration,
+    handle: GcHandle,
 }
 
-impl<DB: Blockstore + SettingsStore + GarbageCollectable + Sync + Send + 'static> MarkAndSweep<DB> {
+impl<DB: Blockstore + SettingsStore + EventsStore + GarbageCollectable + Sync + Send + 'static>
+    MarkAndSweep<DB>
+{
     /// Creates a new mark-and-sweep garbage collector.
     ///
     /// # Arguments
@@ -123,8 +273,15 @@ impl<DB: Blockstore + SettingsStore + GarbageCollectable + Sync + Send + 'static
             marked: HashSet::new(),
             epoch_marked: 0,
             block_time,
+            handle: GcHandle::default(),
         }
     }
+
+    /// Returns a handle to observe and control this garbage collector.
+    pub fn handle(&self) -> GcHandle {
+        self.handle.clone()
+    }
+
     // Populate the initial set with all the available database keys.
     fn populate(&mut self) -> anyhow::Result<()> {
         self.marked = self.db.get_keys()?;
@@ -142,6 +299,9 @@ impl<DB: Blockstore + SettingsStore + GarbageCollectable + Sync + Send + 'static
         );
 
         while let Some(block) = stream.next().await {
+            if self.handle.is_cancelled() {
+                break;
+            }
             let block = block?;
             self.marked.remove(&truncated_hash(block.cid.hash()));
         }
@@ -149,6 +309,18 @@ impl<DB: Blockstore + SettingsStore + GarbageCollectable + Sync + Send + 'static
         anyhow::Ok(())
     }
 
+    /// Runs the `mark` and `filter` phases back to back against the current heaviest tipset and
+    /// returns the keys that `sweep` would remove, leaving the database untouched.
+    ///
+    /// NOTE: There is no `wait` phase, so this is only accurate for a database that is not being
+    /// written to, e.g. one of a stopped node.
+    pub async fn unreachable_keys(&mut self) -> anyhow::Result<HashSet<u32>> {
+        self.populate()?;
+        let tipset = (self.get_heaviest_tipset)();
+        self.filter(tipset, self.depth).await?;
+        Ok(mem::take(&mut self.marked))
+    }
+
     // Remove marked keys from the database.
     fn sweep(&mut self) -> anyhow::Result<()> {
         let marked = mem::take(&mut self.marked);
@@ -170,14 +342,13 @@ impl<DB: Blockstore + SettingsStore + GarbageCollectable + Sync + Send + 'static
     }
 
     fn update_last_gc_run(&self, epoch: ChainEpoch) -> anyhow::Result<()> {
-        self.db
-            .write_bin(SETTINGS_KEY, epoch.to_string().as_bytes())
+        SettingsStore::write_bin(self.db.as_ref(), SETTINGS_KEY, epoch.to_string().as_bytes())
     }
 
     // Unfortunately there seems to be no good way of decoding a slice into i64 without array init
     // and manipulation, therefore a string representation is used.
     fn fetch_last_gc_run(&self) -> anyhow::Result<ChainEpoch> {
-        let bytes = self.db.read_bin(SETTINGS_KEY)?;
+        let bytes = SettingsStore::read_bin(self.db.as_ref(), SETTINGS_KEY)?;
         let epoch = match bytes {
             Some(bytes) => ChainEpoch::from_str_radix(&String::from_utf8(bytes)?, 10)?,
             None => 0,
@@ -189,41 +360,75 @@ impl<DB: Blockstore + SettingsStore + GarbageCollectable + Sync + Send + 'static
     // next step.
     async fn gc_workflow(&mut self, interval: Duration) -> anyhow::Result<()> {
         let depth = self.depth;
-        let tipset = (self.get_heaviest_tipset)();
-        let current_epoch = tipset.epoch();
-        let last_gc_run = self.fetch_last_gc_run()?;
-        // Don't run the GC if there aren't enough state-roots yet or if we're too close to the last
-        // GC run. Sleep and yield to the main loop in order to refresh the heaviest tipset value.
-        if depth > current_epoch - last_gc_run {
-            time::sleep(interval).await;
+
+        if self.handle.take_cancelled() {
+            if !self.marked.is_empty() {
+                info!("GC run cancelled");
+                self.marked = HashSet::new();
+            }
             return anyhow::Ok(());
         }
 
         // This signifies a new run.
         if self.marked.is_empty() {
-            // Make sure we don't run the GC too often.
-            time::sleep(interval).await;
-
+            if !self.handle.take_requested() {
+                let current_epoch = (self.get_heaviest_tipset)().epoch();
+                let last_gc_run = self.fetch_last_gc_run()?;
+                // Don't run the GC if there aren't enough state-roots yet or if we're too close to
+                // the last GC run. Sleep and yield to the main loop in order to refresh the
+                // heaviest tipset value.
+                if depth > current_epoch - last_gc_run {
+                    self.handle.sleep(interval).await;
+                    return anyhow::Ok(());
+                }
+
+                // Make sure we don't run the GC too often.
+                self.handle.sleep(interval).await;
+            }
+
+            let current_epoch = (self.get_heaviest_tipset)().epoch();
+            self.handle.begin_run(current_epoch);
             info!("populate keys for GC");
             self.populate()?;
             self.epoch_marked = current_epoch;
+            let records_marked = self.marked.len();
+            self.handle.update(|status| {
+                status.phase = GcPhase::Wait;
+                status.records_marked = records_marked;
+            });
         }
 
+        let tipset = (self.get_heaviest_tipset)();
+        let current_epoch = tipset.epoch();
         let epochs_since_marked = current_epoch - self.epoch_marked;
         // Don't proceed with next steps until we advance at least `depth` epochs. Sleep and yield
         // to the main loop in order to refresh the heaviest tipset value.
         if epochs_since_marked < depth {
-            time::sleep(self.block_time * (depth - epochs_since_marked) as u32).await;
+            self.handle
+                .sleep(self.block_time * (depth - epochs_since_marked) as u32)
+                .await;
             return anyhow::Ok(());
         }
 
         info!("filter keys for GC");
+        self.handle.update(|status| status.phase = GcPhase::Filter);
         self.filter(tipset, depth).await?;
+        if !self.handle.begin_sweep() {
+            // Cancelled, handled at the start of the next iteration.
+            return anyhow::Ok(());
+        }
 
         info!("GC sweep");
+        let records_removed = self.marked.len();
         self.sweep()?;
+        let pruned = prune_events(self.db.as_ref(), current_epoch - depth)?;
+        info!("pruned indexed events of {pruned} tipsets");
 
         self.update_last_gc_run(current_epoch)?;
+        self.handle.update(|status| {
+            status.phase = GcPhase::Idle;
+            status.records_removed = records_removed;
+        });
 
         anyhow::Ok(())
     }
@@ -234,6 +439,8 @@ mod test {
     use crate::chain::{ChainEpochDelta, ChainStore};
 
     use crate::db::{GarbageCollectable, MarkAndSweep, MemoryDB};
+
+    use super::{GcHandle, GcPhase};
     use crate::message_pool::test_provider::{mock_block, mock_block_with_parents};
     use crate::networks::ChainConfig;
 
@@ -283,6 +490,7 @@ mod test {
                     db.clone(),
                     db.clone(),
                     db.clone(),
+                    db.clone(),
                     Arc::new(config),
                     gen_block,
                 )
@@ -462,4 +670,132 @@ mod test {
             current_epoch + 1 + depth * 2
         );
     }
+
+    #[tokio::test]
+    async fn requested_run_skips_schedule() {
+        let depth = 5;
+        let unreachable_nodes = 3;
+
+        let tester = GCTester::new();
+        let mut gc = MarkAndSweep::new(
+            tester.db.clone(),
+            tester.get_heaviest_tipset_fn(),
+            depth,
+            ZERO_DURATION,
+        );
+        let handle = gc.handle();
+
+        // There aren't enough epochs for a scheduled run.
+        gc.gc_workflow(ZERO_DURATION).await.unwrap();
+        assert!(gc.marked.is_empty());
+        assert_eq!(handle.status().phase, GcPhase::Idle);
+
+        // A requested run marks right away.
+        tester.insert_unreachable(unreachable_nodes);
+        handle.start().unwrap();
+        gc.gc_workflow(ZERO_DURATION).await.unwrap();
+        let status = handle.status();
+        assert_eq!(status.phase, GcPhase::Wait);
+        assert_eq!(status.records_marked, 1 + unreachable_nodes as usize);
+        assert!(handle.start().is_err());
+
+        // And completes once the marked records are old enough.
+        tester.run_epochs(depth);
+        gc.gc_workflow(ZERO_DURATION).await.unwrap();
+        let status = handle.status();
+        assert_eq!(status.phase, GcPhase::Idle);
+        assert_eq!(status.records_removed, unreachable_nodes as usize);
+        assert_eq!(tester.db.get_keys().unwrap().len() as i64, 1 + depth);
+    }
+
+    #[tokio::test]
+    async fn cancelled_run_keeps_data() {
+        let depth = 5;
+        let unreachable_nodes = 3;
+
+        let tester = GCTester::new();
+        let mut gc = MarkAndSweep::new(
+            tester.db.clone(),
+            tester.get_heaviest_tipset_fn(),
+            depth,
+            ZERO_DURATION,
+        );
+        let handle = gc.handle();
+        assert!(handle.cancel().is_err());
+
+        tester.insert_unreachable(unreachable_nodes);
+        handle.start().unwrap();
+        gc.gc_workflow(ZERO_DURATION).await.unwrap();
+        assert_eq!(handle.status().phase, GcPhase::Wait);
+
+        handle.cancel().unwrap();
+        tester.run_epochs(depth);
+        gc.gc_workflow(ZERO_DURATION).await.unwrap();
+        assert!(gc.marked.is_empty());
+        assert_eq!(handle.status().phase, GcPhase::Idle);
+        assert_eq!(
+            tester.db.get_keys().unwrap().len() as i64,
+            1 + depth + unreachable_nodes
+        );
+    }
+
+    #[test]
+    fn handle_flags_follow_the_phase() {
+        let handle = GcHandle::default();
+        assert!(handle.cancel().is_err());
+
+        // A request made while idle is served by the next run, whether it was scheduled or not.
+        handle.start().unwrap();
+        handle.begin_run(0);
+        assert!(!handle.take_requested());
+        assert!(handle.start().is_err());
+
+        // A cancelled run doesn't enter the sweep step.
+        handle.cancel().unwrap();
+        assert!(!handle.begin_sweep());
+        assert!(handle.take_cancelled());
+        assert_eq!(handle.status().phase, GcPhase::Idle);
+
+        // A run that entered the sweep step can't be cancelled.
+        handle.begin_run(0);
+        assert!(handle.begin_sweep());
+        assert!(handle.cancel().is_err());
+        assert!(!handle.take_cancelled());
+    }
+
+    #[test]
+    fn stale_cancellation_does_not_apply_to_the_next_run() {
+        let handle = GcHandle::default();
+        handle.begin_run(0);
+        handle.cancel().unwrap();
+        // The run moves on before noticing the cancellation.
+        handle.update(|status| status.phase = GcPhase::Idle);
+
+        handle.begin_run(0);
+        assert!(handle.begin_sweep());
+    }
+
+    #[tokio::test]
+    async fn unreachable_keys_leave_data() {
+        let depth = 5;
+        let unreachable_nodes = 3;
+
+        let tester = GCTester::new();
+        tester.run_epochs(depth);
+        tester.insert_unreachable(unreachable_nodes);
+        let mut gc = MarkAndSweep::new(
+            tester.db.clone(),
+            tester.get_heaviest_tipset_fn(),
+            depth,
+            ZERO_DURATION,
+        );
+
+        let keys = gc.unreachable_keys().await.unwrap();
+        assert_eq!(keys.len() as i64, unreachable_nodes);
+        assert!(gc.marked.is_empty());
+        assert_eq!(
+            tester.db.get_keys().unwrap().len() as i64,
+            1 + depth + unreachable_nodes
+        );
+    }
 }
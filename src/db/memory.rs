@@ -10,13 +10,14 @@ use fvm_ipld_blockstore::Blockstore;
 use itertools::Itertools;
 use parking_lot::RwLock;
 
-use super::{EthMappingsStore, SettingsStore};
+use super::{EthMappingsStore, EventsStore, SettingsStore};
 
 #[derive(Debug, Default)]
 pub struct MemoryDB {
     blockchain_db: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
     settings_db: RwLock<HashMap<String, Vec<u8>>>,
     eth_mappings_db: RwLock<HashMap<eth::Hash, Vec<u8>>>,
+    events_db: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
 }
 
 impl GarbageCollectable for MemoryDB {
@@ -80,6 +81,26 @@ impl EthMappingsStore for MemoryDB {
     }
 }
 
+impl EventsStore for MemoryDB {
+    fn read_bin(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.events_db.read().get(key).cloned())
+    }
+
+    fn write_bin(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.events_db.write().insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn exists(&self, key: &[u8]) -> anyhow::Result<bool> {
+        Ok(self.events_db.read().contains_key(key))
+    }
+
+    fn remove(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.events_db.write().remove(key);
+        Ok(())
+    }
+}
+
 impl Blockstore for MemoryDB {
     fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
         Ok(self.blockchain_db.read().get(&k.to_bytes()).cloned())
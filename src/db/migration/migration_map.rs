@@ -9,6 +9,7 @@ use std::{
 
 use crate::db::migration::v0_16_0::Migration0_15_2_0_16_0;
 use crate::db::migration::v0_19_0::Migration0_18_0_0_19_0;
+use crate::db::migration::v0_19_1::Migration0_19_0_0_19_1;
 use crate::Config;
 use anyhow::bail;
 use anyhow::Context as _;
@@ -80,6 +81,7 @@ create_migrations!(
     "0.12.1" -> "0.13.0" @ Migration0_12_1_0_13_0,
     "0.15.2" -> "0.16.0" @ Migration0_15_2_0_16_0,
     "0.18.0" -> "0.19.0" @ Migration0_18_0_0_19_0,
+    "0.19.0" -> "0.19.1" @ Migration0_19_0_0_19_1,
 );
 
 pub struct Migration {
@@ -107,9 +109,12 @@ impl Migration {
         );
         std::fs::rename(migrated_db, new_db)?;
 
+        // A migration applied in place returns the old database itself, which was just renamed.
         let old_db = chain_data_path.join(format!("{}", self.from));
-        debug!("Deleting database {}", old_db.display());
-        std::fs::remove_dir_all(old_db)?;
+        if old_db.exists() {
+            debug!("Deleting database {}", old_db.display());
+            std::fs::remove_dir_all(old_db)?;
+        }
 
         info!("Database migration complete");
         Ok(())
@@ -6,6 +6,7 @@ mod migration_map;
 mod v0_12_1;
 mod v0_16_0;
 mod v0_19_0;
+mod v0_19_1;
 mod void_migration;
 
 pub use db_migration::DbMigration;
@@ -166,6 +166,7 @@ async fn create_state_manager_and_populate(config: Config, db_name: String) -> a
         Arc::clone(&db),
         db.writer().clone(),
         db.writer().clone(),
+        db.writer().clone(),
         chain_config.clone(),
         genesis_header.clone(),
     )?);
@@ -0,0 +1,259 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Migration logic for 0.19.0 to 0.19.1 version.
+//! A new column in parity-db has been introduced to index the actor events emitted by the
+//! executed tipsets, as served by the Ethereum RPC API. The existing columns are left untouched,
+//! so the column is appended to the database in place.
+
+use crate::Config;
+use parity_db::{CompressionType, Db, Options};
+use semver::Version;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use super::migration_map::MigrationOperation;
+
+pub(super) struct Migration0_19_0_0_19_1 {
+    from: Version,
+}
+
+/// Migrates the database from version 0.19.0 to 0.19.1
+impl MigrationOperation for Migration0_19_0_0_19_1 {
+    fn new(from: Version, _to: Version) -> Self
+    where
+        Self: Sized,
+    {
+        Self { from }
+    }
+
+    fn pre_checks(&self, _chain_data_path: &Path) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn migrate(&self, chain_data_path: &Path, _config: &Config) -> anyhow::Result<PathBuf> {
+        // The column is added in place, the existing columns are left untouched and copying them
+        // would temporarily double the disk usage.
+        let db_path = chain_data_path.join(self.from.to_string());
+        let mut options = paritydb_0_19_0::ParityDb::to_options(db_path.clone());
+        // The column is only appended to databases that have exactly the 0.19.0 columns, so that
+        // an interrupted migration can be resumed.
+        let existing_columns = Options::load_metadata(&options.path)?
+            .map(|metadata| metadata.columns.len())
+            .unwrap_or_default();
+        if existing_columns == options.columns.len() {
+            info!(
+                "Adding column {} to {}",
+                paritydb_0_19_1::DbColumn::Events,
+                db_path.display()
+            );
+            Db::add_column(
+                &mut options,
+                paritydb_0_19_1::DbColumn::Events.column_options(CompressionType::Lz4),
+            )?;
+        }
+
+        Ok(db_path)
+    }
+
+    fn post_checks(&self, chain_data_path: &Path) -> anyhow::Result<()> {
+        let db_path = chain_data_path.join(self.from.to_string());
+        if let Some(metadata) = Options::load_metadata(&db_path)? {
+            let expected = paritydb_0_19_1::ParityDb::to_options(db_path.clone())
+                .columns
+                .len();
+            anyhow::ensure!(
+                metadata.columns.len() == expected,
+                "migrated database {} has {} columns, expected {expected}",
+                db_path.display(),
+                metadata.columns.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Database settings from Forest `v0.19.0`
+mod paritydb_0_19_0 {
+    use parity_db::{CompressionType, Options};
+    use std::path::PathBuf;
+    use strum::{Display, EnumIter, IntoEnumIterator};
+
+    #[derive(Copy, Clone, Debug, PartialEq, EnumIter, Display)]
+    #[repr(u8)]
+    pub(super) enum DbColumn {
+        GraphDagCborBlake2b256,
+        GraphFull,
+        Settings,
+        EthMappings,
+    }
+
+    impl DbColumn {
+        fn create_column_options(compression: CompressionType) -> Vec<parity_db::ColumnOptions> {
+            DbColumn::iter()
+                .map(|col| {
+                    match col {
+                        DbColumn::GraphDagCborBlake2b256 => parity_db::ColumnOptions {
+                            preimage: true,
+                            compression,
+                            ..Default::default()
+                        },
+                        DbColumn::GraphFull => parity_db::ColumnOptions {
+                            preimage: true,
+                            // This is needed for key retrieval.
+                            btree_index: true,
+                            compression,
+                            ..Default::default()
+                        },
+                        DbColumn::Settings => parity_db::ColumnOptions {
+                            // explicitly disable preimage for settings column
+                            // othewise we are not able to overwrite entries
+                            preimage: false,
+                            // This is needed for key retrieval.
+                            btree_index: true,
+                            compression,
+                            ..Default::default()
+                        },
+                        DbColumn::EthMappings => parity_db::ColumnOptions {
+                            preimage: false,
+                            btree_index: false,
+                            compression,
+                            ..Default::default()
+                        },
+                    }
+                })
+                .collect()
+        }
+    }
+
+    pub(super) struct ParityDb;
+
+    impl ParityDb {
+        pub(super) fn to_options(path: PathBuf) -> Options {
+            Options {
+                path,
+                sync_wal: true,
+                sync_data: true,
+                stats: false,
+                salt: None,
+                columns: DbColumn::create_column_options(CompressionType::Lz4),
+                compression_threshold: [(0, 128)].into_iter().collect(),
+            }
+        }
+    }
+}
+
+/// Database settings from Forest `v0.19.1`
+mod paritydb_0_19_1 {
+    use parity_db::{CompressionType, Options};
+    use std::path::PathBuf;
+    use strum::{Display, EnumIter, IntoEnumIterator};
+
+    #[derive(Copy, Clone, Debug, PartialEq, EnumIter, Display)]
+    #[repr(u8)]
+    pub(super) enum DbColumn {
+        GraphDagCborBlake2b256,
+        GraphFull,
+        Settings,
+        EthMappings,
+        Events,
+    }
+
+    impl DbColumn {
+        pub(super) fn column_options(
+            self,
+            compression: CompressionType,
+        ) -> parity_db::ColumnOptions {
+            match self {
+                DbColumn::GraphDagCborBlake2b256 => parity_db::ColumnOptions {
+                    preimage: true,
+                    compression,
+                    ..Default::default()
+                },
+                DbColumn::GraphFull => parity_db::ColumnOptions {
+                    preimage: true,
+                    // This is needed for key retrieval.
+                    btree_index: true,
+                    compression,
+                    ..Default::default()
+                },
+                DbColumn::Settings => parity_db::ColumnOptions {
+                    // explicitly disable preimage for settings column
+                    // othewise we are not able to overwrite entries
+                    preimage: false,
+                    // This is needed for key retrieval.
+                    btree_index: true,
+                    compression,
+                    ..Default::default()
+                },
+                DbColumn::EthMappings | DbColumn::Events => parity_db::ColumnOptions {
+                    preimage: false,
+                    btree_index: false,
+                    compression,
+                    ..Default::default()
+                },
+            }
+        }
+    }
+
+    pub(super) struct ParityDb;
+
+    impl ParityDb {
+        pub(super) fn to_options(path: PathBuf) -> Options {
+            Options {
+                path,
+                sync_wal: true,
+                sync_data: true,
+                stats: false,
+                salt: None,
+                columns: DbColumn::iter()
+                    .map(|col| col.column_options(CompressionType::Lz4))
+                    .collect(),
+                compression_threshold: [(0, 128)].into_iter().collect(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::parity_db::ParityDb;
+    use crate::db::parity_db_config::ParityDbConfig;
+    use crate::db::{EventsStore, SettingsStore};
+
+    #[test]
+    fn test_migration_appends_events_column() {
+        let chain_data = tempfile::tempdir().unwrap();
+        let from = Version::new(0, 19, 0);
+        let to = Version::new(0, 19, 1);
+        {
+            let options =
+                paritydb_0_19_0::ParityDb::to_options(chain_data.path().join(from.to_string()));
+            let db = Db::open_or_create(&options).unwrap();
+            db.commit([(
+                paritydb_0_19_0::DbColumn::Settings as u8,
+                b"key",
+                Some(b"value".to_vec()),
+            )])
+            .unwrap();
+        }
+
+        let migration = Migration0_19_0_0_19_1::new(from.clone(), to);
+        let migrated = migration
+            .migrate(chain_data.path(), &Config::default())
+            .unwrap();
+        migration.post_checks(chain_data.path()).unwrap();
+        // The database is migrated in place, without a copy.
+        assert_eq!(migrated, chain_data.path().join(from.to_string()));
+        assert_eq!(std::fs::read_dir(chain_data.path()).unwrap().count(), 1);
+
+        let db = ParityDb::open(migrated, &ParityDbConfig::default()).unwrap();
+        assert_eq!(
+            SettingsStore::read_bin(&db, "key").unwrap(),
+            Some(b"value".to_vec())
+        );
+        EventsStore::write_bin(&db, b"event", b"data").unwrap();
+        assert!(EventsStore::exists(&db, b"event").unwrap());
+    }
+}
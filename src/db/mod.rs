@@ -7,7 +7,7 @@ pub mod parity_db;
 pub mod parity_db_config;
 
 mod gc;
-pub use gc::MarkAndSweep;
+pub use gc::{GcHandle, GcStatus, MarkAndSweep};
 pub use memory::MemoryDB;
 mod db_mode;
 pub mod migration;
@@ -25,6 +25,8 @@ pub mod setting_keys {
     pub const HEAD_KEY: &str = "head";
     /// Key used to store the memory pool configuration in the settings store.
     pub const MPOOL_CONFIG_KEY: &str = "/mpool/config";
+    /// Key used to store the epoch ranges covered by the Ethereum mappings index.
+    pub const ETH_MAPPINGS_RANGE_KEY: &str = "/eth_mappings/range";
 }
 
 /// Interface used to store and retrieve settings from the database.
@@ -140,6 +142,60 @@ impl<T: ?Sized + EthMappingsStore> EthMappingsStoreExt for T {
     }
 }
 
+/// Interface used to store and retrieve indexed actor events from the database.
+/// To store IPLD blocks, use the `BlockStore` trait.
+pub trait EventsStore {
+    /// Reads binary field from the `Events` store. This should be used for
+    /// non-serializable data. For serializable data, use [`EventsStoreExt::read_obj`].
+    fn read_bin(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Writes binary field to the `Events` store. This should be used for
+    /// non-serializable data. For serializable data, use [`EventsStoreExt::write_obj`].
+    fn write_bin(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()>;
+
+    /// Returns `Ok(true)` if key exists in store.
+    fn exists(&self, key: &[u8]) -> anyhow::Result<bool>;
+
+    /// Removes the given key from the `Events` store, if present.
+    fn remove(&self, key: &[u8]) -> anyhow::Result<()>;
+}
+
+impl<T: EventsStore> EventsStore for Arc<T> {
+    fn read_bin(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        EventsStore::read_bin(self.as_ref(), key)
+    }
+
+    fn write_bin(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        EventsStore::write_bin(self.as_ref(), key, value)
+    }
+
+    fn exists(&self, key: &[u8]) -> anyhow::Result<bool> {
+        EventsStore::exists(self.as_ref(), key)
+    }
+
+    fn remove(&self, key: &[u8]) -> anyhow::Result<()> {
+        EventsStore::remove(self.as_ref(), key)
+    }
+}
+
+pub trait EventsStoreExt {
+    fn read_obj<V: DeserializeOwned>(&self, key: &[u8]) -> anyhow::Result<Option<V>>;
+    fn write_obj<V: Serialize>(&self, key: &[u8], value: &V) -> anyhow::Result<()>;
+}
+
+impl<T: ?Sized + EventsStore> EventsStoreExt for T {
+    fn read_obj<V: DeserializeOwned>(&self, key: &[u8]) -> anyhow::Result<Option<V>> {
+        match self.read_bin(key)? {
+            Some(bytes) => Ok(Some(fvm_ipld_encoding::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn write_obj<V: Serialize>(&self, key: &[u8], value: &V) -> anyhow::Result<()> {
+        self.write_bin(key, &fvm_ipld_encoding::to_vec(value)?)
+    }
+}
+
 /// Traits for collecting DB stats
 pub trait DBStatistics {
     fn get_statistics(&self) -> Option<String> {
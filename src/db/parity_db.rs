@@ -6,7 +6,7 @@ use std::path::PathBuf;
 
 use super::SettingsStore;
 
-use super::EthMappingsStore;
+use super::{EthMappingsStore, EventsStore};
 
 use crate::db::{
     parity_db_config::ParityDbConfig, truncated_hash, DBStatistics, GarbageCollectable,
@@ -34,7 +34,7 @@ use tracing::warn;
 /// It is used to determine which column to use for a given entry type.
 #[derive(Copy, Clone, Debug, Display, PartialEq, FromRepr, EnumIter)]
 #[repr(u8)]
-enum DbColumn {
+pub enum DbColumn {
     /// Column for storing IPLD data with `Blake2b256` hash and `DAG_CBOR` codec.
     /// Most entries in the `blockstore` will be stored in this column.
     GraphDagCborBlake2b256,
@@ -47,6 +47,8 @@ enum DbColumn {
     Settings,
     /// Column for storing Ethereum mappings.
     EthMappings,
+    /// Column for storing actor events, indexed by tipset and emitter.
+    Events,
 }
 
 impl DbColumn {
@@ -75,7 +77,7 @@ impl DbColumn {
                         compression,
                         ..Default::default()
                     },
-                    DbColumn::EthMappings => parity_db::ColumnOptions {
+                    DbColumn::EthMappings | DbColumn::Events => parity_db::ColumnOptions {
                         preimage: false,
                         btree_index: false,
                         compression,
@@ -195,6 +197,29 @@ impl EthMappingsStore for ParityDb {
     }
 }
 
+impl EventsStore for ParityDb {
+    fn read_bin(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        self.read_from_column(key, DbColumn::Events)
+    }
+
+    fn write_bin(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.write_to_column(key, value, DbColumn::Events)
+    }
+
+    fn exists(&self, key: &[u8]) -> anyhow::Result<bool> {
+        self.db
+            .get_size(DbColumn::Events as u8, key)
+            .map(|size| size.is_some())
+            .context("error checking if key exists")
+    }
+
+    fn remove(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.db
+            .commit([(DbColumn::Events as u8, key, None)])
+            .context("error removing key")
+    }
+}
+
 impl Blockstore for ParityDb {
     fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
         let column = Self::choose_column(k);
@@ -202,7 +227,9 @@ impl Blockstore for ParityDb {
             DbColumn::GraphDagCborBlake2b256 | DbColumn::GraphFull => {
                 self.read_from_column(k.to_bytes(), column)
             }
-            DbColumn::Settings | DbColumn::EthMappings => panic!("invalid column for IPLD data"),
+            DbColumn::Settings | DbColumn::EthMappings | DbColumn::Events => {
+                panic!("invalid column for IPLD data")
+            }
         }
     }
 
@@ -214,7 +241,9 @@ impl Blockstore for ParityDb {
             DbColumn::GraphDagCborBlake2b256 | DbColumn::GraphFull => {
                 self.write_to_column(k.to_bytes(), block, column)
             }
-            DbColumn::Settings | DbColumn::EthMappings => panic!("invalid column for IPLD data"),
+            DbColumn::Settings | DbColumn::EthMappings | DbColumn::Events => {
+                panic!("invalid column for IPLD data")
+            }
         }
     }
 
@@ -375,6 +404,42 @@ impl GarbageCollectable for ParityDb {
     }
 }
 
+impl ParityDb {
+    /// Calls `f` with the column, [`Cid`] and value of every graph record whose key is in `keys`,
+    /// i.e. the records [`GarbageCollectable::remove_keys`] would remove, without removing them.
+    pub fn for_each_marked(
+        &self,
+        keys: &HashSet<u32>,
+        mut f: impl FnMut(DbColumn, Cid, &[u8]) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let mut iter = self.db.iter(DbColumn::GraphFull as u8)?;
+        while let Some((key, value)) = iter.next()? {
+            let cid = Cid::try_from(key)?;
+            if keys.contains(&truncated_hash(cid.hash())) {
+                f(DbColumn::GraphFull, cid, &value)?;
+            }
+        }
+
+        // Same as in `remove_keys`, errors can't be returned from `iter_column_while` directly.
+        let mut result = Ok(());
+
+        self.db
+            .iter_column_while(DbColumn::GraphDagCborBlake2b256 as u8, |val| {
+                let hash = Blake2b256.digest(&val.value);
+                if keys.contains(&truncated_hash(&hash)) {
+                    let cid = Cid::new_v1(DAG_CBOR, hash);
+                    if let Err(e) = f(DbColumn::GraphDagCborBlake2b256, cid, &val.value) {
+                        result = Err(e);
+                        return false;
+                    }
+                }
+                true
+            })?;
+
+        result
+    }
+}
+
 #[cfg(test)]
 mod test {
     use cid::multihash::Code::Sha2_256;
@@ -423,6 +488,7 @@ mod test {
                 DbColumn::GraphFull => DbColumn::GraphDagCborBlake2b256,
                 DbColumn::Settings => panic!("invalid column for IPLD data"),
                 DbColumn::EthMappings => panic!("invalid column for IPLD data"),
+                DbColumn::Events => panic!("invalid column for IPLD data"),
             };
             let actual = db.read_from_column(cid.to_bytes(), other_column).unwrap();
             assert!(actual.is_none());
@@ -486,6 +552,67 @@ mod test {
         assert_eq!(keys.len(), 0);
     }
 
+    #[test]
+    fn for_each_marked_visits_marked_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("paritydb");
+        let config = ParityDbConfig::default();
+        let data = [
+            b"h'nglui mglw'nafh".to_vec(),
+            b"Cthulhu".to_vec(),
+            b"R'lyeh wgah'nagl fhtagn!!".to_vec(),
+        ];
+        let cases = [
+            (
+                DbColumn::GraphDagCborBlake2b256,
+                Cid::new_v1(DAG_CBOR, Blake2b256.digest(&data[0])),
+                &data[0],
+            ),
+            (
+                DbColumn::GraphFull,
+                Cid::new_v1(DAG_CBOR, Sha2_256.digest(&data[1])),
+                &data[1],
+            ),
+            (
+                DbColumn::GraphFull,
+                Cid::new_v1(IPLD_RAW, Blake2b256.digest(&data[2])),
+                &data[2],
+            ),
+        ];
+        {
+            let db = ParityDb::open(path.clone(), &config).unwrap();
+            for (_, cid, data) in cases {
+                db.put_keyed(&cid, data).unwrap();
+            }
+        }
+        // Reopened, so that iterating the columns sees every commit.
+        let db = ParityDb::open(path, &config).unwrap();
+
+        let marked = [cases[0], cases[2]];
+        let keys = marked
+            .iter()
+            .map(|(_, cid, _)| truncated_hash(cid.hash()))
+            .collect();
+        let mut visited = vec![];
+        db.for_each_marked(&keys, |column, cid, data| {
+            visited.push((column, cid, data.to_vec()));
+            Ok(())
+        })
+        .unwrap();
+        visited.sort_by_key(|(_, cid, _)| cid.to_bytes());
+        let mut expected = marked
+            .iter()
+            .map(|(column, cid, data)| (*column, *cid, data.to_vec()))
+            .collect::<Vec<_>>();
+        expected.sort_by_key(|(_, cid, _)| cid.to_bytes());
+        assert_eq!(visited, expected);
+
+        // Nothing is removed.
+        for (_, cid, data) in cases {
+            assert_eq!(Blockstore::get(&db, &cid).unwrap().as_ref(), Some(data));
+        }
+    }
+
     #[test]
     fn choose_column_test() {
         let data = [0u8; 32];
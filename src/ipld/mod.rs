@@ -1,6 +1,7 @@
 // Copyright 2019-2024 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+pub mod path;
 pub mod selector;
 pub mod util;
 
@@ -10,6 +11,7 @@ pub use util::*;
 #[cfg(test)]
 mod tests {
     mod cbor_test;
+    mod path_test;
     mod selector_explore;
     mod selector_gen_tests;
 }
@@ -0,0 +1,287 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Resolution of Lotus-style IPLD paths such as `<cid>/1/@Ha:f01234`.
+//!
+//! Plain segments select a field of a map or an index of a list. Segments
+//! starting with `@` step into an on-chain collection rooted at the current
+//! block:
+//! - `@H:<string>`: HAMT with a raw string key
+//! - `@Hi:<int>`: HAMT with a signed varint key
+//! - `@Hu:<uint>`: HAMT with an unsigned varint key
+//! - `@Ha:<address>`: HAMT keyed by address
+//! - `@A:<uint>`: AMT index
+//!
+//! Links are followed transparently between segments. The path is turned into
+//! a [`Selector`], which then drives the resolution: each step reads the
+//! segment to explore from the selector, and exploring the current node
+//! decides whether the entry exists and what the next step is.
+
+use std::fmt;
+use std::str::FromStr as _;
+
+use super::selector::{ExploreNode, Selector};
+use super::Ipld;
+use crate::shim::address::Address;
+use crate::utils::db::CborStoreExt as _;
+use anyhow::{bail, Context as _};
+use cid::Cid;
+use fil_actors_shared::fvm_ipld_amt::Amt;
+use fil_actors_shared::fvm_ipld_hamt::{BytesKey, Hamt};
+use fvm_ipld_blockstore::Blockstore;
+use indexmap::IndexMap;
+use integer_encoding::VarInt as _;
+
+/// Bit width of the HAMTs used by the built-in actors.
+pub const DEFAULT_HAMT_BIT_WIDTH: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HamtKey {
+    /// Raw string key, `@H:`.
+    String,
+    /// Signed varint key, `@Hi:`.
+    Int,
+    /// Unsigned varint key, `@Hu:`.
+    Uint,
+    /// Address key, `@Ha:`.
+    Address,
+}
+
+impl HamtKey {
+    /// Encodes the textual `key` into the bytes the HAMT is keyed by.
+    fn encode(self, key: &str) -> anyhow::Result<BytesKey> {
+        Ok(BytesKey(match self {
+            Self::String => key.as_bytes().to_vec(),
+            Self::Int => key
+                .parse::<i64>()
+                .with_context(|| format!("invalid int key {key}"))?
+                .encode_var_vec(),
+            Self::Uint => key
+                .parse::<u64>()
+                .with_context(|| format!("invalid uint key {key}"))?
+                .encode_var_vec(),
+            Self::Address => Address::from_str(key)
+                .with_context(|| format!("invalid address {key}"))?
+                .to_bytes(),
+        }))
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::String => "@H:",
+            Self::Int => "@Hi:",
+            Self::Uint => "@Hu:",
+            Self::Address => "@Ha:",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Field of a map, or index of a list.
+    Field(String),
+    /// Key of a HAMT rooted at the current block.
+    Hamt(HamtKey, String),
+    /// Index of an AMT rooted at the current block.
+    Amt(u64),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Field(field) => write!(f, "{field}"),
+            Self::Hamt(kind, key) => write!(f, "{}{key}", kind.prefix()),
+            Self::Amt(index) => write!(f, "@A:{index}"),
+        }
+    }
+}
+
+impl std::str::FromStr for PathSegment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        for kind in [
+            HamtKey::String,
+            HamtKey::Int,
+            HamtKey::Uint,
+            HamtKey::Address,
+        ] {
+            if let Some(key) = s.strip_prefix(kind.prefix()) {
+                kind.encode(key)?;
+                return Ok(Self::Hamt(kind, key.to_owned()));
+            }
+        }
+        if let Some(index) = s.strip_prefix("@A:") {
+            let index = index
+                .parse()
+                .with_context(|| format!("invalid AMT index {index}"))?;
+            Ok(Self::Amt(index))
+        } else if s.starts_with('@') {
+            bail!("unknown path segment {s}")
+        } else {
+            Ok(Self::Field(s.to_owned()))
+        }
+    }
+}
+
+/// Splits a path into its root [`Cid`] and the segments to resolve from it.
+pub fn parse_path(path: &str) -> anyhow::Result<(Cid, Vec<PathSegment>)> {
+    let mut parts = path
+        .strip_prefix("/ipfs/")
+        .unwrap_or(path)
+        .split('/')
+        .filter(|part| !part.is_empty());
+    let root = parts.next().context("empty path")?;
+    let root = Cid::from_str(root).with_context(|| format!("invalid root cid {root}"))?;
+    let segments = parts.map(PathSegment::from_str).collect::<Result<_, _>>()?;
+    Ok((root, segments))
+}
+
+/// Builds the [`Selector`] matching the node at the end of `segments`: an
+/// `ExploreIndex` step for AMT indices, and an `ExploreFields` step otherwise,
+/// keyed by the segment as written in the path.
+pub fn path_selector(segments: &[PathSegment]) -> Selector {
+    segments
+        .iter()
+        .rev()
+        .fold(Selector::Matcher, |next, segment| match segment {
+            PathSegment::Amt(index) => Selector::ExploreIndex {
+                index: *index as usize,
+                next: Box::new(next),
+            },
+            segment => Selector::ExploreFields {
+                fields: IndexMap::from([(segment.to_string(), next)]),
+            },
+        })
+}
+
+/// Resolves `path`, returning the node it points to together with the [`Cid`]
+/// of the block that node was found in. HAMT segments load their HAMT with
+/// `hamt_bit_width`.
+pub fn resolve_path(
+    store: &impl Blockstore,
+    path: &str,
+    hamt_bit_width: u32,
+) -> anyhow::Result<(Cid, Ipld)> {
+    let (root, segments) = parse_path(path)?;
+    resolve_selector(store, root, path_selector(&segments), hamt_bit_width)
+}
+
+/// Resolves the single node matched by a path-shaped `selector`, made of
+/// `ExploreFields` steps with one field and `ExploreIndex` steps, as built by
+/// [`path_selector`], starting at the block `root`.
+pub fn resolve_selector(
+    store: &impl Blockstore,
+    root: Cid,
+    mut selector: Selector,
+    hamt_bit_width: u32,
+) -> anyhow::Result<(Cid, Ipld)> {
+    let mut cid = root;
+    let mut node = load_node(store, &root)?;
+    // Whether `node` is the whole block at `cid`, which collections need.
+    let mut at_block_root = true;
+    loop {
+        let segment = match &selector {
+            Selector::Matcher => return Ok((cid, node)),
+            Selector::ExploreFields { fields } if fields.len() == 1 => fields
+                .keys()
+                .next()
+                .expect("one field")
+                .parse::<PathSegment>()?,
+            Selector::ExploreIndex { index, .. } => PathSegment::Amt(*index as u64),
+            selector => bail!("selector {selector:?} does not describe a path"),
+        };
+        let (view, p) = match &segment {
+            PathSegment::Field(field) => (Node::Ipld(&node), field.clone()),
+            PathSegment::Hamt(..) => {
+                if !at_block_root {
+                    bail!("HAMT segment must address a block, not an inline node at {cid}");
+                }
+                let hamt = Hamt::load_with_bit_width(&cid, store, hamt_bit_width)?;
+                (Node::Hamt(hamt), segment.to_string())
+            }
+            PathSegment::Amt(index) => {
+                if !at_block_root {
+                    bail!("AMT segment must address a block, not an inline node at {cid}");
+                }
+                (Node::Amt(Amt::load(&cid, store)?), index.to_string())
+            }
+        };
+        let Some(explored) = selector.explore(&view, &p) else {
+            // Surface store errors, which exploring only reports as a
+            // missing entry.
+            view.get(&p)?;
+            bail!("{segment} not found in node at {cid}");
+        };
+        selector = explored;
+        let next = view
+            .get(&p)?
+            .with_context(|| format!("{segment} not found in node at {cid}"))?;
+        if let Ipld::Link(link) = next {
+            cid = link;
+            node = load_node(store, &link)?;
+            at_block_root = true;
+        } else {
+            node = next;
+            at_block_root = false;
+        }
+    }
+}
+
+/// The node a path segment is resolved in.
+enum Node<'a, BS> {
+    Ipld(&'a Ipld),
+    /// A HAMT, whose entries are addressed by their `@H*:` path segment.
+    Hamt(Hamt<&'a BS, Ipld>),
+    Amt(Amt<Ipld, &'a BS>),
+}
+
+impl<BS: Blockstore> Node<'_, BS> {
+    fn get(&self, p: &str) -> anyhow::Result<Option<Ipld>> {
+        Ok(match self {
+            Node::Ipld(Ipld::Map(map)) => map.get(p).cloned(),
+            Node::Ipld(Ipld::List(list)) => {
+                p.parse::<usize>().ok().and_then(|i| list.get(i)).cloned()
+            }
+            Node::Ipld(_) => None,
+            Node::Hamt(hamt) => match p.parse::<PathSegment>()? {
+                PathSegment::Hamt(kind, key) => hamt.get(&kind.encode(&key)?)?.cloned(),
+                _ => None,
+            },
+            Node::Amt(amt) => match p.parse() {
+                Ok(index) => amt.get(index)?.cloned(),
+                Err(_) => None,
+            },
+        })
+    }
+}
+
+impl<BS: Blockstore> ExploreNode for Node<'_, BS> {
+    fn is_list(&self) -> bool {
+        match self {
+            Node::Ipld(ipld) => ipld.is_list(),
+            Node::Hamt(..) => false,
+            Node::Amt(_) => true,
+        }
+    }
+
+    fn has_entry(&self, p: &str) -> bool {
+        match self {
+            Node::Ipld(ipld) => ipld.has_entry(p),
+            // Loaded HAMT and AMT nodes are cached, so the lookup is not
+            // repeated when the entry is fetched afterwards.
+            _ => matches!(self.get(p), Ok(Some(_))),
+        }
+    }
+}
+
+fn load_node(store: &impl Blockstore, cid: &Cid) -> anyhow::Result<Ipld> {
+    if cid.codec() == crate::shim::crypto::IPLD_RAW {
+        let bytes = store
+            .get(cid)?
+            .with_context(|| format!("can't find object with cid={cid}"))?;
+        Ok(Ipld::Bytes(bytes))
+    } else {
+        store.get_cbor_required(cid)
+    }
+}
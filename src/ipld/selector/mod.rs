@@ -7,9 +7,7 @@ use std::ops::SubAssign;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-#[cfg(test)]
 use super::Ipld;
-#[cfg(test)]
 use Selector::*;
 
 /// Selectors are expressions that identify and select a subset of data from an
@@ -199,53 +197,65 @@ pub enum Condition {
     Or,
 }
 
-#[cfg(test)]
+/// A node that a [`Selector`] is explored on. Besides plain [`Ipld`], this
+/// covers collections such as HAMTs and AMTs, which are addressed like maps
+/// and lists without being loaded as a whole.
+pub trait ExploreNode {
+    /// Whether the entries of the node are addressed by index.
+    fn is_list(&self) -> bool;
+    /// Whether the node has an entry at path segment `p`.
+    fn has_entry(&self, p: &str) -> bool;
+}
+
+impl ExploreNode for Ipld {
+    fn is_list(&self) -> bool {
+        matches!(self, Ipld::List(_))
+    }
+
+    fn has_entry(&self, p: &str) -> bool {
+        match self {
+            Ipld::Map(m) => m.contains_key(p),
+            Ipld::List(l) => p.parse::<usize>().is_ok_and(|i| i < l.len()),
+            _ => false,
+        }
+    }
+}
+
 impl Selector {
     /// Processes and returns resultant selector node
-    pub fn explore(self, ipld: &Ipld, p: &str) -> Option<Selector> {
+    pub fn explore<N: ExploreNode + ?Sized>(self, node: &N, p: &str) -> Option<Selector> {
         match self {
             ExploreAll { next } => Some(*next),
-            ExploreFields { mut fields } => match ipld {
-                Ipld::Map(m) => {
-                        m.get(p)?;
-                        fields.swap_remove(p)
-                    }
-                ,
-                // Using ExploreFields for list is supported feature in go impl
-                Ipld::List(l) => {
-                    // Check to make sure index is within bounds
-                    if p.parse::<usize>().ok()? >= l.len() {
-                        return None;
-                    }
-                    fields.swap_remove(p)
+            // Using ExploreFields for list is supported feature in go impl
+            ExploreFields { mut fields } => {
+                if !node.has_entry(p) {
+                    return None;
                 }
-                _ => None,
-            },
-            ExploreIndex { index, next } => match ipld {
-                Ipld::List(l) => {
-                    let i = p.parse::<usize>().ok()?;
-                    if i != index || i >= l.len() {
-                        None
-                    } else {
-                        // Path segment matches selector index
-                        Some(*next)
-                    }
+                fields.swap_remove(p)
+            }
+            ExploreIndex { index, next } => {
+                if !node.is_list() {
+                    return None;
+                }
+                let i = p.parse::<usize>().ok()?;
+                if i != index || !node.has_entry(p) {
+                    None
+                } else {
+                    // Path segment matches selector index
+                    Some(*next)
                 }
-                _ => None,
-            },
+            }
             ExploreRange { start, end, next } => {
-                match ipld {
-                    Ipld::List(l) => {
-                        let i = p.parse::<usize>().ok()?;
-                        // Check to make sure index is within list bounds
-                        if i < start || i >= end || i >= l.len() {
-                            None
-                        } else {
-                            // Path segment is within the selector range
-                            Some(*next)
-                        }
-                    }
-                    _ => None,
+                if !node.is_list() {
+                    return None;
+                }
+                let i = p.parse::<usize>().ok()?;
+                // Check to make sure index is within list bounds
+                if i < start || i >= end || !node.has_entry(p) {
+                    None
+                } else {
+                    // Path segment is within the selector range
+                    Some(*next)
                 }
             }
             ExploreRecursive {
@@ -256,7 +266,7 @@ impl Selector {
             } => {
                 let next = current
                     .unwrap_or_else(|| sequence.clone())
-                    .explore(ipld, p)?;
+                    .explore(node, p)?;
 
                 if !has_recursive_edge(&next) {
                     return Some(ExploreRecursive {
@@ -286,7 +296,7 @@ impl Selector {
                 // Push all valid explored selectors to new vector
                 let replace_selectors: Vec<_> = selectors
                     .into_iter()
-                    .filter_map(|s| s.explore(ipld, p))
+                    .filter_map(|s| s.explore(node, p))
                     .collect();
 
                 Selector::from_selectors(replace_selectors)
@@ -306,7 +316,6 @@ impl Selector {
     }
 }
 
-#[cfg(test)]
 fn replace_recursive_edge(next_sel: Selector, replace: Option<Selector>) -> Option<Selector> {
     match next_sel {
         ExploreRecursiveEdge => replace,
@@ -322,7 +331,7 @@ fn replace_recursive_edge(next_sel: Selector, replace: Option<Selector>) -> Opti
         _ => Some(next_sel),
     }
 }
-#[cfg(test)]
+
 fn has_recursive_edge(next_sel: &Selector) -> bool {
     match next_sel {
         ExploreRecursiveEdge { .. } => true,
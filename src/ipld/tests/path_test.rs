@@ -0,0 +1,171 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use crate::db::MemoryDB;
+use crate::ipld::{
+    path::{parse_path, path_selector, resolve_path, resolve_selector, DEFAULT_HAMT_BIT_WIDTH},
+    selector::Selector,
+    Ipld,
+};
+use crate::shim::address::Address;
+use cid::multihash::Code::Blake2b256;
+use fil_actors_shared::fvm_ipld_amt::Amt;
+use fil_actors_shared::fvm_ipld_hamt::{BytesKey, Hamt};
+use fvm_ipld_encoding::CborStore as _;
+use libipld_macro::ipld;
+
+/// Stores `[1, <HAMT by address>, <AMT>]` and returns the root path.
+fn setup(store: &MemoryDB) -> String {
+    let leaf = store
+        .put_cbor(&ipld!({ "name": "leaf", "values": [10, 20] }), Blake2b256)
+        .unwrap();
+
+    let mut hamt = Hamt::<_, Ipld>::new_with_bit_width(store, 5);
+    hamt.set(
+        BytesKey(Address::new_id(1234).to_bytes()),
+        ipld!(["actor", Ipld::Link(leaf)]),
+    )
+    .unwrap();
+    let hamt_root = hamt.flush().unwrap();
+
+    let mut amt = Amt::<Ipld, _>::new(store);
+    amt.set(3, ipld!("three")).unwrap();
+    let amt_root = amt.flush().unwrap();
+
+    let root = store
+        .put_cbor(
+            &ipld!([1, Ipld::Link(hamt_root), Ipld::Link(amt_root)]),
+            Blake2b256,
+        )
+        .unwrap();
+    root.to_string()
+}
+
+#[test]
+fn resolve_plain_and_collection_segments() {
+    let store = MemoryDB::default();
+    let root = setup(&store);
+
+    let (_, node) = resolve_path(&store, &format!("{root}/0"), DEFAULT_HAMT_BIT_WIDTH).unwrap();
+    assert_eq!(node, ipld!(1));
+
+    let (_, node) = resolve_path(
+        &store,
+        &format!("/ipfs/{root}/1/@Ha:f01234/0"),
+        DEFAULT_HAMT_BIT_WIDTH,
+    )
+    .unwrap();
+    assert_eq!(node, ipld!("actor"));
+
+    let (cid, node) = resolve_path(
+        &store,
+        &format!("{root}/1/@Ha:f01234/1/values/1"),
+        DEFAULT_HAMT_BIT_WIDTH,
+    )
+    .unwrap();
+    assert_eq!(node, ipld!(20));
+    assert_eq!(
+        cid,
+        store
+            .put_cbor(&ipld!({ "name": "leaf", "values": [10, 20] }), Blake2b256)
+            .unwrap()
+    );
+
+    let (_, node) =
+        resolve_path(&store, &format!("{root}/2/@A:3"), DEFAULT_HAMT_BIT_WIDTH).unwrap();
+    assert_eq!(node, ipld!("three"));
+}
+
+#[test]
+fn resolve_missing_segments() {
+    let store = MemoryDB::default();
+    let root = setup(&store);
+
+    assert!(resolve_path(&store, &format!("{root}/3"), DEFAULT_HAMT_BIT_WIDTH).is_err());
+    assert!(resolve_path(
+        &store,
+        &format!("{root}/1/@Ha:f05678"),
+        DEFAULT_HAMT_BIT_WIDTH
+    )
+    .is_err());
+    assert!(resolve_path(&store, &format!("{root}/2/@A:4"), DEFAULT_HAMT_BIT_WIDTH).is_err());
+    assert!(resolve_path(
+        &store,
+        &format!("{root}/1/@Ha:f01234/1/missing"),
+        DEFAULT_HAMT_BIT_WIDTH
+    )
+    .is_err());
+    // Collections can only be stepped into at a block root.
+    assert!(resolve_path(
+        &store,
+        &format!("{root}/1/@Ha:f01234/@A:0"),
+        DEFAULT_HAMT_BIT_WIDTH
+    )
+    .is_err());
+    assert!(resolve_path(&store, &format!("{root}/@X:1"), DEFAULT_HAMT_BIT_WIDTH).is_err());
+}
+
+#[test]
+fn resolve_hamt_with_custom_bit_width() {
+    let store = MemoryDB::default();
+    let mut hamt = Hamt::<_, Ipld>::new_with_bit_width(&store, 3);
+    // Enough entries for the root to point to child nodes.
+    for i in 0..100 {
+        hamt.set(BytesKey(format!("key{i}").into_bytes()), ipld!(i))
+            .unwrap();
+    }
+    let root = hamt.flush().unwrap();
+
+    let (_, node) = resolve_path(&store, &format!("{root}/@H:key42"), 3).unwrap();
+    assert_eq!(node, ipld!(42));
+    assert!(resolve_path(&store, &format!("{root}/@H:key42"), DEFAULT_HAMT_BIT_WIDTH).is_err());
+}
+
+#[test]
+fn resolve_follows_the_selector() {
+    let store = MemoryDB::default();
+    let (root, segments) = parse_path(&format!("{}/1/@Ha:f01234/0", setup(&store))).unwrap();
+    let (_, node) = resolve_selector(
+        &store,
+        root,
+        path_selector(&segments),
+        DEFAULT_HAMT_BIT_WIDTH,
+    )
+    .unwrap();
+    assert_eq!(node, ipld!("actor"));
+
+    // Exploring decides whether a step applies: an index step doesn't apply to
+    // a map, even one with a matching key.
+    let root = store.put_cbor(&ipld!({ "0": "zero" }), Blake2b256).unwrap();
+    let (_, node) = resolve_selector(
+        &store,
+        root,
+        Selector::ExploreFields {
+            fields: [("0".to_owned(), Selector::Matcher)].into_iter().collect(),
+        },
+        DEFAULT_HAMT_BIT_WIDTH,
+    )
+    .unwrap();
+    assert_eq!(node, ipld!("zero"));
+    assert!(resolve_selector(
+        &store,
+        root,
+        Selector::ExploreIndex {
+            index: 0,
+            next: Box::new(Selector::Matcher),
+        },
+        DEFAULT_HAMT_BIT_WIDTH,
+    )
+    .is_err());
+
+    // Selectors matching more than one node don't describe a path.
+    assert!(resolve_selector(
+        &store,
+        root,
+        Selector::ExploreAll {
+            next: Box::new(Selector::Matcher),
+        },
+        DEFAULT_HAMT_BIT_WIDTH,
+    )
+    .is_err());
+}
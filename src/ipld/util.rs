@@ -123,7 +123,6 @@ impl<DB, T> ChainStream<DB, T> {
         ChainStream { seen, ..self }
     }
 
-    #[allow(dead_code)]
     pub fn into_seen(self) -> CidHashSet {
         self.seen
     }
@@ -172,6 +171,19 @@ pub fn stream_graph<DB: Blockstore, T: Iterator<Item = Tipset> + Unpin>(
     }
 }
 
+/// Stream all blocks reachable from `root` in a depth-first fashion, without
+/// walking any tipsets. Any dead links are reported as errors.
+pub fn stream_dag<DB: Blockstore>(db: DB, root: Cid) -> ChainStream<DB, std::iter::Empty<Tipset>> {
+    ChainStream {
+        tipset_iter: std::iter::empty(),
+        db,
+        dfs: VecDeque::from([Task::Iterate(VecDeque::from([root]))]),
+        seen: CidHashSet::default(),
+        stateroot_limit: 0,
+        fail_on_dead_links: true,
+    }
+}
+
 impl<DB: Blockstore, T: Iterator<Item = Tipset> + Unpin> Stream for ChainStream<DB, T> {
     type Item = anyhow::Result<CarBlock>;
 
@@ -176,6 +176,7 @@ mod tests {
 
         let response = make_chain_exchange_response(
             &ChainStore::new(
+                db.clone(),
                 db.clone(),
                 db.clone(),
                 db,
@@ -0,0 +1,129 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use super::*;
+use crate::shim::executor::{Entry, StampedEvent};
+use fvm_shared4::event::{ActorEvent, Flags};
+use fvm_shared4::ActorID;
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+#[schemars(rename = "EventEntry")]
+pub struct EntryLotusJson {
+    flags: u64,
+    key: String,
+    codec: u64,
+    #[schemars(with = "LotusJson<Vec<u8>>")]
+    #[serde(with = "crate::lotus_json")]
+    value: Vec<u8>,
+}
+
+impl HasLotusJson for Entry {
+    type LotusJson = EntryLotusJson;
+
+    #[cfg(test)]
+    fn snapshots() -> Vec<(serde_json::Value, Self)> {
+        vec![(
+            json!({
+                "Flags": 3,
+                "Key": "t1",
+                "Codec": 85,
+                "Value": "aGVsbG8gd29ybGQh",
+            }),
+            Entry {
+                flags: Flags::FLAG_INDEXED_ALL,
+                key: "t1".into(),
+                codec: 85,
+                value: Vec::from_iter(*b"hello world!"),
+            },
+        )]
+    }
+
+    fn into_lotus_json(self) -> Self::LotusJson {
+        let Self {
+            flags,
+            key,
+            codec,
+            value,
+        } = self;
+        Self::LotusJson {
+            flags: flags.bits(),
+            key,
+            codec,
+            value,
+        }
+    }
+
+    fn from_lotus_json(lotus_json: Self::LotusJson) -> Self {
+        let Self::LotusJson {
+            flags,
+            key,
+            codec,
+            value,
+        } = lotus_json;
+        Self {
+            flags: Flags::from_bits_retain(flags),
+            key,
+            codec,
+            value,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+#[schemars(rename = "Event")]
+pub struct StampedEventLotusJson {
+    emitter: ActorID,
+    #[schemars(with = "LotusJson<Vec<Entry>>")]
+    #[serde(with = "crate::lotus_json")]
+    entries: Vec<Entry>,
+}
+
+impl HasLotusJson for StampedEvent {
+    type LotusJson = StampedEventLotusJson;
+
+    #[cfg(test)]
+    fn snapshots() -> Vec<(serde_json::Value, Self)> {
+        vec![(
+            json!({
+                "Emitter": 1000,
+                "Entries": [
+                    {
+                        "Flags": 1,
+                        "Key": "t1",
+                        "Codec": 85,
+                        "Value": "aGVsbG8gd29ybGQh",
+                    }
+                ],
+            }),
+            StampedEvent::new(
+                1000,
+                ActorEvent::from(vec![Entry {
+                    flags: Flags::FLAG_INDEXED_KEY,
+                    key: "t1".into(),
+                    codec: 85,
+                    value: Vec::from_iter(*b"hello world!"),
+                }]),
+            ),
+        )]
+    }
+
+    fn into_lotus_json(self) -> Self::LotusJson {
+        Self::LotusJson {
+            emitter: self.emitter,
+            entries: self.event.entries,
+        }
+    }
+
+    fn from_lotus_json(lotus_json: Self::LotusJson) -> Self {
+        let Self::LotusJson { emitter, entries } = lotus_json;
+        Self::new(emitter, ActorEvent::from(entries))
+    }
+}
+
+#[test]
+fn snapshots() {
+    assert_all_snapshots::<Entry>();
+    assert_all_snapshots::<StampedEvent>();
+}
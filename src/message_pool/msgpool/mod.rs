@@ -485,6 +485,35 @@ pub mod tests {
         assert_eq!(mpool.get_sequence(&sender).unwrap(), 2);
     }
 
+    #[tokio::test]
+    async fn test_subscribe_added() {
+        let keystore = KeyStore::new(KeyStoreConfig::Memory).unwrap();
+        let mut wallet = Wallet::new(keystore);
+        let sender = wallet.generate_addr(SignatureType::Secp256k1).unwrap();
+        let target = wallet.generate_addr(SignatureType::Secp256k1).unwrap();
+        let tma = TestApi::default();
+        tma.set_state_sequence(&sender, 0);
+
+        let (tx, _rx) = flume::bounded(50);
+        let mut services = JoinSet::new();
+        let mpool = MessagePool::new(
+            tma,
+            "mptest".to_string(),
+            tx,
+            Default::default(),
+            Arc::default(),
+            &mut services,
+        )
+        .unwrap();
+        let mut added = mpool.subscribe_added();
+        let smsg = create_smsg(&target, &sender, wallet.borrow_mut(), 0, 1000000, 1);
+        mpool.add(smsg.clone()).unwrap();
+        assert_eq!(added.try_recv().unwrap(), smsg);
+        // a rejected message is not published
+        mpool.add(smsg).unwrap_err();
+        added.try_recv().unwrap_err();
+    }
+
     #[tokio::test]
     async fn test_revert_messages() {
         let tma = TestApi::default();
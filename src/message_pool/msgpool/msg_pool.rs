@@ -31,7 +31,11 @@ use itertools::Itertools;
 use lru::LruCache;
 use nonzero_ext::nonzero;
 use parking_lot::{Mutex, RwLock as SyncRwLock};
-use tokio::{sync::broadcast::error::RecvError, task::JoinSet, time::interval};
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinSet,
+    time::interval,
+};
 use tracing::warn;
 
 use crate::message_pool::{
@@ -50,6 +54,9 @@ use crate::message_pool::{
 const BLS_SIG_CACHE_SIZE: NonZeroUsize = nonzero!(40000usize);
 const SIG_VAL_CACHE_SIZE: NonZeroUsize = nonzero!(32000usize);
 
+/// Number of added messages buffered for each subscriber to [`MessagePool::subscribe_added`].
+const ADDED_MESSAGES_CAPACITY: usize = 1000;
+
 pub const MAX_ACTOR_PENDING_MESSAGES: u64 = 1000;
 pub const MAX_UNTRUSTED_ACTOR_PENDING_MESSAGES: u64 = 10;
 
@@ -188,6 +195,8 @@ pub struct MessagePool<T> {
     /// messages
     pub repub_trigger: flume::Sender<()>,
     local_msgs: Arc<SyncRwLock<HashSet<SignedMessage>>>,
+    /// Publishes the messages added to the pending set
+    added: broadcast::Sender<SignedMessage>,
     /// Configurable parameters of the message pool
     pub config: MpoolConfig,
     /// Chain configuration
@@ -311,9 +320,18 @@ where
             self.api.as_ref(),
             self.bls_sig_cache.as_ref(),
             self.pending.as_ref(),
-            msg,
+            msg.clone(),
             self.get_state_sequence(&from, &cur_ts)?,
-        )
+        )?;
+        // Fails only if nobody is subscribed.
+        let _ = self.added.send(msg);
+        Ok(())
+    }
+
+    /// Subscribes to the messages added to the pending set from now on, either pushed locally
+    /// or received from the network.
+    pub fn subscribe_added(&self) -> broadcast::Receiver<SignedMessage> {
+        self.added.subscribe()
     }
 
     /// Get the sequence for a given address, return Error if there is a failure
@@ -476,6 +494,7 @@ where
             sig_val_cache,
             local_msgs,
             republished,
+            added: broadcast::channel(ADDED_MESSAGES_CAPACITY).0,
             config,
             network_sender,
             repub_trigger,
@@ -9,7 +9,7 @@ use once_cell::sync::Lazy;
 use parking_lot::{RwLock, RwLockWriteGuard};
 use prometheus_client::{
     encoding::EncodeLabelSet,
-    metrics::{counter::Counter, family::Family, histogram::Histogram},
+    metrics::{counter::Counter, family::Family, gauge::Gauge, histogram::Histogram},
 };
 use std::sync::Arc;
 use std::{path::PathBuf, time::Instant};
@@ -38,6 +38,25 @@ pub static LRU_CACHE_MISS: Lazy<Family<KindLabel, Counter>> = Lazy::new(|| {
     metric
 });
 
+pub static ETH_MAPPINGS_LOWEST_EPOCH: Lazy<Gauge> = Lazy::new(|| {
+    let metric = Gauge::default();
+    DEFAULT_REGISTRY.write().register(
+        "eth_mappings_lowest_epoch",
+        "Lowest epoch of the highest contiguous range covered by the Ethereum transaction hash index",
+        metric.clone(),
+    );
+    metric
+});
+pub static ETH_MAPPINGS_HIGHEST_EPOCH: Lazy<Gauge> = Lazy::new(|| {
+    let metric = Gauge::default();
+    DEFAULT_REGISTRY.write().register(
+        "eth_mappings_highest_epoch",
+        "Highest epoch covered by the Ethereum transaction hash index",
+        metric.clone(),
+    );
+    metric
+});
+
 pub async fn init_prometheus<DB>(
     prometheus_listener: TcpListener,
     db_directory: PathBuf,
@@ -21,8 +21,10 @@ use crate::shim::version::NetworkVersion;
 use crate::{make_butterfly_policy, make_calibnet_policy, make_devnet_policy, make_mainnet_policy};
 
 mod actors_bundle;
+use actors_bundle::ACTOR_BUNDLES_METADATA;
 pub use actors_bundle::{
-    generate_actor_bundle, get_actor_bundles_metadata, ActorBundleInfo, ACTOR_BUNDLES,
+    generate_actor_bundle, get_actor_bundles_metadata, ActorBundleInfo, ActorBundleMetadata,
+    ACTOR_BUNDLES,
 };
 
 mod drand;
@@ -310,6 +312,35 @@ impl ChainConfig {
             .max(self.genesis_network)
     }
 
+    /// Returns the metadata of the builtin-actors bundle that is in effect at the given network
+    /// version. Returns `None` if the network version is unknown or predates bundled actors.
+    pub fn actor_bundle_metadata(
+        &self,
+        network_version: NetworkVersion,
+    ) -> Option<&'static ActorBundleMetadata> {
+        let newest = self
+            .height_infos
+            .keys()
+            .copied()
+            .map(NetworkVersion::from)
+            .max()?;
+        if network_version > newest {
+            return None;
+        }
+        let bundle = self
+            .height_infos
+            .iter()
+            .filter(|(height, _)| NetworkVersion::from(**height) <= network_version)
+            .filter_map(|(height, info)| {
+                Some((NetworkVersion::from(*height), info.epoch, info.bundle?))
+            })
+            .max_by_key(|(version, epoch, _)| (*version, *epoch))
+            .map(|(_, _, bundle)| bundle)?;
+        ACTOR_BUNDLES_METADATA
+            .values()
+            .find(|metadata| metadata.bundle_cid == bundle)
+    }
+
     pub fn get_beacon_schedule(&self, genesis_ts: u64) -> BeaconSchedule {
         let ds_iter = match self.network {
             NetworkChain::Mainnet => mainnet::DRAND_SCHEDULE.iter(),
@@ -508,6 +539,27 @@ mod tests {
         heights_are_present(&butterflynet::HEIGHT_INFOS);
     }
 
+    #[test]
+    fn test_actor_bundle_metadata() {
+        let config = ChainConfig::mainnet();
+        let version_at = |nv| {
+            config
+                .actor_bundle_metadata(nv)
+                .map(|metadata| metadata.version.as_str())
+        };
+        assert_eq!(version_at(NetworkVersion::V16), None);
+        assert_eq!(version_at(NetworkVersion::V17), Some("v9.0.3"));
+        assert_eq!(version_at(NetworkVersion::V19), Some("v11.0.0"));
+        assert_eq!(version_at(NetworkVersion::V20), Some("v11.0.0"));
+        assert_eq!(version_at(NetworkVersion::V22), Some("v13.0.0"));
+        assert_eq!(version_at(NetworkVersion::from(1000)), None);
+
+        let config = ChainConfig::calibnet();
+        let metadata = config.actor_bundle_metadata(NetworkVersion::V21).unwrap();
+        assert_eq!(metadata.network, NetworkChain::Calibnet);
+        assert_eq!(metadata.version, "v12.0.0");
+    }
+
     #[test]
     fn test_get_upgrade_height_no_env_var() {
         let epoch = get_upgrade_height_from_env("FOREST_TEST_VAR_1");
@@ -3,7 +3,7 @@
 
 use crate::auth::{verify_token, JWT_IDENTIFIER};
 use crate::key_management::KeyStore;
-use crate::rpc::{chain, Permission, RpcMethod as _, CANCEL_METHOD_NAME};
+use crate::rpc::{chain, eth, state, Permission, RpcMethod as _, CANCEL_METHOD_NAME};
 use ahash::{HashMap, HashMapExt as _};
 use futures::future::BoxFuture;
 use futures::FutureExt;
@@ -26,12 +26,22 @@ static METHOD_NAME2REQUIRED_PERMISSION: Lazy<HashMap<&str, Permission>> = Lazy::
     macro_rules! insert {
         ($ty:ty) => {
             access.insert(<$ty>::NAME, <$ty>::PERMISSION);
+            if let Some(alias) = <$ty>::NAME_ALIAS {
+                access.insert(alias, <$ty>::PERMISSION);
+            }
         };
     }
     super::for_each_method!(insert);
 
     access.insert(chain::CHAIN_NOTIFY, Permission::Read);
+    access.insert(chain::CHAIN_NOTIFY_FROM, Permission::Read);
+    access.insert(state::STATE_MARKET_DEALS_STREAM, Permission::Read);
+    access.insert(state::STATE_LIST_ACTORS_STREAM, Permission::Read);
     access.insert(CANCEL_METHOD_NAME, Permission::Read);
+    access.insert(eth::pubsub::ETH_SUBSCRIBE, Permission::Read);
+    access.insert(eth::pubsub::ETH_SUBSCRIBE_ALIAS, Permission::Read);
+    access.insert(eth::pubsub::ETH_UNSUBSCRIBE, Permission::Read);
+    access.insert(eth::pubsub::ETH_UNSUBSCRIBE_ALIAS, Permission::Read);
 
     access
 });
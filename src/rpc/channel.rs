@@ -79,6 +79,8 @@ use super::error::ServerError;
 
 pub const NOTIF_METHOD_NAME: &str = "xrpc.ch.val";
 pub const CANCEL_METHOD_NAME: &str = "xrpc.cancel";
+/// Notification method of Ethereum subscriptions.
+pub const ETH_NOTIF_METHOD_NAME: &str = "eth_subscription";
 
 pub type ChannelId = u64;
 
@@ -123,7 +125,7 @@ impl PendingSubscriptionSink {
         let id = self.id.clone();
         let response = MethodResponse::subscription_response(
             self.id,
-            ResponsePayload::success_borrowed(&channel_id),
+            ResponsePayload::success(subscription_id(self.method, channel_id)),
             self.inner.max_response_size() as usize,
         );
         let success = response.is_success();
@@ -162,6 +164,14 @@ impl PendingSubscriptionSink {
         }
     }
 
+    /// Reject the subscription by responding to the subscription method call with the given
+    /// error.
+    pub async fn reject(self, err: impl Into<ErrorObjectOwned>) {
+        let err = MethodResponse::subscription_error(self.id, err.into());
+        let _ = self.inner.send(err.to_result()).await;
+        let _ = self.subscribe.send(err);
+    }
+
     /// Returns the channel identifier
     pub fn channel_id(&self) -> ChannelId {
         self.channel_id
@@ -237,6 +247,16 @@ impl SubscriptionSink {
     }
 }
 
+/// Returns the identifier of a channel, as sent to the subscriber. Ethereum subscription
+/// identifiers are hex-encoded strings.
+fn subscription_id(method: &str, channel_id: ChannelId) -> serde_json::Value {
+    if method == ETH_NOTIF_METHOD_NAME {
+        format!("{channel_id:#x}").into()
+    } else {
+        channel_id.into()
+    }
+}
+
 fn create_notif_message(
     sink: &SubscriptionSink,
     result: &impl serde::Serialize,
@@ -244,8 +264,14 @@ fn create_notif_message(
     let method = sink.method_name();
     let channel_id = sink.channel_id();
     let result = serde_json::to_string(result)?;
-    let msg =
-        format!(r#"{{"jsonrpc":"2.0","method":"{method}","params":[{channel_id},{result}]}}"#,);
+    let msg = if method == ETH_NOTIF_METHOD_NAME {
+        let subscription = subscription_id(method, channel_id);
+        format!(
+            r#"{{"jsonrpc":"2.0","method":"{method}","params":{{"subscription":{subscription},"result":{result}}}}}"#,
+        )
+    } else {
+        format!(r#"{{"jsonrpc":"2.0","method":"{method}","params":[{channel_id},{result}]}}"#,)
+    };
 
     tracing::debug!("Sending notification: {}", msg);
 
@@ -260,6 +286,17 @@ fn close_payload(channel_id: ChannelId) -> serde_json::Value {
     })
 }
 
+/// The close message of a channel whose producer failed, carrying the error as a second
+/// parameter so that clients can tell a truncated stream from a complete one.
+fn close_with_error_payload(channel_id: ChannelId, error: ServerError) -> serde_json::Value {
+    let error: ErrorObjectOwned = error.into();
+    serde_json::json!({
+        "jsonrpc":"2.0",
+        "method":"xrpc.ch.close",
+        "params":[channel_id, error]
+    })
+}
+
 fn close_channel_response(channel_id: ChannelId) -> MethodResponse {
     MethodResponse::response(
         Id::Null,
@@ -347,10 +384,125 @@ impl RpcModule {
         F: (Fn(Params) -> tokio::sync::broadcast::Receiver<R>) + Send + Sync + 'static,
         R: serde::Serialize + Clone + Send + 'static,
     {
-        self.register_channel_raw(subscribe_method_name, {
+        self.register_subscription(subscribe_method_name, NOTIF_METHOD_NAME, move |params| {
+            Ok(callback(params))
+        })
+    }
+
+    /// Registers a method that streams a finite sequence of values as channel notifications,
+    /// followed by a close message once the receiver returned by `callback` is exhausted.
+    /// Unlike [`RpcModule::register_channel`], no value is dropped: the producer is held back
+    /// until the client has caught up. If the producer sends an error, the channel is closed
+    /// right away, with the error attached to the close message.
+    pub fn register_stream<R, F>(
+        &mut self,
+        method_name: &'static str,
+        callback: F,
+    ) -> Result<&mut MethodCallback, RegisterMethodError>
+    where
+        F: (Fn(Params) -> Result<mpsc::Receiver<Result<R, ServerError>>, ServerError>)
+            + Send
+            + Sync
+            + 'static,
+        R: serde::Serialize + Send + 'static,
+    {
+        self.register_stream_raw(method_name, NOTIF_METHOD_NAME, callback)
+    }
+
+    /// Registers an Ethereum subscription method, whose notifications are sent with the
+    /// [`ETH_NOTIF_METHOD_NAME`] method. The subscription is rejected if `callback` fails. As for
+    /// [`RpcModule::register_stream`], the producer is held back until the client has caught up,
+    /// and an error sent by the producer closes the subscription.
+    pub fn register_eth_subscription<R, F>(
+        &mut self,
+        subscribe_method_name: &'static str,
+        callback: F,
+    ) -> Result<&mut MethodCallback, RegisterMethodError>
+    where
+        F: (Fn(Params) -> Result<mpsc::Receiver<Result<R, ServerError>>, ServerError>)
+            + Send
+            + Sync
+            + 'static,
+        R: serde::Serialize + Send + 'static,
+    {
+        self.register_stream_raw(subscribe_method_name, ETH_NOTIF_METHOD_NAME, callback)
+    }
+
+    /// Registers an Ethereum unsubscription method, which takes the identifier of a
+    /// subscription opened on the same connection and returns whether it was found.
+    pub fn register_eth_unsubscription(
+        &mut self,
+        unsubscribe_method_name: &'static str,
+    ) -> Result<&mut MethodCallback, RegisterMethodError> {
+        self.methods.verify_method_name(unsubscribe_method_name)?;
+        let channels = self.channels.clone();
+        self.methods.verify_and_insert(
+            unsubscribe_method_name,
+            MethodCallback::Unsubscription(Arc::new(
+                move |id,
+                      params: Params,
+                      connection_id: ConnectionId,
+                      max_response,
+                      _extensions| {
+                    let cb = || {
+                        let (sub_id,): (String,) = params.parse()?;
+                        let channel_id =
+                            ChannelId::from_str_radix(sub_id.trim_start_matches("0x"), 16)
+                                .map_err(|e| {
+                                    anyhow::anyhow!("invalid subscription id {sub_id}: {e}")
+                                })?;
+
+                        tracing::debug!("Got unsubscribe request (chann_id={channel_id})");
+
+                        let mut channels = channels.lock();
+                        let key = channels
+                            .iter()
+                            .find(|((conn, _), (_, _, chann))| {
+                                *conn == connection_id && *chann == channel_id
+                            })
+                            .map(|(key, _)| key.clone());
+                        Ok::<bool, ServerError>(key.and_then(|key| channels.remove(&key)).is_some())
+                    };
+                    match cb() {
+                        Ok(found) => MethodResponse::response(
+                            id,
+                            ResponsePayload::success(found),
+                            max_response,
+                        ),
+                        Err(e) => {
+                            let error: ErrorObjectOwned = e.into();
+                            MethodResponse::error(id, error)
+                        }
+                    }
+                },
+            )),
+        )
+    }
+
+    fn register_subscription<R, F>(
+        &mut self,
+        subscribe_method_name: &'static str,
+        notif_method_name: &'static str,
+        callback: F,
+    ) -> Result<&mut MethodCallback, RegisterMethodError>
+    where
+        F: (Fn(Params) -> Result<tokio::sync::broadcast::Receiver<R>, ServerError>)
+            + Send
+            + Sync
+            + 'static,
+        R: serde::Serialize + Clone + Send + 'static,
+    {
+        self.register_channel_raw(subscribe_method_name, notif_method_name, {
             move |params, pending| {
-                let mut receiver = callback(params);
+                let receiver = callback(params);
                 tokio::spawn(async move {
+                    let mut receiver = match receiver {
+                        Ok(receiver) => receiver,
+                        Err(e) => {
+                            pending.reject(e).await;
+                            return;
+                        }
+                    };
                     let sink = pending.accept().await.unwrap();
                     tracing::debug!("Channel created: chann_id={}", sink.channel_id);
 
@@ -392,9 +544,75 @@ impl RpcModule {
         })
     }
 
+    fn register_stream_raw<R, F>(
+        &mut self,
+        method_name: &'static str,
+        notif_method_name: &'static str,
+        callback: F,
+    ) -> Result<&mut MethodCallback, RegisterMethodError>
+    where
+        F: (Fn(Params) -> Result<mpsc::Receiver<Result<R, ServerError>>, ServerError>)
+            + Send
+            + Sync
+            + 'static,
+        R: serde::Serialize + Send + 'static,
+    {
+        self.register_channel_raw(method_name, notif_method_name, move |params, pending| {
+            let receiver = callback(params);
+            tokio::spawn(async move {
+                let mut receiver = match receiver {
+                    Ok(receiver) => receiver,
+                    Err(e) => {
+                        pending.reject(e).await;
+                        return;
+                    }
+                };
+                let sink = pending.accept().await.unwrap();
+                tracing::debug!("Stream created: chann_id={}", sink.channel_id);
+
+                loop {
+                    tokio::select! {
+                        value = receiver.recv() => {
+                            let value = match value {
+                                Some(Ok(value)) => value,
+                                Some(Err(e)) => {
+                                    let close = close_with_error_payload(sink.channel_id(), e);
+                                    let _ = sink.send(close.to_string()).await;
+                                    break;
+                                }
+                                None => {
+                                    let _ = sink.send(close_payload(sink.channel_id()).to_string()).await;
+                                    break;
+                                }
+                            };
+                            match create_notif_message(&sink, &value) {
+                                Ok(msg) => {
+                                    // This fails only if the connection is closed
+                                    if sink.send(msg).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::error!("Failed to serialize stream message: {:?}", e);
+                                    break;
+                                }
+                            }
+                        },
+                        _ = sink.closed() => {
+                            break;
+                        }
+                    }
+                }
+
+                tracing::debug!("Stream task ended (chann_id={})", sink.channel_id);
+            });
+        })
+    }
+
     fn register_channel_raw<R, F>(
         &mut self,
         subscribe_method_name: &'static str,
+        notif_method_name: &'static str,
         callback: F,
     ) -> Result<&mut MethodCallback, RegisterMethodError>
     where
@@ -417,7 +635,7 @@ impl RpcModule {
 
                     let sink = PendingSubscriptionSink {
                         inner: method_sink.clone(),
-                        method: NOTIF_METHOD_NAME,
+                        method: notif_method_name,
                         subscribers: subscribers.clone(),
                         id: id.clone().into_owned(),
                         subscribe: tx,
@@ -4,6 +4,7 @@
 use crate::rpc::{ApiVersion, Ctx, Permission, RpcMethod, ServerError};
 use crate::{beacon::BeaconEntry, shim::clock::ChainEpoch};
 use anyhow::Result;
+use futures::{StreamExt as _, TryStreamExt as _};
 use fvm_ipld_blockstore::Blockstore;
 
 /// `BeaconGetEntry` returns the beacon entry for the given Filecoin epoch. If
@@ -30,3 +31,80 @@ impl RpcMethod<1> for BeaconGetEntry {
         Ok(e)
     }
 }
+
+/// Maximum number of epochs that can be requested by a single
+/// [`BeaconGetEntries`] call.
+const MAX_BEACON_ENTRIES_RANGE: ChainEpoch = 120;
+
+/// Maximum number of beacon entries fetched concurrently by a single
+/// [`BeaconGetEntries`] call.
+const BEACON_ENTRIES_CONCURRENCY: usize = 8;
+
+/// `BeaconGetEntries` returns the beacon entries for the Filecoin epochs in
+/// `[from, to]`, ordered by round. Epochs that map to the same drand round
+/// yield a single entry.
+pub enum BeaconGetEntries {}
+impl RpcMethod<2> for BeaconGetEntries {
+    const NAME: &'static str = "Filecoin.BeaconGetEntries";
+    const PARAM_NAMES: [&'static str; 2] = ["from", "to"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (ChainEpoch, ChainEpoch);
+    type Ok = Vec<BeaconEntry>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (from, to): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        check_epoch_range(from, to)?;
+        let mut fetches = vec![];
+        let mut last_round = None;
+        for epoch in from..=to {
+            let (_, beacon) = ctx.beacon.beacon_for_epoch(epoch)?;
+            let round = beacon
+                .max_beacon_round_for_epoch(ctx.state_manager.get_network_version(epoch), epoch);
+            if last_round == Some(round) {
+                continue;
+            }
+            last_round = Some(round);
+            fetches.push(beacon.entry(round));
+        }
+        let entries = futures::stream::iter(fetches)
+            .buffered(BEACON_ENTRIES_CONCURRENCY)
+            .try_collect()
+            .await?;
+        Ok(entries)
+    }
+}
+
+fn check_epoch_range(from: ChainEpoch, to: ChainEpoch) -> Result<(), ServerError> {
+    if from > to {
+        return Err(ServerError::invalid_params(
+            format!("invalid epoch range: {from} > {to}"),
+            None,
+        ));
+    }
+    if to - from >= MAX_BEACON_ENTRIES_RANGE {
+        return Err(ServerError::invalid_params(
+            format!(
+                "epoch range too large: at most {MAX_BEACON_ENTRIES_RANGE} epochs can be requested"
+            ),
+            None,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epoch_range_is_validated() {
+        check_epoch_range(10, 10).unwrap();
+        check_epoch_range(10, 10 + MAX_BEACON_ENTRIES_RANGE - 1).unwrap();
+        check_epoch_range(10, 9).unwrap_err();
+        check_epoch_range(10, 10 + MAX_BEACON_ENTRIES_RANGE).unwrap_err();
+    }
+}
@@ -7,6 +7,11 @@ use crate::blocks::{CachingBlockHeader, Tipset, TipsetKey};
 use crate::chain::index::ResolveNullTipset;
 use crate::chain::{ChainStore, HeadChange};
 use crate::cid_collections::CidHashSet;
+use crate::db::{GcHandle, GcStatus};
+use crate::ipld::{
+    path::{resolve_path, DEFAULT_HAMT_BIT_WIDTH},
+    stream_dag, Ipld,
+};
 #[cfg(test)]
 use crate::lotus_json::{assert_all_snapshots, assert_unchanged_via_json};
 use crate::lotus_json::{lotus_json_with_self, HasLotusJson, LotusJson};
@@ -15,15 +20,18 @@ use crate::rpc::types::ApiTipsetKey;
 use crate::rpc::{ApiVersion, Ctx, Permission, RpcMethod, ServerError};
 use crate::shim::clock::ChainEpoch;
 use crate::shim::error::ExitCode;
-use crate::shim::executor::Receipt;
+use crate::shim::executor::{get_events, Receipt, StampedEvent};
 use crate::shim::message::Message;
 use crate::utils::db::CborStoreExt as _;
 use crate::utils::io::VoidAsyncWriter;
 use anyhow::{Context as _, Result};
+use cid::multihash::{Code::Blake2b256, MultihashDigest as _};
 use cid::Cid;
+use futures::StreamExt as _;
 use fvm_ipld_blockstore::Blockstore;
-use fvm_ipld_encoding::{CborStore, RawBytes};
+use fvm_ipld_encoding::{CborStore, RawBytes, DAG_CBOR};
 use hex::ToHex;
+use itertools::Itertools as _;
 use jsonrpsee::types::error::ErrorObjectOwned;
 use jsonrpsee::types::Params;
 use num::BigInt;
@@ -35,7 +43,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{
     broadcast::{self, Receiver as Subscriber},
-    Mutex,
+    mpsc, Mutex,
 };
 
 pub enum ChainGetMessage {}
@@ -136,6 +144,28 @@ impl RpcMethod<1> for ChainGetParentReceipts {
     }
 }
 
+/// Returns the actor events stored in the AMT rooted at `events_root`, as
+/// referenced by a message receipt.
+pub enum ChainGetEvents {}
+impl RpcMethod<1> for ChainGetEvents {
+    const NAME: &'static str = "Filecoin.ChainGetEvents";
+    const PARAM_NAMES: [&'static str; 1] = ["events_root"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Cid,);
+    type Ok = Vec<StampedEvent>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (events_root,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let events = get_events(ctx.store(), &events_root)
+            .with_context(|| format!("failed to load events AMT at {events_root}"))?;
+        Ok(events)
+    }
+}
+
 pub enum ChainGetMessagesInTipset {}
 impl RpcMethod<1> for ChainGetMessagesInTipset {
     const NAME: &'static str = "Filecoin.ChainGetMessagesInTipset";
@@ -269,6 +299,86 @@ impl RpcMethod<1> for ChainHasObj {
     }
 }
 
+pub enum ChainStatObj {}
+impl RpcMethod<2> for ChainStatObj {
+    const NAME: &'static str = "Filecoin.ChainStatObj";
+    const PARAM_NAMES: [&'static str; 2] = ["obj_cid", "base_cid"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Cid, Option<Cid>);
+    type Ok = ObjStat;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (obj_cid, base_cid): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let store = ctx.store();
+        // Anything reachable from `base_cid` is excluded from the stats.
+        let mut seen = CidHashSet::default();
+        if let Some(base_cid) = base_cid {
+            let mut stream = stream_dag(store, base_cid);
+            while let Some(block) = stream.next().await {
+                block?;
+            }
+            seen = stream.into_seen();
+        }
+        let mut stats = ObjStat::default();
+        let mut stream = stream_dag(store, obj_cid).with_seen(seen);
+        while let Some(block) = stream.next().await {
+            let block = block?;
+            stats.links += 1;
+            stats.size += block.data.len();
+        }
+        Ok(stats)
+    }
+}
+
+pub enum ChainPutObj {}
+impl RpcMethod<1> for ChainPutObj {
+    const NAME: &'static str = "Filecoin.ChainPutObj";
+    const PARAM_NAMES: [&'static str; 1] = ["data"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Admin;
+
+    type Params = (Vec<u8>,);
+    type Ok = Cid;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (data,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        // The object is stored under a `DAG-CBOR` CID, so it has to be valid `DAG-CBOR`.
+        fvm_ipld_encoding::from_slice::<Ipld>(&data).context("object is not valid DAG-CBOR")?;
+        let cid = Cid::new_v1(DAG_CBOR, Blake2b256.digest(&data));
+        ctx.store().put_keyed(&cid, &data)?;
+        Ok(cid)
+    }
+}
+
+pub enum ChainGetNode {}
+impl RpcMethod<2> for ChainGetNode {
+    const NAME: &'static str = "Filecoin.ChainGetNode";
+    const PARAM_NAMES: [&'static str; 2] = ["path", "hamtBitWidth"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (String, Option<u32>);
+    type Ok = IpldObject;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (path, hamt_bit_width): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let (cid, obj) = resolve_path(
+            ctx.store(),
+            &path,
+            hamt_bit_width.unwrap_or(DEFAULT_HAMT_BIT_WIDTH),
+        )?;
+        Ok(IpldObject { cid, obj })
+    }
+}
+
 pub enum ChainGetBlockMessages {}
 impl RpcMethod<1> for ChainGetBlockMessages {
     const NAME: &'static str = "Filecoin.ChainGetBlockMessages";
@@ -338,7 +448,7 @@ impl RpcMethod<2> for ChainGetPath {
 /// ```
 ///
 /// Exposes errors from the [`Blockstore`], and returns an error if there is no common ancestor.
-fn impl_chain_get_path(
+pub(crate) fn impl_chain_get_path(
     chain_store: &ChainStore<impl Blockstore>,
     from: &TipsetKey,
     to: &TipsetKey,
@@ -549,6 +659,61 @@ impl RpcMethod<1> for ChainSetHead {
     }
 }
 
+/// Starts a garbage collection run immediately.
+pub enum ChainPrune {}
+impl RpcMethod<0> for ChainPrune {
+    const NAME: &'static str = "Forest.ChainPrune";
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Admin;
+
+    type Params = ();
+    type Ok = ();
+
+    async fn handle(ctx: Ctx<impl Blockstore>, (): Self::Params) -> Result<Self::Ok, ServerError> {
+        Ok(gc_handle(&ctx)?.start()?)
+    }
+}
+
+/// Returns the progress of the current, or last, garbage collection run.
+pub enum ChainPruneStatus {}
+impl RpcMethod<0> for ChainPruneStatus {
+    const NAME: &'static str = "Forest.ChainPruneStatus";
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Admin;
+
+    type Params = ();
+    type Ok = GcStatus;
+
+    async fn handle(ctx: Ctx<impl Blockstore>, (): Self::Params) -> Result<Self::Ok, ServerError> {
+        Ok(gc_handle(&ctx)?.status())
+    }
+}
+
+/// Cancels the current garbage collection run, unless it already reached the
+/// sweep phase.
+pub enum ChainPruneCancel {}
+impl RpcMethod<0> for ChainPruneCancel {
+    const NAME: &'static str = "Forest.ChainPruneCancel";
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Admin;
+
+    type Params = ();
+    type Ok = ();
+
+    async fn handle(ctx: Ctx<impl Blockstore>, (): Self::Params) -> Result<Self::Ok, ServerError> {
+        Ok(gc_handle(&ctx)?.cancel()?)
+    }
+}
+
+fn gc_handle<DB>(ctx: &Ctx<DB>) -> anyhow::Result<&GcHandle> {
+    ctx.gc
+        .as_ref()
+        .context("garbage collection is disabled on this node")
+}
+
 pub enum ChainGetMinBaseFee {}
 impl RpcMethod<1> for ChainGetMinBaseFee {
     const NAME: &'static str = "Filecoin.ChainGetMinBaseFee";
@@ -644,6 +809,100 @@ pub(crate) fn chain_notify<DB: Blockstore>(
     receiver
 }
 
+pub const CHAIN_NOTIFY_FROM: &str = "Forest.ChainNotifyFrom";
+/// Maximum number of head changes sent in a single notification while
+/// replaying missed head changes.
+const CHAIN_NOTIFY_REPLAY_BATCH_SIZE: usize = 100;
+/// Number of notifications buffered before the producer waits for the client.
+const CHAIN_NOTIFY_FROM_CAPACITY: usize = 16;
+
+/// Resumable variant of [`CHAIN_NOTIFY`]. The client passes the key of the
+/// last tipset it has seen. The reverts and applies that lead from that tipset
+/// to the current head are replayed first, as computed by
+/// [`impl_chain_get_path`], followed by live updates. Every update is the
+/// path from the previously notified head, so no reorg is skipped, even if the
+/// head advanced several times in between.
+///
+/// Without a tipset key, the current head is sent first, as in
+/// [`CHAIN_NOTIFY`].
+pub(crate) fn chain_notify_from<DB: Blockstore + Send + Sync + 'static>(
+    params: Params<'_>,
+    ctx: &Ctx<DB>,
+) -> Result<mpsc::Receiver<Result<Vec<ApiHeadChange>, ServerError>>, ServerError> {
+    let mut params = params.sequence();
+    let from: Option<LotusJson<TipsetKey>> = params.optional_next()?;
+    Ok(notify_head_changes_from(
+        ctx.chain_store.clone(),
+        from.map(LotusJson::into_inner),
+    )?)
+}
+
+/// Streams the head changes since `from` for [`chain_notify_from`], in
+/// batches of at most [`CHAIN_NOTIFY_REPLAY_BATCH_SIZE`].
+fn notify_head_changes_from<DB: Blockstore + Send + Sync + 'static>(
+    chain_store: Arc<ChainStore<DB>>,
+    from: Option<TipsetKey>,
+) -> anyhow::Result<mpsc::Receiver<Result<Vec<ApiHeadChange>, ServerError>>> {
+    // Subscribe before computing the replay, so that no head change is missed
+    // in between.
+    let mut subscriber = chain_store.publisher().subscribe();
+    let head = chain_store.heaviest_tipset();
+    let replay = match &from {
+        Some(from) => impl_chain_get_path(&chain_store, from, head.key())?
+            .into_iter()
+            .map(ApiHeadChange::from)
+            .collect(),
+        None => vec![ApiHeadChange {
+            change: "current".into(),
+            tipset: head.as_ref().clone(),
+        }],
+    };
+
+    let (sender, receiver) = mpsc::channel(CHAIN_NOTIFY_FROM_CAPACITY);
+    tokio::spawn(async move {
+        for changes in replay.chunks(CHAIN_NOTIFY_REPLAY_BATCH_SIZE) {
+            // Fails only if the client went away.
+            if sender.send(Ok(changes.to_vec())).await.is_err() {
+                return;
+            }
+        }
+
+        let mut last = head;
+        loop {
+            let next = match subscriber.recv().await {
+                Ok(HeadChange::Apply(ts)) => ts,
+                // Missed notifications are recovered below, as the path is
+                // computed from the last notified head. The heads still
+                // buffered are older than the current one, so they are
+                // dropped rather than replayed as reverts.
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    subscriber = subscriber.resubscribe();
+                    chain_store.heaviest_tipset()
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            };
+            let changes = match impl_chain_get_path(&chain_store, last.key(), next.key()) {
+                Ok(changes) => changes,
+                Err(e) => {
+                    let _ = sender.send(Err(e.into())).await;
+                    return;
+                }
+            };
+            last = next;
+            if changes.is_empty() {
+                continue;
+            }
+            let changes = changes.into_iter().map(ApiHeadChange::from).collect_vec();
+            for changes in changes.chunks(CHAIN_NOTIFY_REPLAY_BATCH_SIZE) {
+                if sender.send(Ok(changes.to_vec())).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    Ok(receiver)
+}
+
 fn load_api_messages_from_tipset(
     store: &impl Blockstore,
     tipset: &Tipset,
@@ -693,6 +952,26 @@ pub struct BlockMessages {
 }
 lotus_json_with_self!(BlockMessages);
 
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjStat {
+    pub size: usize,
+    pub links: usize,
+}
+lotus_json_with_self!(ObjStat);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct IpldObject {
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<Cid>")]
+    pub cid: Cid,
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<Ipld>")]
+    pub obj: Ipld,
+}
+lotus_json_with_self!(IpldObject);
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 pub struct ApiReceipt {
@@ -748,6 +1027,19 @@ pub struct ApiHeadChange {
 }
 lotus_json_with_self!(ApiHeadChange);
 
+impl From<PathChange> for ApiHeadChange {
+    fn from(change: PathChange) -> Self {
+        let (change, tipset) = match change {
+            PathChange::Revert(tipset) => ("revert", tipset),
+            PathChange::Apply(tipset) => ("apply", tipset),
+        };
+        Self {
+            change: change.into(),
+            tipset: tipset.as_ref().clone(),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(tag = "Type", content = "Val", rename_all = "snake_case")]
 pub enum PathChange<T = Arc<Tipset>> {
@@ -838,7 +1130,7 @@ mod tests {
     use PathChange::{Apply, Revert};
 
     use crate::{
-        blocks::{chain4u, Chain4U, RawBlockHeader},
+        blocks::{chain4u, Chain4U, HeaderBuilder, RawBlockHeader},
         db::{car::PlainCar, MemoryDB},
         networks::{self, ChainConfig},
     };
@@ -951,6 +1243,137 @@ mod tests {
         let _ = (a, c1);
     }
 
+    #[tokio::test]
+    async fn notify_from_stale_key_across_revert() {
+        let store = Arc::new(ChainStore::calibnet());
+        chain4u! {
+            in store.blockstore();
+            [_genesis = store.genesis_block_header()]
+            -> [_a] -> [b1] -> [c1]
+        };
+        chain4u! {
+            from [_a] in store.blockstore();
+            [b2] -> [c2]
+        };
+        store
+            .set_heaviest_tipset(Arc::new(c2.make_tipset()))
+            .unwrap();
+
+        // the client last saw `b1`, which has been reverted since
+        let mut receiver =
+            notify_head_changes_from(store.clone(), Some(b1.make_tipset().key().clone())).unwrap();
+        assert_head_changes(
+            &receiver.recv().await.unwrap().unwrap(),
+            [("revert", b1), ("apply", b2), ("apply", c2)],
+        );
+
+        // live updates carry the reverts too
+        store
+            .set_heaviest_tipset(Arc::new(c1.make_tipset()))
+            .unwrap();
+        assert_head_changes(
+            &receiver.recv().await.unwrap().unwrap(),
+            [("revert", c2), ("revert", b2), ("apply", b1), ("apply", c1)],
+        );
+    }
+
+    #[tokio::test]
+    async fn notify_from_replay_is_chunked() {
+        let store = Arc::new(ChainStore::calibnet());
+        chain4u! {
+            in store.blockstore();
+            [_genesis = store.genesis_block_header()]
+            -> [a]
+        };
+        let headers = extend_chain(&store, "a", 250);
+        store
+            .set_heaviest_tipset(Arc::new(headers.last().unwrap().make_tipset()))
+            .unwrap();
+
+        let mut receiver =
+            notify_head_changes_from(store.clone(), Some(a.make_tipset().key().clone())).unwrap();
+        for chunk in headers.chunks(CHAIN_NOTIFY_REPLAY_BATCH_SIZE) {
+            assert_head_changes(
+                &receiver.recv().await.unwrap().unwrap(),
+                chunk.iter().map(|it| ("apply", it)),
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn notify_from_recovers_after_lag() {
+        let store = Arc::new(ChainStore::calibnet());
+        chain4u! {
+            in store.blockstore();
+            [_genesis = store.genesis_block_header()]
+            -> [a]
+        };
+        store
+            .set_heaviest_tipset(Arc::new(a.make_tipset()))
+            .unwrap();
+        // more head changes than the publisher buffers
+        let mut headers = extend_chain(&store, "a", 351);
+        let last = headers.pop().unwrap();
+
+        let mut receiver = notify_head_changes_from(store.clone(), None).unwrap();
+        // The test runtime is single-threaded, so the notifying task does not
+        // run until the receiver is polled, and lags behind the publisher.
+        for header in &headers {
+            store
+                .set_heaviest_tipset(Arc::new(header.make_tipset()))
+                .unwrap();
+        }
+
+        assert_head_changes(&receiver.recv().await.unwrap().unwrap(), [("current", a)]);
+        for chunk in headers.chunks(CHAIN_NOTIFY_REPLAY_BATCH_SIZE) {
+            assert_head_changes(
+                &receiver.recv().await.unwrap().unwrap(),
+                chunk.iter().map(|it| ("apply", it)),
+            );
+        }
+
+        // the head changes that were buffered when lagging are not replayed
+        store
+            .set_heaviest_tipset(Arc::new(last.make_tipset()))
+            .unwrap();
+        assert_head_changes(&receiver.recv().await.unwrap().unwrap(), [("apply", &last)]);
+    }
+
+    /// Appends `len` single-block tipsets to the block named `parent`.
+    fn extend_chain(
+        store: &ChainStore<Chain4U<PlainCar<&'static [u8]>>>,
+        parent: &str,
+        len: usize,
+    ) -> Vec<RawBlockHeader> {
+        let mut parent = parent.to_owned();
+        (0..len)
+            .map(|i| {
+                let name = format!("{parent}-{i}");
+                let header = store
+                    .blockstore()
+                    .insert(&[&parent], &name, HeaderBuilder::new());
+                parent = name;
+                header
+            })
+            .collect()
+    }
+
+    #[track_caller]
+    fn assert_head_changes<'a>(
+        actual: &[ApiHeadChange],
+        expected: impl IntoIterator<Item = (&'a str, &'a RawBlockHeader)>,
+    ) {
+        let actual = actual
+            .iter()
+            .map(|it| (it.change.as_str(), it.tipset.key().clone()))
+            .collect::<Vec<_>>();
+        let expected = expected
+            .into_iter()
+            .map(|(change, header)| (change, header.make_tipset().key().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, actual);
+    }
+
     impl ChainStore<Chain4U<PlainCar<&'static [u8]>>> {
         fn _load(genesis_car: &'static [u8], genesis_cid: Cid) -> Self {
             let db = Arc::new(Chain4U::with_blockstore(
@@ -961,6 +1384,7 @@ mod tests {
                 db,
                 Arc::new(MemoryDB::default()),
                 Arc::new(MemoryDB::default()),
+                Arc::new(MemoryDB::default()),
                 Arc::new(ChainConfig::calibnet()),
                 genesis_block_header,
             )
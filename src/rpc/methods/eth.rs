@@ -1,39 +1,49 @@
 // Copyright 2019-2024 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+pub mod filter;
+pub mod pubsub;
+pub mod trace;
 pub mod types;
 
+use self::filter::{EthFilterResult, EthFilterSpec, FilterID};
+use self::trace::{EthBlockTrace, EthTraceReplayBlockTransaction};
 use self::types::*;
 use super::gas;
 use crate::blocks::Tipset;
 use crate::chain::{index::ResolveNullTipset, ChainStore};
 use crate::chain_sync::SyncStage;
 use crate::cid_collections::CidHashSet;
+use crate::interpreter::VMTrace;
 use crate::lotus_json::LotusJson;
 use crate::lotus_json::{lotus_json_with_self, HasLotusJson};
 use crate::message::{ChainMessage, Message as _, SignedMessage};
 use crate::rpc::error::ServerError;
+use crate::rpc::state::{ApiInvocResult, ExecutionTrace};
+use crate::rpc::types::ApiTipsetKey;
 use crate::rpc::{ApiVersion, Ctx, Permission, RpcMethod};
 use crate::shim::address::{Address as FilecoinAddress, Protocol};
 use crate::shim::crypto::{Signature, SignatureType};
 use crate::shim::econ::{TokenAmount, BLOCK_GAS_LIMIT};
-use crate::shim::executor::Receipt;
+use crate::shim::error::ExitCode;
+use crate::shim::executor::{Entry, Receipt};
 use crate::shim::fvm_shared_latest::address::{Address as VmAddress, DelegatedAddress};
 use crate::shim::fvm_shared_latest::MethodNum;
 use crate::shim::message::Message;
 use crate::shim::{clock::ChainEpoch, state_tree::StateTree};
 use crate::utils::db::BlockstoreExt as _;
-use anyhow::{bail, Result};
+use anyhow::{bail, Context as _, Result};
 use bytes::{Buf, BytesMut};
 use cbor4ii::core::{dec::Decode, utils::SliceReader, Value};
 use cid::Cid;
+use fil_actor_interface::evm;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::{RawBytes, CBOR, DAG_CBOR, IPLD_RAW};
 use itertools::Itertools;
 use keccak_hash::keccak;
 use num_bigint::{self, Sign};
 use num_traits::{Signed as _, Zero as _};
-use rlp::RlpStream;
+use rlp::{Rlp, RlpStream};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{fmt, str::FromStr};
@@ -57,6 +67,12 @@ const ADDRESS_LENGTH: usize = 20;
 /// Ethereum Virtual Machine word size in bytes.
 const EVM_WORD_LENGTH: usize = 32;
 
+/// Multicodec of empty payloads, e.g., of the parameters of a call without any.
+const IDENTITY: u64 = 0x00;
+
+/// Length of a delegated (`r || s || v`) signature in bytes.
+const SIGNATURE_LENGTH: usize = 65;
+
 /// Keccak-256 of an RLP of an empty array.
 /// In Filecoin, we don't have the concept of uncle blocks but rather use tipsets to reward miners
 /// who craft blocks.
@@ -75,8 +91,20 @@ const EIP_1559_TX_TYPE: u64 = 2;
 /// The address used in messages to actors that have since been deleted.
 const REVERTED_ETH_ADDRESS: &str = "0xff0000000000000000000000ffffffffffffffff";
 
+/// JSON-RPC error code used by Ethereum clients (e.g., Geth) to signal that the
+/// execution reverted. The revert data is attached to the error.
+const EXECUTION_REVERTED_ERROR_CODE: i32 = 3;
+
+/// Solidity selector of `Panic(uint256)`.
+const PANIC_FUNCTION_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Solidity selector of `Error(string)`.
+const ERROR_FUNCTION_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
 #[repr(u64)]
 enum EAMMethod {
+    Create = 2,
+    Create2 = 3,
     CreateExternal = 4,
 }
 
@@ -86,6 +114,8 @@ enum EVMMethod {
     // number.
     // InvokeContract = frc42_dispatch::method_hash!("InvokeEVM"),
     InvokeContract = 3844450837,
+    Resurrect = 2,
+    InvokeContractDelegate = 6,
 }
 
 #[derive(PartialEq, Debug, Deserialize, Serialize, Default, Clone, JsonSchema)]
@@ -151,6 +181,17 @@ impl Hash {
     pub fn empty_root() -> Self {
         Self(ethereum_types::H256::from_str(EMPTY_ROOT).unwrap())
     }
+
+    /// Converts the hash back into a `DAG_CBOR` `Blake2b256` CID. This is the inverse of
+    /// `From<Cid>`, and is how native Filecoin messages and tipsets are addressed.
+    pub fn to_cid(&self) -> Cid {
+        let mh = cid::multihash::Multihash::wrap(
+            cid::multihash::Code::Blake2b256.into(),
+            self.0.as_bytes(),
+        )
+        .expect("a 32-byte digest fits in a multihash");
+        Cid::new_v1(DAG_CBOR, mh)
+    }
 }
 
 impl FromStr for Hash {
@@ -342,6 +383,48 @@ impl Tx {
 
 lotus_json_with_self!(Tx);
 
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthLog {
+    pub address: EthAddress,
+    pub data: EthBytes,
+    pub topics: Vec<Hash>,
+    pub removed: bool,
+    pub log_index: Uint64,
+    pub transaction_index: Uint64,
+    pub transaction_hash: Hash,
+    pub block_hash: Hash,
+    pub block_number: Uint64,
+}
+
+lotus_json_with_self!(EthLog);
+
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthTxReceipt {
+    pub transaction_hash: Hash,
+    pub transaction_index: Uint64,
+    pub block_hash: Hash,
+    pub block_number: Uint64,
+    pub from: EthAddress,
+    #[schemars(with = "Option<EthAddress>")]
+    #[serde(with = "crate::lotus_json", default)]
+    pub to: Option<EthAddress>,
+    pub root: Hash,
+    pub status: Uint64,
+    #[schemars(with = "Option<EthAddress>")]
+    #[serde(with = "crate::lotus_json", default)]
+    pub contract_address: Option<EthAddress>,
+    pub cumulative_gas_used: Uint64,
+    pub gas_used: Uint64,
+    pub effective_gas_price: BigInt,
+    pub logs_bloom: Bloom,
+    pub logs: Vec<EthLog>,
+    pub r#type: Uint64,
+}
+
+lotus_json_with_self!(EthTxReceipt);
+
 #[derive(PartialEq, Debug, Clone, Default)]
 struct TxArgs {
     pub chain_id: u64,
@@ -414,11 +497,39 @@ impl TxArgs {
         Ok(Hash(keccak(self.rlp_signed_message()?)))
     }
 
+    pub fn rlp_unsigned_message(&self) -> Result<Vec<u8>> {
+        // The signature fields are left out of the unsigned payload.
+        const MSG_ITEMS: usize = 9;
+
+        let stream = self.rlp_stream(MSG_ITEMS)?;
+        Ok(with_tx_type_prefix(&stream.out()))
+    }
+
     pub fn rlp_signed_message(&self) -> Result<Vec<u8>> {
         // An item is either an item list or bytes.
         const MSG_ITEMS: usize = 12;
 
-        let mut stream = RlpStream::new_list(MSG_ITEMS);
+        let mut stream = self.rlp_stream(MSG_ITEMS)?;
+        stream.append(&format_bigint(&self.v)?);
+        stream.append(&format_bigint(&self.r)?);
+        stream.append(&format_bigint(&self.s)?);
+
+        let bytes = with_tx_type_prefix(&stream.out());
+
+        let hex = bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join("");
+        tracing::trace!("rlp: {}", &hex);
+
+        Ok(bytes)
+    }
+
+    /// Starts an RLP list of `items` elements and appends the fields shared by the signed and
+    /// unsigned payloads.
+    fn rlp_stream(&self, items: usize) -> Result<RlpStream> {
+        let mut stream = RlpStream::new_list(items);
         stream.append(&format_u64(self.chain_id));
         stream.append(&format_u64(self.nonce));
         stream.append(&format_bigint(&self.max_priority_fee_per_gas)?);
@@ -429,24 +540,175 @@ impl TxArgs {
         stream.append(&self.input);
         let access_list: &[u8] = &[];
         stream.append_list(access_list);
+        Ok(stream)
+    }
 
-        stream.append(&format_bigint(&self.v)?);
-        stream.append(&format_bigint(&self.r)?);
-        stream.append(&format_bigint(&self.s)?);
+    /// Returns the 65-byte `r || s || v` signature, as carried by delegated Filecoin signatures.
+    fn signature(&self) -> Result<Signature> {
+        let mut sig = Vec::with_capacity(SIGNATURE_LENGTH);
+        sig.extend_from_slice(&pad_to_word(&self.r)?);
+        sig.extend_from_slice(&pad_to_word(&self.s)?);
+        let v = format_bigint(&self.v)?;
+        if v.len() > 1 {
+            bail!("signature v value is out of range: {}", self.v.0);
+        }
+        sig.push(v.first().copied().unwrap_or_default());
+        Ok(Signature::new(SignatureType::Delegated, sig))
+    }
+
+    /// Recovers the `f410` address of the account that signed the transaction.
+    fn sender(&self) -> Result<FilecoinAddress> {
+        let sig = self.signature()?;
+        let (rs, v) = sig.bytes().split_at(SIGNATURE_LENGTH - 1);
+        let digest = keccak(self.rlp_unsigned_message()?);
+        let message = libsecp256k1::Message::parse(&digest.0);
+        let signature = libsecp256k1::Signature::parse_standard_slice(rs)
+            .map_err(|e| anyhow::anyhow!("invalid signature: {e}"))?;
+        let recovery_id = libsecp256k1::RecoveryId::parse(v.first().copied().unwrap_or_default())
+            .map_err(|e| anyhow::anyhow!("invalid recovery id: {e}"))?;
+        let pubkey = libsecp256k1::recover(&message, &signature, &recovery_id)
+            .map_err(|e| anyhow::anyhow!("failed to recover public key: {e}"))?;
+        // The Ethereum address is the last 20 bytes of the Keccak-256 of the uncompressed public
+        // key, without its `0x04` prefix.
+        let hash = keccak(&pubkey.serialize()[1..]);
+        EthAddress::try_from(&hash.0[EVM_WORD_LENGTH - ADDRESS_LENGTH..])?.to_filecoin_address()
+    }
+
+    /// Converts the transaction into the Filecoin message that gets included on chain.
+    fn to_unsigned_message(&self, from: FilecoinAddress) -> Result<Message> {
+        let params = EthCallMessage::convert_data_to_message_params(EthBytes(self.input.clone()))?;
+        let (to, method_num) = if let Some(to) = &self.to {
+            (
+                to.to_filecoin_address()?,
+                EVMMethod::InvokeContract as MethodNum,
+            )
+        } else {
+            (
+                FilecoinAddress::ETHEREUM_ACCOUNT_MANAGER_ACTOR,
+                EAMMethod::CreateExternal as MethodNum,
+            )
+        };
+        Ok(Message {
+            version: 0,
+            from,
+            to,
+            sequence: self.nonce,
+            value: TokenAmount::from_atto(self.value.0.clone()),
+            method_num,
+            params,
+            gas_limit: self.gas_limit,
+            gas_fee_cap: TokenAmount::from_atto(self.max_fee_per_gas.0.clone()),
+            gas_premium: TokenAmount::from_atto(self.max_priority_fee_per_gas.0.clone()),
+        })
+    }
+
+    /// Converts the transaction into a signed Filecoin message, checking that it was signed for
+    /// the given chain.
+    pub fn to_signed_message(&self, chain_id: u32) -> Result<SignedMessage> {
+        if self.chain_id != chain_id as u64 {
+            bail!(
+                "invalid chain id: expected {chain_id}, got {}",
+                self.chain_id
+            );
+        }
+        let from = self.sender()?;
+        Ok(SignedMessage::new_unchecked(
+            self.to_unsigned_message(from)?,
+            self.signature()?,
+        ))
+    }
+}
 
-        let mut rlp = stream.out()[..].to_vec();
-        let mut bytes: Vec<u8> = vec![0x02];
-        bytes.append(&mut rlp);
+fn with_tx_type_prefix(rlp: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(rlp.len() + 1);
+    bytes.push(EIP_1559_TX_TYPE as u8);
+    bytes.extend_from_slice(rlp);
+    bytes
+}
 
-        let hex = bytes
-            .iter()
-            .map(|b| format!("{:02x}", b))
-            .collect::<Vec<_>>()
-            .join("");
-        tracing::trace!("rlp: {}", &hex);
+fn pad_to_word(value: &BigInt) -> Result<[u8; EVM_WORD_LENGTH]> {
+    let bytes = format_bigint(value)?;
+    if bytes.len() > EVM_WORD_LENGTH {
+        bail!("value does not fit in {EVM_WORD_LENGTH} bytes: {}", value.0);
+    }
+    let mut word = [0; EVM_WORD_LENGTH];
+    if let Some(tail) = word.get_mut(EVM_WORD_LENGTH - bytes.len()..) {
+        tail.copy_from_slice(&bytes);
+    }
+    Ok(word)
+}
 
-        Ok(bytes)
+fn parse_rlp_u64(rlp: &Rlp, index: usize) -> Result<u64> {
+    Ok(rlp.val_at::<u64>(index)?)
+}
+
+fn parse_rlp_bigint(rlp: &Rlp, index: usize) -> Result<BigInt> {
+    let bytes = rlp.at(index)?.data()?;
+    if bytes.len() > EVM_WORD_LENGTH {
+        bail!("integer at index {index} is larger than {EVM_WORD_LENGTH} bytes");
+    }
+    if bytes.first() == Some(&0) {
+        bail!("integer at index {index} has leading zeros");
+    }
+    Ok(BigInt(num_bigint::BigInt::from_bytes_be(Sign::Plus, bytes)))
+}
+
+/// Parses a signed, RLP-encoded Ethereum transaction. Only EIP-1559 transactions are supported.
+///
+/// See <https://eips.ethereum.org/EIPS/eip-1559>
+fn parse_eth_transaction(data: &[u8]) -> Result<TxArgs> {
+    let Some((&tx_type, payload)) = data.split_first() else {
+        bail!("empty transaction");
+    };
+    if tx_type > 0x7f {
+        bail!("legacy transactions are not supported");
+    }
+    if tx_type as u64 != EIP_1559_TX_TYPE {
+        bail!("unsupported transaction type: {tx_type}");
+    }
+
+    // An item is either an item list or bytes.
+    const MSG_ITEMS: usize = 12;
+
+    let rlp = Rlp::new(payload);
+    let info = rlp.payload_info()?;
+    if info.header_len + info.value_len != payload.len() {
+        bail!("unexpected trailing data after the transaction");
+    }
+    if !rlp.is_list() || rlp.item_count()? != MSG_ITEMS {
+        bail!("not an EIP-1559 transaction: should have {MSG_ITEMS} elements in the rlp list");
+    }
+
+    let to = rlp.at(5)?.data()?;
+    let to = if to.is_empty() {
+        None
+    } else {
+        Some(EthAddress::try_from(to)?)
+    };
+
+    let access_list = rlp.at(8)?;
+    if !access_list.is_list() || access_list.item_count()? != 0 {
+        bail!("access list should be an empty list");
     }
+
+    let v = parse_rlp_bigint(&rlp, 9)?;
+    if !v.0.is_zero() && v.0 != num_bigint::BigInt::from(1) {
+        bail!("EIP-1559 transactions only support 0 or 1 for v");
+    }
+
+    Ok(TxArgs {
+        chain_id: parse_rlp_u64(&rlp, 0)?,
+        nonce: parse_rlp_u64(&rlp, 1)?,
+        max_priority_fee_per_gas: parse_rlp_bigint(&rlp, 2)?,
+        max_fee_per_gas: parse_rlp_bigint(&rlp, 3)?,
+        gas_limit: parse_rlp_u64(&rlp, 4)?,
+        to,
+        value: parse_rlp_bigint(&rlp, 6)?,
+        input: rlp.at(7)?.data()?.to_vec(),
+        v,
+        r: parse_rlp_bigint(&rlp, 10)?,
+        s: parse_rlp_bigint(&rlp, 11)?,
+    })
 }
 
 #[derive(Debug, Clone, Default)]
@@ -529,6 +791,7 @@ impl HasLotusJson for EthSyncingResult {
 pub enum Web3ClientVersion {}
 impl RpcMethod<0> for Web3ClientVersion {
     const NAME: &'static str = "Filecoin.Web3ClientVersion";
+    const NAME_ALIAS: Option<&'static str> = Some("web3_clientVersion");
     const PARAM_NAMES: [&'static str; 0] = [];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -547,6 +810,7 @@ impl RpcMethod<0> for Web3ClientVersion {
 pub enum EthAccounts {}
 impl RpcMethod<0> for EthAccounts {
     const NAME: &'static str = "Filecoin.EthAccounts";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_accounts");
     const PARAM_NAMES: [&'static str; 0] = [];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -566,6 +830,7 @@ impl RpcMethod<0> for EthAccounts {
 pub enum EthBlockNumber {}
 impl RpcMethod<0> for EthBlockNumber {
     const NAME: &'static str = "Filecoin.EthBlockNumber";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_blockNumber");
     const PARAM_NAMES: [&'static str; 0] = [];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -600,6 +865,7 @@ impl RpcMethod<0> for EthBlockNumber {
 pub enum EthChainId {}
 impl RpcMethod<0> for EthChainId {
     const NAME: &'static str = "Filecoin.EthChainId";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_chainId");
     const PARAM_NAMES: [&'static str; 0] = [];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -621,6 +887,7 @@ impl RpcMethod<0> for EthChainId {
 pub enum EthGasPrice {}
 impl RpcMethod<0> for EthGasPrice {
     const NAME: &'static str = "Filecoin.EthGasPrice";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_gasPrice");
     const PARAM_NAMES: [&'static str; 0] = [];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -644,9 +911,184 @@ impl RpcMethod<0> for EthGasPrice {
     }
 }
 
+/// Maximum number of tipsets a single `EthFeeHistory` request can cover.
+const MAX_FEE_HISTORY_BLOCK_COUNT: u64 = 1024;
+/// Maximum number of reward percentiles a single `EthFeeHistory` request can ask for.
+const MAX_FEE_HISTORY_REWARD_PERCENTILES: usize = 100;
+
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthFeeHistoryResult {
+    pub oldest_block: Uint64,
+    pub base_fee_per_gas: Vec<BigInt>,
+    pub gas_used_ratio: Vec<f64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reward: Option<Vec<Vec<BigInt>>>,
+}
+lotus_json_with_self!(EthFeeHistoryResult);
+
+pub enum EthFeeHistory {}
+impl RpcMethod<3> for EthFeeHistory {
+    const NAME: &'static str = "Filecoin.EthFeeHistory";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_feeHistory");
+    const PARAM_NAMES: [&'static str; 3] =
+        ["block_count", "newest_block_number", "reward_percentiles"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Uint64, BlockNumberOrHash, Option<Vec<f64>>);
+    type Ok = EthFeeHistoryResult;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (Uint64(block_count), newest_block, reward_percentiles): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        if block_count > MAX_FEE_HISTORY_BLOCK_COUNT {
+            return Err(anyhow::anyhow!(
+                "block count should be smaller than {MAX_FEE_HISTORY_BLOCK_COUNT}"
+            )
+            .into());
+        }
+        if let Some(reward_percentiles) = &reward_percentiles {
+            validate_reward_percentiles(reward_percentiles)?;
+        }
+        let percentiles = reward_percentiles.as_deref().unwrap_or_default();
+
+        let newest = tipset_by_block_number_or_hash(&ctx.chain_store, newest_block)?;
+        // Clients expect the base fee of the block following the newest one as well. Due to the
+        // deferred execution of Filecoin, it cannot be inferred from the newest tipset, so the
+        // last base fee is repeated, as Lotus does.
+        let mut oldest_block = 1;
+        let mut base_fee_per_gas = vec![newest
+            .block_headers()
+            .first()
+            .parent_base_fee
+            .clone()
+            .into()];
+        let mut gas_used_ratio = vec![];
+        let mut reward = vec![];
+        let mut ts = newest;
+        while (gas_used_ratio.len() as u64) < block_count && ts.epoch() > 0 {
+            let base_fee = ts.block_headers().first().parent_base_fee.clone();
+            let (_, msgs_and_receipts) = execute_tipset(ctx.clone(), &ts).await?;
+            let gas_rewards = msgs_and_receipts
+                .iter()
+                .map(|(msg, receipt)| (effective_gas_premium(msg, &base_fee), receipt.gas_used()))
+                .collect();
+            let (rewards, total_gas_used) =
+                calculate_rewards_and_gas_used(percentiles, gas_rewards);
+            let max_gas = BLOCK_GAS_LIMIT * ts.block_headers().len() as u64;
+
+            base_fee_per_gas.push(base_fee.into());
+            gas_used_ratio.push(total_gas_used as f64 / max_gas as f64);
+            reward.push(rewards);
+            oldest_block = ts.epoch() as u64;
+
+            ts = ctx
+                .chain_store
+                .chain_index
+                .load_required_tipset(ts.parents())?;
+        }
+
+        // The history is collected from the newest tipset to the oldest one, clients expect the
+        // opposite order.
+        base_fee_per_gas.reverse();
+        gas_used_ratio.reverse();
+        reward.reverse();
+
+        Ok(EthFeeHistoryResult {
+            oldest_block: Uint64(oldest_block),
+            base_fee_per_gas,
+            gas_used_ratio,
+            reward: reward_percentiles.map(|_| reward),
+        })
+    }
+}
+
+fn validate_reward_percentiles(reward_percentiles: &[f64]) -> anyhow::Result<()> {
+    if reward_percentiles.len() > MAX_FEE_HISTORY_REWARD_PERCENTILES {
+        bail!(
+            "length of the reward percentile array cannot be greater than {MAX_FEE_HISTORY_REWARD_PERCENTILES}"
+        );
+    }
+    let mut previous = 0.0;
+    for &percentile in reward_percentiles {
+        if !(0.0..=100.0).contains(&percentile) {
+            bail!("invalid reward percentile: {percentile} should be between 0 and 100");
+        }
+        if percentile < previous {
+            bail!("invalid reward percentile: {percentile} should be larger than {previous}");
+        }
+        previous = percentile;
+    }
+    Ok(())
+}
+
+/// Premium per gas unit the miner actually receives for a message, given the base fee it was
+/// executed with. This can be negative when the fee cap does not cover the base fee.
+fn effective_gas_premium(msg: &ChainMessage, base_fee: &TokenAmount) -> TokenAmount {
+    let available = msg.gas_fee_cap() - base_fee;
+    msg.gas_premium().min(available)
+}
+
+/// Computes the premium paid at each of the (ascending) `reward_percentiles` of the gas used by
+/// `gas_rewards`, a list of `(effective premium, gas used)` pairs, and the total gas used.
+fn calculate_rewards_and_gas_used(
+    reward_percentiles: &[f64],
+    mut gas_rewards: Vec<(TokenAmount, u64)>,
+) -> (Vec<BigInt>, u64) {
+    let total_gas_used = gas_rewards
+        .iter()
+        .map(|(_, gas_used)| gas_used)
+        .sum::<u64>();
+    let min_premium = TokenAmount::from_atto(gas::MIN_GAS_PREMIUM as u64);
+    gas_rewards.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut rewards = Vec::with_capacity(reward_percentiles.len());
+    let mut idx = 0;
+    let mut sum = 0;
+    for percentile in reward_percentiles {
+        let threshold = (total_gas_used as f64 * percentile / 100.0) as u64;
+        while sum < threshold && idx + 1 < gas_rewards.len() {
+            sum += gas_rewards
+                .get(idx)
+                .map(|(_, gas_used)| *gas_used)
+                .unwrap_or_default();
+            idx += 1;
+        }
+        let premium = gas_rewards
+            .get(idx)
+            .map(|(premium, _)| premium.clone())
+            .unwrap_or_else(|| min_premium.clone());
+        rewards.push(premium.into());
+    }
+    (rewards, total_gas_used)
+}
+
+pub enum EthMaxPriorityFeePerGas {}
+impl RpcMethod<0> for EthMaxPriorityFeePerGas {
+    const NAME: &'static str = "Filecoin.EthMaxPriorityFeePerGas";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_maxPriorityFeePerGas");
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = ();
+    type Ok = BigInt;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let premium = gas::estimate_gas_premium(&ctx, 0).await?;
+        Ok(premium.into())
+    }
+}
+
 pub enum EthGetBalance {}
 impl RpcMethod<2> for EthGetBalance {
     const NAME: &'static str = "Filecoin.EthGetBalance";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getBalance");
     const PARAM_NAMES: [&'static str; 2] = ["address", "block_param"];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -977,6 +1419,7 @@ fn encode_as_abi_helper(param1: u64, param2: u64, data: &[u8]) -> Vec<u8> {
 /// Decodes the payload using the given codec.
 fn decode_payload(payload: &fvm_ipld_encoding::RawBytes, codec: u64) -> Result<EthBytes> {
     match codec {
+        IDENTITY => Ok(EthBytes::default()),
         DAG_CBOR | CBOR => {
             let result: Result<Vec<u8>, _> = serde_ipld_dagcbor::de::from_reader(payload.reader());
             match result {
@@ -1089,6 +1532,31 @@ pub fn new_eth_tx_from_signed_message<DB: Blockstore>(
     Ok(Tx { hash, ..tx })
 }
 
+/// Builds the Ethereum transaction for the message at position `index` within a tipset.
+fn new_eth_tx_from_chain_message<DB: Blockstore>(
+    msg: &ChainMessage,
+    state: &StateTree<DB>,
+    chain_id: u32,
+    block_hash: &Hash,
+    block_number: ChainEpoch,
+    index: usize,
+) -> Result<Tx> {
+    let smsg = match msg {
+        ChainMessage::Signed(msg) => msg.clone(),
+        ChainMessage::Unsigned(msg) => {
+            let sig = Signature::new_bls(vec![]);
+            SignedMessage::new_unchecked(msg.clone(), sig)
+        }
+    };
+    let tx = new_eth_tx_from_signed_message(&smsg, state, chain_id)?;
+    Ok(Tx {
+        block_hash: block_hash.clone(),
+        block_number: Uint64(block_number as u64),
+        transaction_index: Uint64(index as u64),
+        ..tx
+    })
+}
+
 pub async fn block_from_filecoin_tipset<DB: Blockstore + Send + Sync + 'static>(
     data: Ctx<DB>,
     tipset: Arc<Tipset>,
@@ -1110,24 +1578,15 @@ pub async fn block_from_filecoin_tipset<DB: Blockstore + Send + Sync + 'static>(
     let mut hash_transactions = vec![];
     let mut gas_used = 0;
     for (i, (msg, receipt)) in msgs_and_receipts.iter().enumerate() {
-        let ti = Uint64(i as u64);
         gas_used += receipt.gas_used();
-        let smsg = match msg {
-            ChainMessage::Signed(msg) => msg.clone(),
-            ChainMessage::Unsigned(msg) => {
-                let sig = Signature::new_bls(vec![]);
-                SignedMessage::new_unchecked(msg.clone(), sig)
-            }
-        };
-
-        let mut tx = new_eth_tx_from_signed_message(
-            &smsg,
+        let tx = new_eth_tx_from_chain_message(
+            msg,
             &state_tree,
             data.state_manager.chain_config().eth_chain_id,
+            &block_hash,
+            tipset.epoch(),
+            i,
         )?;
-        tx.block_hash = block_hash.clone();
-        tx.block_number = block_number.clone();
-        tx.transaction_index = ti;
 
         if full_tx_info {
             full_transactions.push(tx);
@@ -1160,6 +1619,7 @@ pub async fn block_from_filecoin_tipset<DB: Blockstore + Send + Sync + 'static>(
 pub enum EthGetBlockByHash {}
 impl RpcMethod<2> for EthGetBlockByHash {
     const NAME: &'static str = "Filecoin.EthGetBlockByHash";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getBlockByHash");
     const PARAM_NAMES: [&'static str; 2] = ["block_param", "full_tx_info"];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -1180,6 +1640,7 @@ impl RpcMethod<2> for EthGetBlockByHash {
 pub enum EthGetBlockByNumber {}
 impl RpcMethod<2> for EthGetBlockByNumber {
     const NAME: &'static str = "Filecoin.EthGetBlockByNumber";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getBlockByNumber");
     const PARAM_NAMES: [&'static str; 2] = ["block_param", "full_tx_info"];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -1200,6 +1661,7 @@ impl RpcMethod<2> for EthGetBlockByNumber {
 pub enum EthGetBlockTransactionCountByHash {}
 impl RpcMethod<1> for EthGetBlockTransactionCountByHash {
     const NAME: &'static str = "Filecoin.EthGetBlockTransactionCountByHash";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getBlockTransactionCountByHash");
     const PARAM_NAMES: [&'static str; 1] = ["block_hash"];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -1225,6 +1687,7 @@ impl RpcMethod<1> for EthGetBlockTransactionCountByHash {
 pub enum EthGetBlockTransactionCountByNumber {}
 impl RpcMethod<1> for EthGetBlockTransactionCountByNumber {
     const NAME: &'static str = "Filecoin.EthGetBlockTransactionCountByNumber";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getBlockTransactionCountByNumber");
     const PARAM_NAMES: [&'static str; 1] = ["block_number"];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -1265,57 +1728,550 @@ fn count_messages_in_tipset(store: &impl Blockstore, ts: &Tipset) -> anyhow::Res
     Ok(message_cids.len())
 }
 
-pub enum EthSyncing {}
-impl RpcMethod<0> for EthSyncing {
-    const NAME: &'static str = "Filecoin.EthSyncing";
-    const PARAM_NAMES: [&'static str; 0] = [];
+/// Resolves an Ethereum transaction hash to the CID of the Filecoin message it refers to. The
+/// hashes of native Filecoin messages are not stored, as they are derived from the message CID.
+fn eth_tx_cid_from_hash<DB: Blockstore>(chain_store: &ChainStore<DB>, hash: &Hash) -> Result<Cid> {
+    Ok(chain_store
+        .get_mapping(hash)?
+        .unwrap_or_else(|| hash.to_cid()))
+}
+
+/// A message that has been included and executed on chain.
+struct EthMessageLookup {
+    /// The tipset including the message.
+    tipset: Arc<Tipset>,
+    /// The tipset holding the receipt of the message, i.e., the child of `tipset`.
+    execution_tipset: Arc<Tipset>,
+    message: ChainMessage,
+    /// Position of the message within `tipset`.
+    index: usize,
+    receipt: Receipt,
+}
+
+async fn search_eth_message<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    cid: Cid,
+) -> Result<Option<EthMessageLookup>> {
+    // A message that isn't in the store can't be on chain.
+    if !ctx.store().has(&cid)? {
+        return Ok(None);
+    }
+    let Some((execution_tipset, receipt)) = ctx
+        .state_manager
+        .search_for_message(None, cid, None)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let tipset = ctx
+        .chain_store
+        .chain_index
+        .load_required_tipset(execution_tipset.parents())?;
+    let Some((index, message)) = ctx
+        .chain_store
+        .messages_for_tipset(&tipset)?
+        .into_iter()
+        .find_position(|msg| msg.cid().ok() == Some(cid))
+    else {
+        bail!(
+            "message {cid} not found in tipset at epoch {}",
+            tipset.epoch()
+        );
+    };
+    Ok(Some(EthMessageLookup {
+        tipset,
+        execution_tipset,
+        message,
+        index,
+        receipt,
+    }))
+}
+
+fn eth_tx_from_message_lookup<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    lookup: &EthMessageLookup,
+) -> Result<Tx> {
+    let state = StateTree::new_from_root(
+        ctx.state_manager.blockstore_owned(),
+        lookup.execution_tipset.parent_state(),
+    )?;
+    new_eth_tx_from_chain_message(
+        &lookup.message,
+        &state,
+        ctx.state_manager.chain_config().eth_chain_id,
+        &lookup.tipset.key().cid()?.into(),
+        lookup.tipset.epoch(),
+        lookup.index,
+    )
+}
+
+/// Extracts the data and topics of an Ethereum log from the entries of an actor event. Returns
+/// `None` for events that can't be represented as a log.
+///
+/// See `ethLogFromEvent` in <https://github.com/filecoin-project/lotus/blob/v1.26.2/node/impl/full/eth_events.go>
+fn eth_log_from_event(entries: &[Entry]) -> Option<(EthBytes, Vec<Hash>)> {
+    const MAX_TOPICS: usize = 4;
+
+    let mut topics_found = [false; MAX_TOPICS];
+    let mut topics_found_count = 0;
+    let mut data_found = false;
+    let mut data = vec![];
+    let mut topics = Vec::with_capacity(MAX_TOPICS);
+    for entry in entries {
+        // Drop events with non-raw topics to avoid mistakes.
+        if entry.codec != IPLD_RAW {
+            tracing::warn!(
+                "did not expect an event entry with a codec other than raw: {}",
+                entry.codec
+            );
+            return None;
+        }
+        let topic_index = match entry.key.as_str() {
+            "t1" => Some(0),
+            "t2" => Some(1),
+            "t3" => Some(2),
+            "t4" => Some(3),
+            _ => None,
+        };
+        match (entry.key.as_str(), topic_index) {
+            (_, Some(idx)) => {
+                // Drop events with mis-sized or duplicate topics.
+                if entry.value.len() != EVM_WORD_LENGTH
+                    || std::mem::replace(topics_found.get_mut(idx)?, true)
+                {
+                    return None;
+                }
+                topics_found_count += 1;
+                if topics.len() <= idx {
+                    topics.resize(idx + 1, Hash::default());
+                }
+                *topics.get_mut(idx)? = Hash(ethereum_types::H256::from_slice(&entry.value));
+            }
+            ("d", None) => {
+                // Drop events with duplicate data fields.
+                if data_found {
+                    return None;
+                }
+                data_found = true;
+                data.clone_from(&entry.value);
+            }
+            (key, None) => {
+                // Skip entries we don't understand, this makes it easier to extend things.
+                tracing::warn!("unexpected event entry: {key}");
+            }
+        }
+    }
+    // Drop events with skipped topics.
+    if topics.len() != topics_found_count {
+        return None;
+    }
+    Some((EthBytes(data), topics))
+}
+
+/// Computes the price paid per unit of gas, including the miner tip and the burnt fees.
+///
+/// See `ComputeGasOutputs` in <https://github.com/filecoin-project/lotus/blob/v1.26.2/chain/vm/burn.go>
+fn effective_gas_price(tx: &Tx, base_fee: &TokenAmount, gas_used: u64) -> BigInt {
+    const GAS_OVERUSE_NUM: u64 = 11;
+    const GAS_OVERUSE_DENOM: u64 = 10;
+
+    if gas_used == 0 {
+        return BigInt::default();
+    }
+    let gas_limit = tx.gas.0;
+    let fee_cap = &tx.max_fee_per_gas.0;
+    let base_fee = base_fee.atto();
+    let base_fee_to_pay = std::cmp::min(base_fee, fee_cap);
+    let base_fee_burn = base_fee_to_pay * gas_used;
+    let miner_tip = if base_fee_to_pay + &tx.max_priority_fee_per_gas.0 > *fee_cap {
+        fee_cap - base_fee_to_pay
+    } else {
+        tx.max_priority_fee_per_gas.0.clone()
+    };
+    let miner_tip = miner_tip * gas_limit;
+
+    // The share of the unused gas that is burnt for over-estimating the gas limit.
+    let over = (gas_limit as i128) - ((GAS_OVERUSE_NUM * gas_used) / GAS_OVERUSE_DENOM) as i128;
+    let gas_burned = if over < 0 {
+        0
+    } else {
+        let over = (over as u64).min(gas_used) as u128;
+        ((gas_limit.saturating_sub(gas_used)) as u128 * over / gas_used as u128) as u64
+    };
+    let over_estimation_burn = base_fee_to_pay * gas_burned;
+
+    BigInt((base_fee_burn + miner_tip + over_estimation_burn) / gas_used)
+}
+
+fn new_eth_tx_receipt<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    tx: &Tx,
+    lookup: &EthMessageLookup,
+) -> Result<EthTxReceipt> {
+    let msg_receipt = &lookup.receipt;
+    let mut receipt = EthTxReceipt {
+        transaction_hash: tx.hash.clone(),
+        transaction_index: tx.transaction_index.clone(),
+        block_hash: tx.block_hash.clone(),
+        block_number: tx.block_number.clone(),
+        from: tx.from.clone(),
+        to: tx.to.clone(),
+        status: Uint64(msg_receipt.exit_code().is_success() as u64),
+        gas_used: Uint64(msg_receipt.gas_used()),
+        // The message paid the base fee of the tipset including it.
+        effective_gas_price: effective_gas_price(
+            tx,
+            &lookup.tipset.block_headers().first().parent_base_fee,
+            msg_receipt.gas_used(),
+        ),
+        r#type: Uint64(EIP_1559_TX_TYPE),
+        ..EthTxReceipt::default()
+    };
+
+    // A successful contract creation returns the address of the new contract.
+    if receipt.to.is_none() && msg_receipt.exit_code().is_success() {
+        let ret: CreateExternalReturn =
+            fvm_ipld_encoding::from_slice(msg_receipt.return_data().bytes())?;
+        receipt.contract_address = Some(EthAddress::try_from(ret.eth_address.as_slice())?);
+    }
+
+    let state = StateTree::new_from_root(
+        ctx.state_manager.blockstore_owned(),
+        lookup.execution_tipset.parent_state(),
+    )?;
+    for (i, event) in msg_receipt.events(ctx.store())?.into_iter().enumerate() {
+        let Some((data, topics)) = eth_log_from_event(&event.event.entries) else {
+            continue;
+        };
+        let address = lookup_eth_address(&FilecoinAddress::new_id(event.emitter), &state)?
+            .unwrap_or_else(|| EthAddress::from_actor_id(event.emitter));
+        for topic in &topics {
+            receipt
+                .logs_bloom
+                .0
+                .accrue(ethereum_types::BloomInput::Raw(topic.0.as_bytes()));
+        }
+        receipt
+            .logs_bloom
+            .0
+            .accrue(ethereum_types::BloomInput::Raw(address.0.as_bytes()));
+        receipt.logs.push(EthLog {
+            address,
+            data,
+            topics,
+            removed: false,
+            log_index: Uint64(i as u64),
+            transaction_index: tx.transaction_index.clone(),
+            transaction_hash: tx.hash.clone(),
+            block_hash: tx.block_hash.clone(),
+            block_number: tx.block_number.clone(),
+        });
+    }
+
+    Ok(receipt)
+}
+
+pub enum EthGetTransactionByHash {}
+impl RpcMethod<1> for EthGetTransactionByHash {
+    const NAME: &'static str = "Filecoin.EthGetTransactionByHash";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getTransactionByHash");
+    const PARAM_NAMES: [&'static str; 1] = ["tx_hash"];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
 
-    type Params = ();
-    type Ok = EthSyncingResult;
+    type Params = (Hash,);
+    type Ok = Option<Tx>;
 
     async fn handle(
         ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
-        (): Self::Params,
+        (tx_hash,): Self::Params,
     ) -> Result<Self::Ok, ServerError> {
-        let crate::rpc::sync::RPCSyncState { active_syncs } =
-            crate::rpc::sync::SyncState::handle(ctx, ()).await?;
-        match active_syncs
-            .into_iter()
-            .rev()
-            .find_or_first(|ss| ss.stage() != SyncStage::Idle)
-        {
-            Some(sync_state) => match (sync_state.base(), sync_state.target()) {
-                (Some(base), Some(target)) => Ok(EthSyncingResult {
-                    done_sync: sync_state.stage() == SyncStage::Complete,
-                    current_block: sync_state.epoch(),
-                    starting_block: base.epoch(),
-                    highest_block: target.epoch(),
-                }),
-                _ => Err(ServerError::internal_error(
-                    "missing syncing information, try again",
-                    None,
-                )),
-            },
-            None => Err(ServerError::internal_error("sync state not found", None)),
+        let cid = eth_tx_cid_from_hash(&ctx.chain_store, &tx_hash)?;
+
+        // First, try to find the transaction on chain.
+        if let Some(lookup) = search_eth_message(&ctx, cid).await? {
+            return Ok(Some(eth_tx_from_message_lookup(&ctx, &lookup)?));
+        }
+
+        // Then, look for it in the message pool. The addresses of native messages are resolved
+        // in the state the pool checks the messages against.
+        let (pending, ts) = ctx.mpool.pending()?;
+        if let Some(smsg) = pending.iter().find(|smsg| smsg.cid().ok() == Some(cid)) {
+            let state =
+                StateTree::new_from_root(ctx.state_manager.blockstore_owned(), ts.parent_state())?;
+            return Ok(Some(new_eth_tx_from_signed_message(
+                smsg,
+                &state,
+                ctx.state_manager.chain_config().eth_chain_id,
+            )?));
         }
+
+        // Ethereum clients expect an empty response when the transaction is not found.
+        Ok(None)
     }
 }
 
-pub enum EthGetCode {}
-impl RpcMethod<2> for EthGetCode {
-    const NAME: &'static str = "Filecoin.EthGetCode";
-    const PARAM_NAMES: [&'static str; 2] = ["eth_address", "block_number_or_hash"];
+pub enum EthGetTransactionCount {}
+impl RpcMethod<2> for EthGetTransactionCount {
+    const NAME: &'static str = "Filecoin.EthGetTransactionCount";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getTransactionCount");
+    const PARAM_NAMES: [&'static str; 2] = ["sender", "block_param"];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
 
     type Params = (EthAddress, BlockNumberOrHash);
-    type Ok = EthBytes;
+    type Ok = Uint64;
 
     async fn handle(
         ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
-        (eth_address, block_number_or_hash): Self::Params,
+        (sender, block_param): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let addr = sender.to_filecoin_address()?;
+        // The pending nonce accounts for the messages of the sender waiting in the message pool.
+        if block_param == BlockNumberOrHash::from_predefined(Predefined::Pending) {
+            return Ok(Uint64(ctx.mpool.get_sequence(&addr)?));
+        }
+
+        let ts = tipset_by_block_number_or_hash(&ctx.chain_store, block_param)?;
+        // The nonce after the messages of the tipset have been executed, as Lotus reports it.
+        let (state_root, _) = ctx.state_manager.tipset_state(&ts).await?;
+        let Some(actor) = ctx.state_manager.get_actor(&addr, state_root)? else {
+            return Ok(Uint64(0));
+        };
+        // Contracts track their nonce in their own state, as it is only bumped when they create
+        // other contracts.
+        if fil_actor_interface::is_evm_actor(&actor.code) {
+            let nonce = match evm::State::load(ctx.store(), actor.code, actor.state)? {
+                evm::State::V10(state) => state.nonce,
+                evm::State::V11(state) => state.nonce,
+                evm::State::V12(state) => state.nonce,
+                evm::State::V13(state) => state.nonce,
+            };
+            return Ok(Uint64(nonce));
+        }
+        Ok(Uint64(actor.sequence))
+    }
+}
+
+pub enum EthGetMessageCidByTransactionHash {}
+impl RpcMethod<1> for EthGetMessageCidByTransactionHash {
+    const NAME: &'static str = "Filecoin.EthGetMessageCidByTransactionHash";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getMessageCidByTransactionHash");
+    const PARAM_NAMES: [&'static str; 1] = ["tx_hash"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Hash,);
+    type Ok = Option<Cid>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (tx_hash,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        if let Some(cid) = ctx.chain_store.get_mapping(&tx_hash)? {
+            return Ok(Some(cid));
+        }
+
+        // Ethereum transactions still in the message pool may not have been indexed yet, e.g.,
+        // when received from the network.
+        let chain_id = ctx.state_manager.chain_config().eth_chain_id;
+        let (pending, _) = ctx.mpool.pending()?;
+        for smsg in pending.iter().filter(|smsg| smsg.is_delegated()) {
+            if filter::eth_tx_hash_from_signed_message(smsg, chain_id)? == tx_hash {
+                return Ok(Some(smsg.cid()?));
+            }
+        }
+
+        // Native Filecoin messages are not indexed, as their hash is derived from their CID.
+        let cid = tx_hash.to_cid();
+        if crate::chain::get_chain_message(ctx.store(), &cid).is_ok() {
+            return Ok(Some(cid));
+        }
+        Ok(None)
+    }
+}
+
+pub enum EthGetTransactionHashByCid {}
+impl RpcMethod<1> for EthGetTransactionHashByCid {
+    const NAME: &'static str = "Filecoin.EthGetTransactionHashByCid";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getTransactionHashByCid");
+    const PARAM_NAMES: [&'static str; 1] = ["cid"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Cid,);
+    type Ok = Option<Hash>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (cid,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let message = match crate::chain::get_chain_message(ctx.store(), &cid) {
+            Ok(message) => Some(message),
+            Err(_) => {
+                let (pending, _) = ctx.mpool.pending()?;
+                pending
+                    .into_iter()
+                    .find(|smsg| smsg.cid().ok() == Some(cid))
+                    .map(ChainMessage::Signed)
+            }
+        };
+        match message {
+            Some(ChainMessage::Signed(smsg)) if smsg.is_delegated() => {
+                // The hash of Ethereum transactions is recomputed from the signed message.
+                Ok(Some(filter::eth_tx_hash_from_signed_message(
+                    &smsg,
+                    ctx.state_manager.chain_config().eth_chain_id,
+                )?))
+            }
+            Some(_) => Ok(Some(cid.into())),
+            None => Ok(None),
+        }
+    }
+}
+
+pub enum EthGetTransactionByBlockHashAndIndex {}
+impl RpcMethod<2> for EthGetTransactionByBlockHashAndIndex {
+    const NAME: &'static str = "Filecoin.EthGetTransactionByBlockHashAndIndex";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getTransactionByBlockHashAndIndex");
+    const PARAM_NAMES: [&'static str; 2] = ["block_hash", "tx_index"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Hash, Uint64);
+    type Ok = Option<Tx>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (block_hash, tx_index): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let ts = Arc::new(get_tipset_from_hash(&ctx.chain_store, &block_hash)?);
+        Ok(Some(eth_tx_by_index(ctx, ts, tx_index.0).await?))
+    }
+}
+
+pub enum EthGetTransactionByBlockNumberAndIndex {}
+impl RpcMethod<2> for EthGetTransactionByBlockNumberAndIndex {
+    const NAME: &'static str = "Filecoin.EthGetTransactionByBlockNumberAndIndex";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getTransactionByBlockNumberAndIndex");
+    const PARAM_NAMES: [&'static str; 2] = ["block_param", "tx_index"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (BlockNumberOrHash, Uint64);
+    type Ok = Option<Tx>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (block_param, tx_index): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let ts = tipset_by_block_number_or_hash(&ctx.chain_store, block_param)?;
+        Ok(Some(eth_tx_by_index(ctx, ts, tx_index.0).await?))
+    }
+}
+
+async fn eth_tx_by_index<DB: Blockstore + Send + Sync + 'static>(
+    ctx: Ctx<DB>,
+    tipset: Arc<Tipset>,
+    index: u64,
+) -> Result<Tx> {
+    let block_hash: Hash = tipset.key().cid()?.into();
+    let (state_root, msgs_and_receipts) = execute_tipset(ctx.clone(), &tipset).await?;
+    let Some((msg, _)) = msgs_and_receipts.get(index as usize) else {
+        bail!(
+            "index {index} out of range: tipset contains {} messages",
+            msgs_and_receipts.len()
+        );
+    };
+    let state = StateTree::new_from_root(ctx.state_manager.blockstore_owned(), &state_root)?;
+    new_eth_tx_from_chain_message(
+        msg,
+        &state,
+        ctx.state_manager.chain_config().eth_chain_id,
+        &block_hash,
+        tipset.epoch(),
+        index as usize,
+    )
+}
+
+pub enum EthGetTransactionReceipt {}
+impl RpcMethod<1> for EthGetTransactionReceipt {
+    const NAME: &'static str = "Filecoin.EthGetTransactionReceipt";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getTransactionReceipt");
+    const PARAM_NAMES: [&'static str; 1] = ["tx_hash"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Hash,);
+    type Ok = Option<EthTxReceipt>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (tx_hash,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let cid = eth_tx_cid_from_hash(&ctx.chain_store, &tx_hash)?;
+        // Ethereum clients expect an empty response when the transaction is not found.
+        let Some(lookup) = search_eth_message(&ctx, cid).await? else {
+            return Ok(None);
+        };
+        let tx = eth_tx_from_message_lookup(&ctx, &lookup)?;
+        Ok(Some(new_eth_tx_receipt(&ctx, &tx, &lookup)?))
+    }
+}
+
+pub enum EthSyncing {}
+impl RpcMethod<0> for EthSyncing {
+    const NAME: &'static str = "Filecoin.EthSyncing";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_syncing");
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = ();
+    type Ok = EthSyncingResult;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let crate::rpc::sync::RPCSyncState { active_syncs } =
+            crate::rpc::sync::SyncState::handle(ctx, ()).await?;
+        match active_syncs
+            .into_iter()
+            .rev()
+            .find_or_first(|ss| ss.stage() != SyncStage::Idle)
+        {
+            Some(sync_state) => match (sync_state.base(), sync_state.target()) {
+                (Some(base), Some(target)) => Ok(EthSyncingResult {
+                    done_sync: sync_state.stage() == SyncStage::Complete,
+                    current_block: sync_state.epoch(),
+                    starting_block: base.epoch(),
+                    highest_block: target.epoch(),
+                }),
+                _ => Err(ServerError::internal_error(
+                    "missing syncing information, try again",
+                    None,
+                )),
+            },
+            None => Err(ServerError::internal_error("sync state not found", None)),
+        }
+    }
+}
+
+pub enum EthGetCode {}
+impl RpcMethod<2> for EthGetCode {
+    const NAME: &'static str = "Filecoin.EthGetCode";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getCode");
+    const PARAM_NAMES: [&'static str; 2] = ["eth_address", "block_number_or_hash"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (EthAddress, BlockNumberOrHash);
+    type Ok = EthBytes;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (eth_address, block_number_or_hash): Self::Params,
     ) -> Result<Self::Ok, ServerError> {
         let ts = tipset_by_block_number_or_hash(&ctx.chain_store, block_number_or_hash)?;
         let to_address = FilecoinAddress::try_from(&eth_address)?;
@@ -1367,6 +2323,7 @@ impl RpcMethod<2> for EthGetCode {
 pub enum EthGetStorageAt {}
 impl RpcMethod<3> for EthGetStorageAt {
     const NAME: &'static str = "Filecoin.EthGetStorageAt";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getStorageAt");
     const PARAM_NAMES: [&'static str; 3] = ["eth_address", "position", "block_number_or_hash"];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -1437,14 +2394,525 @@ impl RpcMethod<3> for EthGetStorageAt {
     }
 }
 
+/// Executes a message at the given tipset and fails with an `execution reverted` error if the
+/// message did not succeed.
+fn apply_message<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    tipset: Arc<Tipset>,
+    msg: &Message,
+) -> Result<ApiInvocResult, ServerError> {
+    let invoc_res = ctx.state_manager.call(msg, Some(tipset))?;
+    let Some(msg_rct) = &invoc_res.msg_rct else {
+        return Err(anyhow::anyhow!("no message receipt").into());
+    };
+    if !msg_rct.exit_code().is_success() {
+        let revert_data = fvm_ipld_encoding::from_slice::<RawBytes>(&msg_rct.return_data())
+            .map(|bytes| bytes.bytes().to_vec())
+            .unwrap_or_default();
+        return Err(ServerError::new(
+            EXECUTION_REVERTED_ERROR_CODE,
+            format!(
+                "message execution failed: exit {}, revert reason: {}, vm error: {}",
+                msg_rct.exit_code().value(),
+                parse_eth_revert(&msg_rct.return_data()),
+                invoc_res.error
+            ),
+            serde_json::Value::String(format!("0x{}", hex::encode(revert_data))),
+        ));
+    }
+    Ok(invoc_res)
+}
+
+/// Reads a 256-bit ABI word as a `u64`, failing if the value does not fit.
+fn u64_from_abi_word(word: &[u8]) -> Option<u64> {
+    if word.len() != EVM_WORD_LENGTH {
+        return None;
+    }
+    let (padding, value) = word.split_at(EVM_WORD_LENGTH - std::mem::size_of::<u64>());
+    if padding.iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(u64::from_be_bytes(value.try_into().ok()?))
+}
+
+/// Extracts a human-readable revert reason from the CBOR-encoded return data of a failed EVM
+/// invocation.
+///
+/// See <https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require>
+fn parse_eth_revert(ret: &[u8]) -> String {
+    if ret.is_empty() {
+        return "none".into();
+    }
+    let Ok(cbytes) = fvm_ipld_encoding::from_slice::<RawBytes>(ret) else {
+        return "ERROR: revert reason is not cbor encoded bytes".into();
+    };
+    let cbytes = cbytes.bytes();
+    if cbytes.is_empty() {
+        return "none".into();
+    }
+    let as_hex = || format!("0x{}", hex::encode(cbytes));
+    // If it's not long enough to contain an ABI encoded response, return immediately.
+    if cbytes.len() < 4 + EVM_WORD_LENGTH {
+        return as_hex();
+    }
+    let (selector, data) = cbytes.split_at(4);
+    if selector == PANIC_FUNCTION_SELECTOR {
+        let Some(code) = data.get(..EVM_WORD_LENGTH).and_then(u64_from_abi_word) else {
+            return as_hex();
+        };
+        match code {
+            0x00 => "Panic()".into(),
+            0x01 => "Assert()".into(),
+            0x11 => "ArithmeticOverflow()".into(),
+            0x12 => "DivideByZero()".into(),
+            0x21 => "InvalidEnumVariant()".into(),
+            0x22 => "InvalidStorageArray()".into(),
+            0x31 => "PopEmptyArray()".into(),
+            0x32 => "ArrayIndexOutOfBounds()".into(),
+            0x41 => "OutOfMemory()".into(),
+            0x51 => "CalledUninitializedFunction()".into(),
+            _ => format!("Panic({code:#x})"),
+        }
+    } else if selector == ERROR_FUNCTION_SELECTOR {
+        let reason = (|| {
+            let offset = usize::try_from(u64_from_abi_word(data.get(..EVM_WORD_LENGTH)?)?).ok()?;
+            let start = offset.checked_add(EVM_WORD_LENGTH)?;
+            let length = usize::try_from(u64_from_abi_word(data.get(offset..start)?)?).ok()?;
+            data.get(start..start.checked_add(length)?)
+        })();
+        match reason {
+            Some(reason) => format!("Error({})", String::from_utf8_lossy(reason)),
+            None => as_hex(),
+        }
+    } else {
+        as_hex()
+    }
+}
+
+pub enum EthCall {}
+impl RpcMethod<2> for EthCall {
+    const NAME: &'static str = "Filecoin.EthCall";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_call");
+    const PARAM_NAMES: [&'static str; 2] = ["tx", "block_param"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (EthCallMessage, BlockNumberOrHash);
+    type Ok = EthBytes;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (tx, block_param): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let msg = Message::try_from(tx)?;
+        let ts = tipset_by_block_number_or_hash(&ctx.chain_store, block_param)?;
+        let invoc_res = apply_message(&ctx, ts, &msg)?;
+
+        if msg.to == FilecoinAddress::ETHEREUM_ACCOUNT_MANAGER_ACTOR {
+            // Contract creation doesn't return any bytes.
+            return Ok(EthBytes::default());
+        }
+        match invoc_res.msg_rct {
+            Some(msg_rct) if !msg_rct.return_data().is_empty() => {
+                Ok(decode_payload(&msg_rct.return_data(), CBOR)?)
+            }
+            _ => Ok(EthBytes::default()),
+        }
+    }
+}
+
+pub enum EthEstimateGas {}
+impl RpcMethod<2> for EthEstimateGas {
+    const NAME: &'static str = "Filecoin.EthEstimateGas";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_estimateGas");
+    const PARAM_NAMES: [&'static str; 2] = ["tx", "block_param"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (EthCallMessage, Option<BlockNumberOrHash>);
+    type Ok = Uint64;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (tx, block_param): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let mut msg = Message::try_from(tx)?;
+        // Set the gas limit to the zero sentinel value, which makes
+        // gas estimation actually run.
+        msg.gas_limit = 0;
+        let tipset = match block_param {
+            Some(block_param) => tipset_by_block_number_or_hash(&ctx.chain_store, block_param)?,
+            None => ctx.chain_store.heaviest_tipset(),
+        };
+        let tsk = ApiTipsetKey(Some(tipset.key().clone()));
+
+        let gassed_msg = match gas::estimate_message_gas(&ctx, msg.clone(), None, tsk.clone()).await
+        {
+            // A zero gas limit means that the message failed to execute.
+            Ok(gassed_msg) if gassed_msg.gas_limit > 0 => gassed_msg,
+            result => {
+                // On failure, `GasEstimateMessageGas` doesn't return the invocation result,
+                // so we can't get the revert reason. Re-execute the message the way `EthCall`
+                // does, which gives us an Ethereum-specific error with the revert information.
+                msg.gas_limit = BLOCK_GAS_LIMIT;
+                apply_message(&ctx, tipset, &msg)?;
+                return Err(anyhow::anyhow!(
+                    "failed to estimate gas: {}",
+                    result.map_or_else(|e| e.to_string(), |_| "message execution failed".into())
+                )
+                .into());
+            }
+        };
+
+        let expected_gas = eth_gas_search(&ctx, gassed_msg, &tsk).await?;
+        Ok(Uint64(expected_gas))
+    }
+}
+
+/// Checks that the message executes with its gas limit. If the message runs out of gas
+/// (possibly in a nested call), searches for a gas limit that allows it to succeed.
+async fn eth_gas_search<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    msg: Message,
+    tsk: &ApiTipsetKey,
+) -> anyhow::Result<u64> {
+    let (res, prior_messages, ts) =
+        gas::estimate_call_with_gas(ctx, msg.clone(), tsk, VMTrace::Traced).await?;
+    let Some(msg_rct) = res.msg_rct else {
+        bail!("no message receipt");
+    };
+    if msg_rct.exit_code().is_success() {
+        return Ok(msg.gas_limit);
+    }
+
+    if res
+        .execution_trace
+        .as_ref()
+        .is_some_and(|trace| trace_contains_exit_code(trace, ExitCode::SYS_OUT_OF_GAS))
+    {
+        let ret = gas_search(ctx, &msg, &prior_messages, ts).await?;
+        return Ok(((ret as f64) * ctx.mpool.config.gas_limit_overestimation) as u64);
+    }
+
+    bail!(
+        "message execution failed: exit {}, reason: {}",
+        msg_rct.exit_code().value(),
+        res.error.unwrap_or_default()
+    )
+}
+
+fn trace_contains_exit_code(trace: &ExecutionTrace, exit_code: ExitCode) -> bool {
+    trace.msg_rct.exit_code == exit_code
+        || trace
+            .subcalls
+            .iter()
+            .any(|subcall| trace_contains_exit_code(subcall, exit_code))
+}
+
+/// Does an exponential search to find a gas limit to execute the message with. It first
+/// finds a high gas limit that allows the message to execute by doubling the previous gas
+/// limit until it succeeds, then does a binary search until it gets within a range of 1%.
+async fn gas_search<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    msg: &Message,
+    prior_messages: &[ChainMessage],
+    ts: Arc<Tipset>,
+) -> anyhow::Result<u64> {
+    let mut high = msg.gas_limit;
+    let mut low = msg.gas_limit;
+
+    let can_succeed = |limit: u64| {
+        let mut msg = msg.clone();
+        msg.gas_limit = limit;
+        let ts = ts.clone();
+        async move {
+            let from_a = ctx
+                .state_manager
+                .resolve_to_key_addr(&msg.from, &ts)
+                .await?;
+            let mut chain_msg = gas::pretend_signed(msg, from_a.protocol());
+            let res = ctx
+                .state_manager
+                .call_with_gas(&mut chain_msg, prior_messages, Some(ts), VMTrace::NotTraced)
+                .await?;
+            anyhow::Ok(
+                res.msg_rct
+                    .is_some_and(|msg_rct| msg_rct.exit_code().is_success()),
+            )
+        }
+    };
+
+    while !can_succeed(high).await? {
+        low = high;
+        high = high.saturating_mul(2);
+        if high > BLOCK_GAS_LIMIT {
+            high = BLOCK_GAS_LIMIT;
+            break;
+        }
+    }
+
+    let mut check_threshold = high / 100;
+    while high - low > check_threshold {
+        let median = (low + high) / 2;
+        if can_succeed(median).await? {
+            high = median;
+        } else {
+            low = median;
+        }
+        check_threshold = median / 100;
+    }
+
+    Ok(high)
+}
+
+pub enum EthGetLogs {}
+impl RpcMethod<1> for EthGetLogs {
+    const NAME: &'static str = "Filecoin.EthGetLogs";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getLogs");
+    const PARAM_NAMES: [&'static str; 1] = ["eth_filter"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (EthFilterSpec,);
+    type Ok = EthFilterResult;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (eth_filter,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let logs = filter::eth_get_logs(&ctx, &eth_filter).await?;
+        Ok(EthFilterResult::Logs(logs))
+    }
+}
+
+pub enum EthNewFilter {}
+impl RpcMethod<1> for EthNewFilter {
+    const NAME: &'static str = "Filecoin.EthNewFilter";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_newFilter");
+    const PARAM_NAMES: [&'static str; 1] = ["filter_spec"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (EthFilterSpec,);
+    type Ok = FilterID;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (filter_spec,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(filter::eth_new_filter(&ctx, filter_spec)?)
+    }
+}
+
+pub enum EthNewBlockFilter {}
+impl RpcMethod<0> for EthNewBlockFilter {
+    const NAME: &'static str = "Filecoin.EthNewBlockFilter";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_newBlockFilter");
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = ();
+    type Ok = FilterID;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(filter::eth_new_block_filter(&ctx)?)
+    }
+}
+
+pub enum EthNewPendingTransactionFilter {}
+impl RpcMethod<0> for EthNewPendingTransactionFilter {
+    const NAME: &'static str = "Filecoin.EthNewPendingTransactionFilter";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_newPendingTransactionFilter");
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = ();
+    type Ok = FilterID;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(filter::eth_new_pending_transaction_filter(&ctx)?)
+    }
+}
+
+pub enum EthGetFilterChanges {}
+impl RpcMethod<1> for EthGetFilterChanges {
+    const NAME: &'static str = "Filecoin.EthGetFilterChanges";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getFilterChanges");
+    const PARAM_NAMES: [&'static str; 1] = ["filter_id"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (FilterID,);
+    type Ok = EthFilterResult;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (filter_id,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(filter::eth_get_filter_changes(&ctx, &filter_id).await?)
+    }
+}
+
+pub enum EthGetFilterLogs {}
+impl RpcMethod<1> for EthGetFilterLogs {
+    const NAME: &'static str = "Filecoin.EthGetFilterLogs";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_getFilterLogs");
+    const PARAM_NAMES: [&'static str; 1] = ["filter_id"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (FilterID,);
+    type Ok = EthFilterResult;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (filter_id,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(filter::eth_get_filter_logs(&ctx, &filter_id).await?)
+    }
+}
+
+pub enum EthUninstallFilter {}
+impl RpcMethod<1> for EthUninstallFilter {
+    const NAME: &'static str = "Filecoin.EthUninstallFilter";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_uninstallFilter");
+    const PARAM_NAMES: [&'static str; 1] = ["filter_id"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (FilterID,);
+    type Ok = bool;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (filter_id,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(ctx.eth_filters.uninstall(&filter_id))
+    }
+}
+
+pub enum EthTraceBlock {}
+impl RpcMethod<1> for EthTraceBlock {
+    const NAME: &'static str = "Filecoin.EthTraceBlock";
+    const NAME_ALIAS: Option<&'static str> = Some("trace_block");
+    const PARAM_NAMES: [&'static str; 1] = ["block_param"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (BlockNumberOrHash,);
+    type Ok = Vec<EthBlockTrace>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (block_param,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let ts = tipset_by_block_number_or_hash(&ctx.chain_store, block_param)?;
+        let block_hash: Hash = ts.key().cid()?.into();
+        let transactions = trace::tipset_transaction_traces(&ctx, &ts).await?;
+        let mut traces = vec![];
+        for transaction in transactions {
+            traces.extend(transaction.traces.into_iter().map(|trace| EthBlockTrace {
+                trace,
+                block_hash: block_hash.clone(),
+                block_number: ts.epoch(),
+                transaction_hash: transaction.hash.clone(),
+                transaction_position: transaction.position,
+            }));
+        }
+        Ok(traces)
+    }
+}
+
+pub enum EthTraceReplayBlockTransactions {}
+impl RpcMethod<2> for EthTraceReplayBlockTransactions {
+    const NAME: &'static str = "Filecoin.EthTraceReplayBlockTransactions";
+    const NAME_ALIAS: Option<&'static str> = Some("trace_replayBlockTransactions");
+    const PARAM_NAMES: [&'static str; 2] = ["block_param", "trace_types"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (BlockNumberOrHash, Vec<String>);
+    type Ok = Vec<EthTraceReplayBlockTransaction>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (block_param, trace_types): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        if trace_types.as_slice() != ["trace"] {
+            return Err(anyhow::anyhow!("only 'trace' is supported").into());
+        }
+        let ts = tipset_by_block_number_or_hash(&ctx.chain_store, block_param)?;
+        let transactions = trace::tipset_transaction_traces(&ctx, &ts).await?;
+        Ok(transactions
+            .into_iter()
+            .map(|transaction| EthTraceReplayBlockTransaction {
+                output: trace::transaction_output(&transaction.traces),
+                state_diff: None,
+                trace: transaction.traces,
+                transaction_hash: transaction.hash,
+                vm_trace: None,
+            })
+            .collect())
+    }
+}
+
+pub enum EthSendRawTransaction {}
+impl RpcMethod<1> for EthSendRawTransaction {
+    const NAME: &'static str = "Filecoin.EthSendRawTransaction";
+    const NAME_ALIAS: Option<&'static str> = Some("eth_sendRawTransaction");
+    const PARAM_NAMES: [&'static str; 1] = ["raw_tx"];
+    const API_VERSION: ApiVersion = ApiVersion::V1;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (EthBytes,);
+    type Ok = Hash;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (raw_tx,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let tx_args = parse_eth_transaction(&raw_tx.0)?;
+        let smsg = tx_args.to_signed_message(ctx.state_manager.chain_config().eth_chain_id)?;
+        let cid = ctx.mpool.push(smsg).await?;
+        // The transaction hash is the Keccak-256 of the signed payload.
+        let hash = Hash(keccak(&raw_tx.0));
+        ctx.chain_store.put_mapping(hash.clone(), cid)?;
+        Ok(hash)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::beacon::{mock_beacon::MockBeacon, BeaconPoint, BeaconSchedule};
+    use crate::blocks::{CachingBlockHeader, RawBlockHeader};
+    use crate::chain_sync::SyncConfig;
+    use crate::db::MemoryDB;
+    use crate::key_management::{generate_key, sign, KeyStore, KeyStoreConfig};
+    use crate::message_pool::{MessagePool, MpoolRpcProvider};
+    use crate::networks::ChainConfig;
+    use crate::rpc::RPCState;
+    use crate::shim::executor::Receipt_v3;
+    use crate::shim::machine::BuiltinActor;
+    use crate::shim::state_tree::{ActorState, StateTreeVersion};
+    use crate::shim::version::NetworkVersion;
+    use crate::state_manager::StateManager;
+    use crate::utils::db::CborStoreExt as _;
     use ethereum_types::H160;
     use num_bigint;
     use num_traits::{FromBytes, Signed};
     use quickcheck_macros::quickcheck;
     use std::num::ParseIntError;
+    use tokio::sync::{mpsc, RwLock};
+    use tokio::task::JoinSet;
 
     #[quickcheck]
     fn gas_price_result_serde_roundtrip(i: u128) {
@@ -1455,6 +2923,41 @@ mod test {
         assert_eq!(r.0, decoded.0);
     }
 
+    #[test]
+    fn test_calculate_rewards_and_gas_used() {
+        let atto = |n: u64| TokenAmount::from_atto(n);
+        let (rewards, total) = calculate_rewards_and_gas_used(
+            &[0.0, 25.0, 50.0, 100.0],
+            vec![(atto(300), 10), (atto(100), 10), (atto(200), 20)],
+        );
+        assert_eq!(total, 40);
+        assert_eq!(
+            rewards,
+            vec![
+                atto(100).into(),
+                atto(200).into(),
+                atto(300).into(),
+                atto(300).into()
+            ]
+        );
+
+        // Without messages, the minimum premium is reported.
+        let (rewards, total) = calculate_rewards_and_gas_used(&[50.0], vec![]);
+        assert_eq!(total, 0);
+        assert_eq!(rewards, vec![atto(gas::MIN_GAS_PREMIUM as u64).into()]);
+    }
+
+    #[test]
+    fn test_validate_reward_percentiles() {
+        validate_reward_percentiles(&[]).unwrap();
+        validate_reward_percentiles(&[0.0, 25.0, 25.0, 100.0]).unwrap();
+        validate_reward_percentiles(&[50.0, 25.0]).unwrap_err();
+        validate_reward_percentiles(&[-1.0]).unwrap_err();
+        validate_reward_percentiles(&[100.5]).unwrap_err();
+        validate_reward_percentiles(&vec![50.0; MAX_FEE_HISTORY_REWARD_PERCENTILES + 1])
+            .unwrap_err();
+    }
+
     fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
         (0..s.len())
             .step_by(2)
@@ -1532,6 +3035,105 @@ mod test {
         assert_eq!(expected_hash, eth_tx_args.hash().unwrap());
     }
 
+    #[test]
+    fn test_parse_signed_eth_transaction() {
+        let secret = libsecp256k1::SecretKey::parse(&[0x42; 32]).unwrap();
+        let mut tx_args = TxArgs {
+            chain_id: 314159,
+            nonce: 7,
+            to: None,
+            value: BigInt(num_bigint::BigInt::from(1000)),
+            max_fee_per_gas: BigInt(num_bigint::BigInt::from(1500000120)),
+            max_priority_fee_per_gas: BigInt(num_bigint::BigInt::from(1500000000)),
+            gas_limit: 37442471,
+            input: vec![0x60, 0x80, 0x60, 0x40],
+            ..TxArgs::default()
+        };
+        let digest = keccak(tx_args.rlp_unsigned_message().unwrap());
+        let (sig, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&digest.0), &secret);
+        let sig = sig.serialize();
+        let (r, s) = sig.split_at(32);
+        tx_args.r = BigInt(num_bigint::BigInt::from_bytes_be(Sign::Plus, r));
+        tx_args.s = BigInt(num_bigint::BigInt::from_bytes_be(Sign::Plus, s));
+        tx_args.v = BigInt(recovery_id.serialize().into());
+
+        let raw_tx = tx_args.rlp_signed_message().unwrap();
+        let parsed = parse_eth_transaction(&raw_tx).unwrap();
+        assert_eq!(parsed, tx_args);
+
+        let pubkey = libsecp256k1::PublicKey::from_secret_key(&secret).serialize();
+        let expected_sender = EthAddress::try_from(&keccak(&pubkey[1..]).0[12..])
+            .unwrap()
+            .to_filecoin_address()
+            .unwrap();
+        let smsg = parsed.to_signed_message(314159).unwrap();
+        assert_eq!(smsg.message().from, expected_sender);
+        assert_eq!(
+            smsg.message().to,
+            FilecoinAddress::ETHEREUM_ACCOUNT_MANAGER_ACTOR
+        );
+        assert_eq!(smsg.message().sequence, 7);
+        assert_eq!(smsg.signature().signature_type(), SignatureType::Delegated);
+        assert_eq!(
+            eth_tx_from_signed_eth_message(&smsg, 314159)
+                .unwrap()
+                .eth_hash()
+                .unwrap(),
+            Hash(keccak(&raw_tx))
+        );
+
+        // Wrong chain
+        assert!(parsed.to_signed_message(314).is_err());
+        // Legacy transaction
+        assert!(parse_eth_transaction(&raw_tx[1..]).is_err());
+        // Trailing data
+        let mut trailing = raw_tx.clone();
+        trailing.push(0);
+        assert!(parse_eth_transaction(&trailing).is_err());
+    }
+
+    #[test]
+    fn test_eth_log_from_event() {
+        let entry = |key: &str, value: Vec<u8>| Entry {
+            flags: fvm_shared4::event::Flags::FLAG_INDEXED_ALL,
+            key: key.into(),
+            codec: IPLD_RAW,
+            value,
+        };
+
+        let (data, topics) = eth_log_from_event(&[
+            entry("t2", vec![2; 32]),
+            entry("t1", vec![1; 32]),
+            entry("d", vec![7]),
+        ])
+        .unwrap();
+        assert_eq!(data, EthBytes(vec![7]));
+        assert_eq!(
+            topics,
+            vec![
+                Hash(ethereum_types::H256([1; 32])),
+                Hash(ethereum_types::H256([2; 32]))
+            ]
+        );
+
+        // Skipped topic
+        assert!(eth_log_from_event(&[entry("t2", vec![2; 32])]).is_none());
+        // Duplicate topic
+        assert!(
+            eth_log_from_event(&[entry("t1", vec![1; 32]), entry("t1", vec![1; 32])]).is_none()
+        );
+        // Mis-sized topic
+        assert!(eth_log_from_event(&[entry("t1", vec![1; 31])]).is_none());
+    }
+
+    #[test]
+    fn test_hash_cid_roundtrip() {
+        let cid = Cid::from_str("bafy2bzaceaa466o2jfc4g4ggrmtf55ygigvkmxvkr5mvhy4qbwlxetbmlkqjk")
+            .unwrap();
+        assert_eq!(Hash::from(cid).to_cid(), cid);
+    }
+
     #[quickcheck]
     fn u64_roundtrip(i: u64) {
         let bm = format_u64(i);
@@ -1628,6 +3230,45 @@ mod test {
         assert_eq!(block.transactions_root, Hash::default());
     }
 
+    #[test]
+    fn test_parse_eth_revert() {
+        fn cbor_bytes(data: &[u8]) -> Vec<u8> {
+            fvm_ipld_encoding::to_vec(&RawBytes::new(data.to_vec())).unwrap()
+        }
+
+        assert_eq!(parse_eth_revert(&[]), "none");
+        assert_eq!(parse_eth_revert(&cbor_bytes(&[])), "none");
+        assert_eq!(
+            parse_eth_revert(&[0xff]),
+            "ERROR: revert reason is not cbor encoded bytes"
+        );
+        assert_eq!(parse_eth_revert(&cbor_bytes(&[0xde, 0xad])), "0xdead");
+
+        fn abi_word(value: u64) -> [u8; EVM_WORD_LENGTH] {
+            let mut word = [0; EVM_WORD_LENGTH];
+            word[EVM_WORD_LENGTH - 8..].copy_from_slice(&value.to_be_bytes());
+            word
+        }
+
+        // Panic(uint256) with code 0x11
+        let panic = [&PANIC_FUNCTION_SELECTOR[..], &abi_word(0x11)].concat();
+        assert_eq!(
+            parse_eth_revert(&cbor_bytes(&panic)),
+            "ArithmeticOverflow()"
+        );
+
+        // Error(string) with message "not owner"
+        let error = [
+            &ERROR_FUNCTION_SELECTOR[..],
+            &abi_word(EVM_WORD_LENGTH as u64),
+            &abi_word(9),
+            b"not owner",
+            &[0; EVM_WORD_LENGTH - 9],
+        ]
+        .concat();
+        assert_eq!(parse_eth_revert(&cbor_bytes(&error)), "Error(not owner)");
+    }
+
     #[test]
     fn test_tx_args_to_address_is_none() {
         let msg = Message {
@@ -1660,4 +3301,186 @@ mod test {
             EthAddress(H160::from_str("0xa251031ed6b4779e2a0b913683e71043d88002a3").unwrap())
         );
     }
+
+    /// Builds an RPC context whose head is a genesis tipset on top of `state_root`.
+    fn ctx_with_state(db: Arc<MemoryDB>, state_root: Cid) -> Ctx<MemoryDB> {
+        let chain_config = Arc::new(ChainConfig::default());
+        let genesis_header = CachingBlockHeader::new(RawBlockHeader {
+            miner_address: FilecoinAddress::new_id(0),
+            state_root,
+            timestamp: 7777,
+            ..Default::default()
+        });
+        crate::chain::persist_objects(&db, std::iter::once(&genesis_header)).unwrap();
+        let chain_store = Arc::new(
+            ChainStore::new(
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db,
+                chain_config.clone(),
+                genesis_header,
+            )
+            .unwrap(),
+        );
+        let state_manager = Arc::new(
+            StateManager::new(
+                chain_store.clone(),
+                chain_config.clone(),
+                Arc::new(SyncConfig::default()),
+            )
+            .unwrap(),
+        );
+        let (network_send, _) = flume::bounded(5);
+        let (tipset_send, _) = flume::bounded(5);
+        let mpool = MessagePool::new(
+            MpoolRpcProvider::new(chain_store.publisher().clone(), state_manager.clone()),
+            "test".to_string(),
+            network_send.clone(),
+            Default::default(),
+            chain_config,
+            &mut JoinSet::new(),
+        )
+        .unwrap();
+        Arc::new(RPCState {
+            state_manager,
+            keystore: Arc::new(RwLock::new(KeyStore::new(KeyStoreConfig::Memory).unwrap())),
+            mpool: Arc::new(mpool),
+            bad_blocks: Default::default(),
+            sync_state: Default::default(),
+            network_send,
+            network_name: "test".to_owned(),
+            start_time: chrono::Utc::now(),
+            chain_store,
+            beacon: Arc::new(BeaconSchedule(vec![BeaconPoint {
+                height: 0,
+                beacon: Box::<MockBeacon>::default(),
+            }])),
+            shutdown: mpsc::channel(1).0,
+            tipset_send,
+            eth_filters: Default::default(),
+            gc: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_receipt_pays_the_base_fee_of_the_including_tipset() {
+        let db = Arc::new(MemoryDB::default());
+        let state_root = StateTree::new(db.clone(), StateTreeVersion::V5)
+            .unwrap()
+            .flush()
+            .unwrap();
+        let ctx = ctx_with_state(db, state_root);
+        let tipset = |epoch, parent_base_fee| {
+            Arc::new(Tipset::from(CachingBlockHeader::new(RawBlockHeader {
+                miner_address: FilecoinAddress::new_id(0),
+                epoch,
+                state_root,
+                parent_base_fee: TokenAmount::from_atto(parent_base_fee),
+                ..Default::default()
+            })))
+        };
+        let gas_used = 1000;
+        let lookup = EthMessageLookup {
+            tipset: tipset(1, 100),
+            execution_tipset: tipset(2, 200),
+            message: ChainMessage::Unsigned(Message::default()),
+            index: 0,
+            receipt: Receipt_v3 {
+                exit_code: fvm_shared3::error::ExitCode::OK,
+                return_data: RawBytes::default(),
+                gas_used,
+                events_root: None,
+            }
+            .into(),
+        };
+        let tx = Tx {
+            to: Some(EthAddress::default()),
+            gas: Uint64(2 * gas_used),
+            max_fee_per_gas: TokenAmount::from_atto(500).into(),
+            max_priority_fee_per_gas: TokenAmount::from_atto(10).into(),
+            ..Tx::default()
+        };
+
+        let receipt = new_eth_tx_receipt(&ctx, &tx, &lookup).unwrap();
+        assert_eq!(
+            receipt.effective_gas_price,
+            effective_gas_price(&tx, &TokenAmount::from_atto(100), gas_used)
+        );
+        assert_ne!(
+            receipt.effective_gas_price,
+            effective_gas_price(&tx, &TokenAmount::from_atto(200), gas_used)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_pending_native_transaction_by_hash() {
+        let db = Arc::new(MemoryDB::default());
+        let key = generate_key(SignatureType::Secp256k1).unwrap();
+        let manifest = &ChainConfig::mainnet()
+            .actor_bundle_metadata(NetworkVersion::V22)
+            .unwrap()
+            .manifest;
+        let mut init_state =
+            fil_actor_init_state::v13::State::new(&db, "test".to_string()).unwrap();
+        let (sender_id, _) = init_state
+            .map_addresses_to_id(&db, &key.address.into(), None)
+            .unwrap();
+        let mut state = StateTree::new(db.clone(), StateTreeVersion::V5).unwrap();
+        state
+            .set_actor(
+                &FilecoinAddress::INIT_ACTOR,
+                ActorState::new(
+                    manifest.get(BuiltinActor::Init).unwrap(),
+                    db.put_cbor_default(&init_state).unwrap(),
+                    TokenAmount::default(),
+                    0,
+                    None,
+                ),
+            )
+            .unwrap();
+        state
+            .set_actor(
+                &FilecoinAddress::new_id(sender_id),
+                ActorState::new(
+                    manifest.get(BuiltinActor::Account).unwrap(),
+                    db.put_cbor_default(&fil_actor_account_state::v13::State {
+                        address: key.address.into(),
+                    })
+                    .unwrap(),
+                    TokenAmount::from_whole(1),
+                    0,
+                    None,
+                ),
+            )
+            .unwrap();
+        let ctx = ctx_with_state(db, state.flush().unwrap());
+
+        let message = Message {
+            from: key.address,
+            to: FilecoinAddress::new_id(sender_id),
+            gas_limit: 1_000_000,
+            gas_fee_cap: TokenAmount::from_atto(1_000),
+            gas_premium: TokenAmount::from_atto(100),
+            ..Message::default()
+        };
+        let signature = sign(
+            SignatureType::Secp256k1,
+            key.key_info.private_key(),
+            &message.cid().unwrap().to_bytes(),
+        )
+        .unwrap();
+        let smsg = SignedMessage::new_unchecked(message, signature);
+        ctx.mpool.add(smsg.clone()).unwrap();
+
+        let hash: Hash = smsg.cid().unwrap().into();
+        let tx = EthGetTransactionByHash::handle(ctx, (hash.clone(),))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tx.hash, hash);
+        assert_eq!(tx.from, EthAddress::from_actor_id(sender_id));
+        assert_eq!(tx.to, Some(EthAddress::from_actor_id(sender_id)));
+        assert_eq!(tx.gas, Uint64(1_000_000));
+    }
 }
@@ -0,0 +1,934 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Filtering of the actor events persisted by the [`ChainStore`] event index, as served by
+//! `Filecoin.EthGetLogs`, and the filters installed by Ethereum clients to poll for new logs,
+//! blocks and pending transactions.
+
+use super::*;
+use crate::chain::HeadChange;
+use crate::chain::IndexedEvent;
+use crate::rpc::chain::{impl_chain_get_path, PathChange};
+use ahash::{HashMap, HashSet};
+use anyhow::Context as _;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{error::TryRecvError, Receiver};
+
+/// Maximum number of epochs a single log query may span.
+pub const MAX_FILTER_HEIGHT_RANGE: ChainEpoch = 2880;
+
+/// Maximum number of tipsets whose events are not indexed yet that a single log query may
+/// execute. Queries needing more fail rather than tying up the node.
+pub const MAX_UNINDEXED_TIPSETS: usize = 10;
+
+/// A single address or a list of addresses, as accepted by the `address` field of a filter.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum EthAddressList {
+    List(Vec<EthAddress>),
+    Single(EthAddress),
+}
+
+impl Default for EthAddressList {
+    fn default() -> Self {
+        Self::List(vec![])
+    }
+}
+
+impl EthAddressList {
+    pub fn addresses(&self) -> &[EthAddress] {
+        match self {
+            Self::List(addresses) => addresses,
+            Self::Single(address) => std::slice::from_ref(address),
+        }
+    }
+}
+
+/// The set of accepted values for a topic position. `null` and the empty list match any topic.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum EthHashList {
+    List(Vec<Hash>),
+    Single(Option<Hash>),
+}
+
+impl Default for EthHashList {
+    fn default() -> Self {
+        Self::Single(None)
+    }
+}
+
+impl EthHashList {
+    pub fn hashes(&self) -> &[Hash] {
+        match self {
+            Self::List(hashes) => hashes,
+            Self::Single(Some(hash)) => std::slice::from_ref(hash),
+            Self::Single(None) => &[],
+        }
+    }
+}
+
+/// Filter criteria of log queries. See <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getlogs>
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthFilterSpec {
+    /// Interpreted as an epoch (in hex) or one of "latest" for the last mined block, "earliest"
+    /// for the earliest block or "pending" for not yet committed messages. Defaults to "latest".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<String>,
+    /// Same as `from_block`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<String>,
+    /// Contract addresses from which logs should originate. Any address matches when empty.
+    #[serde(default)]
+    pub address: EthAddressList,
+    /// Topics to match, by position. Any topic matches when empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topics: Option<Vec<EthHashList>>,
+    /// Restricts the logs to a single tipset. Mutually exclusive with `from_block` and
+    /// `to_block`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<Hash>,
+}
+
+lotus_json_with_self!(EthFilterSpec);
+
+/// Result of log and filter queries: either the matching logs, or the hashes of new blocks or
+/// pending transactions.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum EthFilterResult {
+    Logs(Vec<EthLog>),
+    Hashes(Vec<Hash>),
+}
+
+impl Default for EthFilterResult {
+    fn default() -> Self {
+        Self::Logs(vec![])
+    }
+}
+
+lotus_json_with_self!(EthFilterResult);
+
+fn parse_block_height(block: Option<&str>, head: ChainEpoch) -> Result<ChainEpoch> {
+    match block {
+        None | Some("") | Some("latest") => Ok(head - 1),
+        Some("pending") => Ok(head),
+        Some("earliest") => Ok(0),
+        Some(block) => {
+            // `from_str_radix` accepts a leading sign, which is not valid in a hex quantity.
+            let Some(hex) = block
+                .strip_prefix("0x")
+                .filter(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+            else {
+                bail!("block number is not a hex: {block}");
+            };
+            Ok(ChainEpoch::from_str_radix(hex, 16)?)
+        }
+    }
+}
+
+/// Resolves the inclusive range of epochs covered by `from_block` and `to_block`. The
+/// "latest" block is the parent of `head`, as it is the last one whose messages have been
+/// executed.
+fn parse_block_range(
+    head: ChainEpoch,
+    from_block: Option<&str>,
+    to_block: Option<&str>,
+    max_range: ChainEpoch,
+) -> Result<(ChainEpoch, ChainEpoch)> {
+    let min_height = parse_block_height(from_block, head)?;
+    let max_height = parse_block_height(to_block, head)?;
+    if max_height > head {
+        bail!("to block {max_height} is beyond the chain head {head}");
+    }
+    if max_height - min_height > max_range {
+        bail!("invalid epoch range: to block is too far in the future (maximum: {max_range})");
+    }
+    Ok((min_height, max_height))
+}
+
+/// Returns `true` if the topics of a log satisfy the per-position constraints of a filter.
+fn topics_match(filter: &[EthHashList], topics: &[Hash]) -> bool {
+    filter.iter().enumerate().all(|(i, accepted)| {
+        let accepted = accepted.hashes();
+        accepted.is_empty() || topics.get(i).is_some_and(|topic| accepted.contains(topic))
+    })
+}
+
+/// Returns the canonical tipsets covered by the filter, in ascending order. Tipsets that were
+/// reorged out of the canonical chain never match.
+fn filter_tipsets<DB: Blockstore>(
+    chain_store: &ChainStore<DB>,
+    spec: &EthFilterSpec,
+) -> Result<Vec<Arc<Tipset>>> {
+    let head = chain_store.heaviest_tipset();
+
+    if let Some(block_hash) = &spec.block_hash {
+        if spec.from_block.is_some() || spec.to_block.is_some() {
+            bail!("must not specify block hash and from/to block");
+        }
+        let tsk = chain_store.get_required_tipset_key(block_hash)?;
+        let ts = chain_store.chain_index.load_required_tipset(&tsk)?;
+        let canonical = chain_store.chain_index.tipset_by_height(
+            ts.epoch(),
+            head,
+            ResolveNullTipset::TakeOlder,
+        )?;
+        return Ok(if canonical == ts { vec![ts] } else { vec![] });
+    }
+
+    let (from, to) = parse_block_range(
+        head.epoch(),
+        spec.from_block.as_deref(),
+        spec.to_block.as_deref(),
+        MAX_FILTER_HEIGHT_RANGE,
+    )?;
+    if from > to {
+        return Ok(vec![]);
+    }
+
+    let mut tipsets = vec![];
+    let mut ts =
+        chain_store
+            .chain_index
+            .tipset_by_height(to, head, ResolveNullTipset::TakeOlder)?;
+    while ts.epoch() >= from {
+        let parent = (ts.epoch() > 0)
+            .then(|| chain_store.chain_index.load_required_tipset(ts.parents()))
+            .transpose()?;
+        tipsets.push(ts);
+        match parent {
+            Some(parent) => ts = parent,
+            None => break,
+        }
+    }
+    tipsets.reverse();
+    Ok(tipsets)
+}
+
+/// Returns the Ethereum hash of a signed message. Only messages signed by Ethereum accounts have
+/// a hash distinct from their [`Cid`].
+pub(super) fn eth_tx_hash_from_signed_message(smsg: &SignedMessage, chain_id: u32) -> Result<Hash> {
+    if smsg.is_delegated() {
+        eth_tx_from_signed_eth_message(smsg, chain_id)?.eth_hash()
+    } else {
+        Ok(smsg.cid()?.into())
+    }
+}
+
+/// Returns the Ethereum hash of the transaction with the given message [`Cid`].
+pub(super) fn eth_tx_hash_from_message_cid<DB: Blockstore>(
+    db: &DB,
+    cid: &Cid,
+    chain_id: u32,
+) -> Result<Hash> {
+    match crate::chain::get_chain_message(db, cid)? {
+        ChainMessage::Signed(smsg) => eth_tx_hash_from_signed_message(&smsg, chain_id),
+        ChainMessage::Unsigned(_) => Ok((*cid).into()),
+    }
+}
+
+/// Returns the logs emitted by the canonical tipsets matching the filter. Tipsets that have
+/// not been indexed yet are executed first.
+pub async fn eth_get_logs<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    spec: &EthFilterSpec,
+) -> Result<Vec<EthLog>> {
+    let tipsets = filter_tipsets(&ctx.chain_store, spec)?;
+    tipset_logs(ctx, spec, tipsets).await
+}
+
+/// Returns the logs emitted by the messages of the given tipsets that match the address and
+/// topic criteria of the filter.
+/// Fails if more than [`MAX_UNINDEXED_TIPSETS`] of the given tipsets would have to be executed to
+/// index their events, before any of them is.
+fn ensure_few_unindexed_tipsets<DB: Blockstore>(
+    chain_store: &ChainStore<DB>,
+    tipsets: &[Arc<Tipset>],
+) -> Result<()> {
+    let mut unindexed = 0;
+    for ts in tipsets {
+        if !chain_store.tipset_events_indexed(ts.key())? {
+            unindexed += 1;
+        }
+    }
+    if unindexed > MAX_UNINDEXED_TIPSETS {
+        bail!("the events of {unindexed} tipsets in range are not indexed yet, at most {MAX_UNINDEXED_TIPSETS} can be, narrow the range");
+    }
+    Ok(())
+}
+
+async fn tipset_logs<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    spec: &EthFilterSpec,
+    tipsets: Vec<Arc<Tipset>>,
+) -> Result<Vec<EthLog>> {
+    let chain_store = ctx.chain_store.as_ref();
+
+    // Events are indexed with the ID and delegated addresses their emitter had at the time, so
+    // addresses are matched without resolving them against the current state.
+    let emitters = if spec.address.addresses().is_empty() {
+        None
+    } else {
+        Some(
+            spec.address
+                .addresses()
+                .iter()
+                .map(EthAddress::to_filecoin_address)
+                .collect::<Result<Vec<_>>>()?,
+        )
+    };
+    let topics = spec.topics.as_deref().unwrap_or_default();
+    let chain_id = ctx.state_manager.chain_config().eth_chain_id;
+
+    ensure_few_unindexed_tipsets(chain_store, &tipsets)?;
+
+    let mut tx_hashes: HashMap<Cid, Hash> = HashMap::default();
+    let mut logs = vec![];
+    for ts in tipsets {
+        let events = match chain_store.tipset_events(ts.key(), emitters.as_deref())? {
+            Some(events) => events,
+            None => {
+                let (state_root, receipts_root) = ctx.state_manager.tipset_state(&ts).await?;
+                chain_store.index_tipset_events(&ts, &state_root, &receipts_root)?;
+                chain_store
+                    .tipset_events(ts.key(), emitters.as_deref())?
+                    .unwrap_or_default()
+            }
+        };
+        let block_hash: Hash = ts.key().cid()?.into();
+        for IndexedEvent {
+            emitter,
+            delegated_address,
+            event_index,
+            message_index,
+            message_cid,
+            entries,
+        } in events
+        {
+            let Some((data, event_topics)) = eth_log_from_event(&entries) else {
+                continue;
+            };
+            if !topics_match(topics, &event_topics) {
+                continue;
+            }
+            let address = delegated_address
+                .and_then(|address| EthAddress::from_filecoin_address(&address).ok())
+                .filter(|address| !address.is_masked_id())
+                .unwrap_or_else(|| EthAddress::from_actor_id(emitter));
+            let transaction_hash = match tx_hashes.get(&message_cid) {
+                Some(hash) => hash.clone(),
+                None => {
+                    let hash = eth_tx_hash_from_message_cid(ctx.store(), &message_cid, chain_id)?;
+                    tx_hashes.insert(message_cid, hash.clone());
+                    hash
+                }
+            };
+            logs.push(EthLog {
+                address,
+                data,
+                topics: event_topics,
+                removed: false,
+                log_index: Uint64(event_index),
+                transaction_index: Uint64(message_index),
+                transaction_hash,
+                block_hash: block_hash.clone(),
+                block_number: Uint64(ts.epoch() as u64),
+            });
+        }
+    }
+    Ok(logs)
+}
+
+/// Identifier of a filter installed with `Filecoin.EthNew*Filter`.
+pub type FilterID = Hash;
+
+/// How long a filter is kept installed without being polled.
+pub const FILTER_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Maximum number of filters that can be installed at the same time.
+pub const MAX_FILTERS: usize = 100;
+
+enum FilterKind {
+    /// Collects the logs of the tipsets executed since the last poll.
+    Event {
+        spec: EthFilterSpec,
+        head_changes: HeadChanges,
+    },
+    /// Collects the hashes of the tipsets applied since the last poll.
+    Block { head_changes: HeadChanges },
+    /// Collects the hashes of the messages added to the message pool since the last poll.
+    PendingTransaction { seen: HashSet<Cid> },
+}
+
+struct InstalledFilter {
+    kind: FilterKind,
+    last_polled: Instant,
+}
+
+/// Keeps track of the filters installed by Ethereum clients between polls. Filters that are
+/// not polled for longer than the TTL are uninstalled.
+pub struct EthFilterManager {
+    filters: Mutex<HashMap<FilterID, InstalledFilter>>,
+    ttl: Duration,
+}
+
+impl Default for EthFilterManager {
+    fn default() -> Self {
+        Self::new(FILTER_TTL)
+    }
+}
+
+impl EthFilterManager {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            filters: Default::default(),
+            ttl,
+        }
+    }
+
+    fn install(&self, kind: FilterKind) -> Result<FilterID> {
+        let mut filters = self.filters.lock();
+        self.remove_expired(&mut filters);
+        if filters.len() >= MAX_FILTERS {
+            bail!("maximum number of filters registered");
+        }
+        let id = Hash(ethereum_types::H256(rand::random()));
+        filters.insert(
+            id.clone(),
+            InstalledFilter {
+                kind,
+                last_polled: Instant::now(),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Removes the filter, returning `false` if it was not installed.
+    pub fn uninstall(&self, id: &FilterID) -> bool {
+        self.filters.lock().remove(id).is_some()
+    }
+
+    /// Runs `f` on the installed filter and marks it as polled.
+    fn poll<T>(&self, id: &FilterID, f: impl FnOnce(&mut FilterKind) -> T) -> Result<T> {
+        let mut filters = self.filters.lock();
+        self.remove_expired(&mut filters);
+        let filter = filters.get_mut(id).context("filter not found")?;
+        filter.last_polled = Instant::now();
+        Ok(f(&mut filter.kind))
+    }
+
+    fn remove_expired(&self, filters: &mut HashMap<FilterID, InstalledFilter>) {
+        filters.retain(|_, filter| filter.last_polled.elapsed() < self.ttl);
+    }
+}
+
+/// The head changes published since a filter was last polled.
+struct HeadChanges {
+    receiver: Receiver<HeadChange>,
+    /// The last head delivered, from which the path to the current head is computed.
+    last: Arc<Tipset>,
+}
+
+impl HeadChanges {
+    fn subscribe(chain_store: &ChainStore<impl Blockstore>) -> Self {
+        // Subscribe before reading the head, so that no head change is missed in between.
+        let receiver = chain_store.publisher().subscribe();
+        Self {
+            receiver,
+            last: chain_store.heaviest_tipset(),
+        }
+    }
+
+    /// Drains the published head changes and returns the tipsets reverted and applied since
+    /// the last poll. Only the new heads are published, so the path is computed from the last
+    /// head delivered, which also covers the tipsets skipped by multi-epoch jumps, re-orgs and
+    /// the head changes missed when the receiver lagged behind.
+    fn changes(&mut self, chain_store: &ChainStore<impl Blockstore>) -> Result<Vec<PathChange>> {
+        let mut head = None;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(HeadChange::Apply(ts)) => head = Some(ts),
+                Err(TryRecvError::Lagged(_)) => {
+                    self.receiver = self.receiver.resubscribe();
+                    head = Some(chain_store.heaviest_tipset());
+                }
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+        let Some(head) = head else {
+            return Ok(vec![]);
+        };
+        let path = impl_chain_get_path(chain_store, self.last.key(), head.key())?;
+        self.last = head;
+        Ok(path)
+    }
+
+    /// Returns the tipsets applied since the last poll.
+    fn applied(&mut self, chain_store: &ChainStore<impl Blockstore>) -> Result<Vec<Arc<Tipset>>> {
+        Ok(self
+            .changes(chain_store)?
+            .into_iter()
+            .filter_map(|change| match change {
+                PathChange::Apply(ts) => Some(ts),
+                PathChange::Revert(_) => None,
+            })
+            .collect())
+    }
+}
+
+/// Returns the messages of `pending` that are not in `seen`, and replaces `seen` with the
+/// messages of `pending`.
+fn new_pending_messages(
+    pending: Vec<SignedMessage>,
+    seen: &mut HashSet<Cid>,
+) -> Vec<SignedMessage> {
+    let mut current = HashSet::default();
+    let mut added = vec![];
+    for smsg in pending {
+        if let Ok(cid) = smsg.cid() {
+            if !seen.contains(&cid) {
+                added.push(smsg);
+            }
+            current.insert(cid);
+        }
+    }
+    *seen = current;
+    added
+}
+
+/// Returns the logs matching an event filter that were emitted by the tipsets executed as
+/// part of applying the given tipsets, i.e. by their parents.
+pub(super) async fn applied_tipsets_logs<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    spec: &EthFilterSpec,
+    applied: Vec<Arc<Tipset>>,
+) -> Result<Vec<EthLog>> {
+    let (from, to) = filter_height_bounds(spec)?;
+    let mut tipsets = vec![];
+    for ts in applied {
+        let executed = ctx
+            .chain_store
+            .chain_index
+            .load_required_tipset(ts.parents())?;
+        let in_range = from.map_or(true, |from| executed.epoch() >= from)
+            && to.map_or(true, |to| executed.epoch() <= to);
+        let matches_hash = match &spec.block_hash {
+            Some(hash) => *hash == executed.key().cid()?.into(),
+            None => true,
+        };
+        if in_range && matches_hash {
+            tipsets.push(executed);
+        }
+    }
+    tipset_logs(ctx, spec, tipsets).await
+}
+
+/// Returns the logs matching an event filter along a head change path. The logs of the
+/// reverted tipsets are returned again, marked as removed.
+async fn path_changes_logs<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    spec: &EthFilterSpec,
+    path: Vec<PathChange>,
+) -> Result<Vec<EthLog>> {
+    let mut logs = vec![];
+    for change in path {
+        let (ts, removed) = match change {
+            PathChange::Revert(ts) => (ts, true),
+            PathChange::Apply(ts) => (ts, false),
+        };
+        logs.extend(
+            applied_tipsets_logs(ctx, spec, vec![ts])
+                .await?
+                .into_iter()
+                .map(|log| EthLog { removed, ..log }),
+        );
+    }
+    Ok(logs)
+}
+
+/// Returns the bounds set by the explicit `from_block` and `to_block` of an event filter.
+/// Filters following "latest" are unbounded.
+fn filter_height_bounds(spec: &EthFilterSpec) -> Result<(Option<ChainEpoch>, Option<ChainEpoch>)> {
+    let bound = |block: Option<&str>| match block {
+        None | Some("") | Some("latest") | Some("pending") => Ok(None),
+        block => parse_block_height(block, 0).map(Some),
+    };
+    Ok((
+        bound(spec.from_block.as_deref())?,
+        bound(spec.to_block.as_deref())?,
+    ))
+}
+
+pub fn eth_new_filter<DB: Blockstore>(ctx: &Ctx<DB>, spec: EthFilterSpec) -> Result<FilterID> {
+    // Reject invalid specs on installation rather than on every poll.
+    filter_height_bounds(&spec)?;
+    if spec.block_hash.is_some() && (spec.from_block.is_some() || spec.to_block.is_some()) {
+        bail!("must not specify block hash and from/to block");
+    }
+    ctx.eth_filters.install(FilterKind::Event {
+        spec,
+        head_changes: HeadChanges::subscribe(&ctx.chain_store),
+    })
+}
+
+pub fn eth_new_block_filter<DB: Blockstore>(ctx: &Ctx<DB>) -> Result<FilterID> {
+    ctx.eth_filters.install(FilterKind::Block {
+        head_changes: HeadChanges::subscribe(&ctx.chain_store),
+    })
+}
+
+pub fn eth_new_pending_transaction_filter<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+) -> Result<FilterID> {
+    let (pending, _) = ctx.mpool.pending()?;
+    let seen = pending.iter().map(|smsg| smsg.cid()).try_collect()?;
+    ctx.eth_filters
+        .install(FilterKind::PendingTransaction { seen })
+}
+
+/// Returns what happened since the filter was last polled.
+pub async fn eth_get_filter_changes<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    id: &FilterID,
+) -> Result<EthFilterResult> {
+    let chain_id = ctx.state_manager.chain_config().eth_chain_id;
+
+    enum Changes {
+        Logs(EthFilterSpec, Vec<PathChange>),
+        Blocks(Vec<Arc<Tipset>>),
+        Messages(Vec<SignedMessage>),
+    }
+    let is_pending_transaction_filter = ctx.eth_filters.poll(id, |kind| {
+        matches!(kind, FilterKind::PendingTransaction { .. })
+    })?;
+    // The pending messages are fetched before polling again, so that the filters lock is not
+    // held while locking the message pool. Other filters don't need them.
+    let pending = if is_pending_transaction_filter {
+        ctx.mpool.pending()?.0
+    } else {
+        vec![]
+    };
+    let changes = ctx.eth_filters.poll(id, |kind| -> Result<_> {
+        Ok(match kind {
+            FilterKind::Event { spec, head_changes } => {
+                Changes::Logs(spec.clone(), head_changes.changes(&ctx.chain_store)?)
+            }
+            FilterKind::Block { head_changes } => {
+                Changes::Blocks(head_changes.applied(&ctx.chain_store)?)
+            }
+            FilterKind::PendingTransaction { seen } => {
+                Changes::Messages(new_pending_messages(pending, seen))
+            }
+        })
+    })??;
+
+    match changes {
+        Changes::Logs(spec, path) => Ok(EthFilterResult::Logs(
+            path_changes_logs(ctx, &spec, path).await?,
+        )),
+        Changes::Blocks(applied) => Ok(EthFilterResult::Hashes(
+            applied
+                .iter()
+                .map(|ts| Ok(ts.key().cid()?.into()))
+                .collect::<Result<_>>()?,
+        )),
+        Changes::Messages(added) => Ok(EthFilterResult::Hashes(
+            added
+                .iter()
+                .map(|smsg| eth_tx_hash_from_signed_message(smsg, chain_id))
+                .collect::<Result<_>>()?,
+        )),
+    }
+}
+
+/// Returns all the logs matching an event filter.
+pub async fn eth_get_filter_logs<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    id: &FilterID,
+) -> Result<EthFilterResult> {
+    let spec = ctx.eth_filters.poll(id, |kind| match kind {
+        FilterKind::Event { spec, .. } => Some(spec.clone()),
+        _ => None,
+    })?;
+    let spec = spec.context("wrong filter type")?;
+    Ok(EthFilterResult::Logs(eth_get_logs(ctx, &spec).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{chain4u, CachingBlockHeader, HeaderBuilder, RawBlockHeader, TipsetKey};
+
+    #[test]
+    fn parse_block_range_defaults_to_latest() {
+        assert_eq!(parse_block_range(100, None, None, 2880).unwrap(), (99, 99));
+        assert_eq!(
+            parse_block_range(100, Some("earliest"), Some("0x10"), 2880).unwrap(),
+            (0, 16)
+        );
+        assert_eq!(
+            parse_block_range(100, Some("0x5f"), Some("pending"), 2880).unwrap(),
+            (95, 100)
+        );
+        // Not a hex.
+        parse_block_range(100, Some("95"), None, 2880).unwrap_err();
+        parse_block_range(100, Some("0x-1"), None, 2880).unwrap_err();
+        parse_block_range(100, Some("0x+1"), None, 2880).unwrap_err();
+        parse_block_range(100, Some("0x"), None, 2880).unwrap_err();
+        // Beyond the head.
+        parse_block_range(100, None, Some("0x65"), 2880).unwrap_err();
+        // Too wide.
+        parse_block_range(100, Some("earliest"), None, 10).unwrap_err();
+    }
+
+    #[test]
+    fn topics_match_by_position() {
+        let a = Hash(ethereum_types::H256::repeat_byte(0xa));
+        let b = Hash(ethereum_types::H256::repeat_byte(0xb));
+        let topics = [a.clone(), b.clone()];
+
+        assert!(topics_match(&[], &topics));
+        assert!(topics_match(
+            &[EthHashList::Single(Some(a.clone()))],
+            &topics
+        ));
+        assert!(topics_match(
+            &[
+                EthHashList::Single(None),
+                EthHashList::List(vec![a.clone(), b.clone()])
+            ],
+            &topics
+        ));
+        assert!(!topics_match(
+            &[EthHashList::Single(Some(b.clone()))],
+            &topics
+        ));
+        assert!(!topics_match(
+            &[
+                EthHashList::default(),
+                EthHashList::default(),
+                EthHashList::Single(Some(a))
+            ],
+            &topics
+        ));
+    }
+
+    #[test]
+    fn filter_spec_deserialize() {
+        let spec: EthFilterSpec = serde_json::from_str(
+            r#"{"fromBlock":"0x1","address":"0xff00000000000000000000000000000000000400","topics":[null,["0x0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a"]]}"#,
+        )
+        .unwrap();
+        assert_eq!(spec.from_block.as_deref(), Some("0x1"));
+        assert_eq!(spec.to_block, None);
+        assert_eq!(spec.address.addresses().len(), 1);
+        let topics = spec.topics.unwrap();
+        assert!(topics[0].hashes().is_empty());
+        assert_eq!(
+            topics[1].hashes(),
+            [Hash(ethereum_types::H256::repeat_byte(0xa))]
+        );
+    }
+
+    #[test]
+    fn filter_manager_lifecycle() {
+        let manager = EthFilterManager::default();
+        let id = manager
+            .install(FilterKind::PendingTransaction {
+                seen: HashSet::default(),
+            })
+            .unwrap();
+        assert!(manager.poll(&id, |_| ()).is_ok());
+        assert!(manager.uninstall(&id));
+        assert!(!manager.uninstall(&id));
+        manager.poll(&id, |_| ()).unwrap_err();
+    }
+
+    #[test]
+    fn filter_manager_expires_filters() {
+        let manager = EthFilterManager::new(Duration::ZERO);
+        let id = manager
+            .install(FilterKind::PendingTransaction {
+                seen: HashSet::default(),
+            })
+            .unwrap();
+        manager.poll(&id, |_| ()).unwrap_err();
+    }
+
+    #[test]
+    fn filter_manager_collects_head_changes() {
+        let store = ChainStore::calibnet();
+        chain4u! {
+            in store.blockstore();
+            [_genesis = store.genesis_block_header()] -> [a] -> [b]
+        };
+        let manager = EthFilterManager::default();
+        let id = manager
+            .install(FilterKind::Block {
+                head_changes: HeadChanges::subscribe(&store),
+            })
+            .unwrap();
+        for header in [&a, &b] {
+            store.set_heaviest_tipset(Arc::new(tipset(header))).unwrap();
+        }
+        let poll = || {
+            manager
+                .poll(&id, |kind| match kind {
+                    FilterKind::Block { head_changes } => head_changes.applied(&store).unwrap(),
+                    _ => unreachable!(),
+                })
+                .unwrap()
+        };
+        assert_eq!(
+            keys(&poll()),
+            [tipset(a).key().clone(), tipset(b).key().clone()]
+        );
+        assert!(poll().is_empty());
+    }
+
+    #[test]
+    fn head_changes_recover_from_lagging() {
+        let store = ChainStore::calibnet();
+        chain4u! {
+            in store.blockstore();
+            [_genesis = store.genesis_block_header()] -> [a]
+        };
+        store.set_heaviest_tipset(Arc::new(tipset(a))).unwrap();
+        let mut head_changes = HeadChanges::subscribe(&store);
+        // more head changes than the publisher buffers
+        let mut parent = "a".to_owned();
+        let headers = (0..300)
+            .map(|i| {
+                let name = format!("a-{i}");
+                let header = store
+                    .blockstore()
+                    .insert(&[&parent], &name, HeaderBuilder::new());
+                store
+                    .set_heaviest_tipset(Arc::new(tipset(&header)))
+                    .unwrap();
+                parent = name;
+                header
+            })
+            .collect::<Vec<_>>();
+
+        let applied = head_changes.applied(&store).unwrap();
+        assert_eq!(
+            keys(&applied),
+            headers
+                .iter()
+                .map(|it| tipset(it).key().clone())
+                .collect::<Vec<_>>()
+        );
+        assert!(head_changes.applied(&store).unwrap().is_empty());
+    }
+
+    #[test]
+    fn head_changes_cover_skipped_and_reverted_tipsets() {
+        let store = ChainStore::calibnet();
+        chain4u! {
+            in store.blockstore();
+            [_genesis = store.genesis_block_header()] -> [a] -> [b1] -> [c1]
+        };
+        chain4u! {
+            from [a] in store.blockstore();
+            [b2]
+        };
+        let mut head_changes = HeadChanges::subscribe(&store);
+        let key = |header: &RawBlockHeader| tipset(header).key().clone();
+        let path_keys = |path: Vec<PathChange>| {
+            path.into_iter()
+                .map(|change| match change {
+                    PathChange::Revert(ts) => PathChange::Revert(ts.key().clone()),
+                    PathChange::Apply(ts) => PathChange::Apply(ts.key().clone()),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Only the new head is published when it jumps several epochs.
+        store.set_heaviest_tipset(Arc::new(tipset(c1))).unwrap();
+        assert_eq!(
+            path_keys(head_changes.changes(&store).unwrap()),
+            [
+                PathChange::Apply(key(a)),
+                PathChange::Apply(key(b1)),
+                PathChange::Apply(key(c1))
+            ]
+        );
+
+        // re-org
+        store.set_heaviest_tipset(Arc::new(tipset(b2))).unwrap();
+        assert_eq!(
+            path_keys(head_changes.changes(&store).unwrap()),
+            [
+                PathChange::Revert(key(c1)),
+                PathChange::Revert(key(b1)),
+                PathChange::Apply(key(b2))
+            ]
+        );
+        assert!(head_changes.changes(&store).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unindexed_tipsets_are_counted_before_any_execution() {
+        use crate::blocks::TxMeta;
+        use crate::shim::state_tree::StateTreeVersion;
+        use crate::utils::db::CborStoreExt as _;
+        use fil_actors_shared::fvm_ipld_amt::Amtv0 as Amt;
+
+        let store = ChainStore::calibnet();
+        let db = store.blockstore();
+        let empty_amt = Amt::<Cid, _>::new(db).flush().unwrap();
+        let messages = db
+            .put_cbor_default(&TxMeta {
+                bls_message_root: empty_amt,
+                secp_message_root: empty_amt,
+            })
+            .unwrap();
+        let state_root = StateTree::new(Arc::new(db), StateTreeVersion::V5)
+            .unwrap()
+            .flush()
+            .unwrap();
+        let tipsets = (1..=MAX_UNINDEXED_TIPSETS as ChainEpoch + 1)
+            .map(|epoch| {
+                Arc::new(tipset(&RawBlockHeader {
+                    epoch,
+                    state_root,
+                    messages,
+                    ..Default::default()
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        ensure_few_unindexed_tipsets(&store, &tipsets).unwrap_err();
+        store
+            .index_tipset_events(&tipsets[0], &state_root, &empty_amt)
+            .unwrap();
+        ensure_few_unindexed_tipsets(&store, &tipsets).unwrap();
+    }
+
+    fn tipset(header: &RawBlockHeader) -> Tipset {
+        Tipset::from(CachingBlockHeader::new(header.clone()))
+    }
+
+    fn keys(tipsets: &[Arc<Tipset>]) -> Vec<TipsetKey> {
+        tipsets.iter().map(|ts| ts.key().clone()).collect()
+    }
+
+    #[test]
+    fn filter_height_bounds_follow_latest() {
+        let spec = EthFilterSpec {
+            from_block: Some("0x10".into()),
+            ..Default::default()
+        };
+        assert_eq!(filter_height_bounds(&spec).unwrap(), (Some(16), None));
+        let spec = EthFilterSpec {
+            from_block: Some("earliest".into()),
+            to_block: Some("0x20".into()),
+            ..Default::default()
+        };
+        assert_eq!(filter_height_bounds(&spec).unwrap(), (Some(0), Some(32)));
+    }
+}
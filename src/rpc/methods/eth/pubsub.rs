@@ -0,0 +1,242 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Ethereum subscriptions, served over WebSocket connections.
+//!
+//! A subscription is opened with `Filecoin.EthSubscribe`, taking the event type and, for `logs`,
+//! an optional filter. The node then pushes `eth_subscription` notifications until
+//! `Filecoin.EthUnsubscribe` is called with the returned subscription identifier:
+//! - `newHeads` streams the applied tipsets as [`Block`]s, without transactions.
+//! - `logs` streams the [`EthLog`]s matching the filter, as tipsets get executed. The logs of
+//!   tipsets reverted by a reorg are sent again with `removed` set.
+//! - `newPendingTransactions` streams the hashes of the messages added to the message pool.
+
+use super::filter::{applied_tipsets_logs, eth_tx_hash_from_signed_message};
+use super::*;
+use crate::chain::HeadChange;
+use crate::rpc::chain::{impl_chain_get_path, PathChange};
+use jsonrpsee::types::Params;
+use tokio::sync::broadcast::{error::RecvError, Receiver as Subscriber};
+use tokio::sync::mpsc;
+
+pub const ETH_SUBSCRIBE: &str = "Filecoin.EthSubscribe";
+pub const ETH_SUBSCRIBE_ALIAS: &str = "eth_subscribe";
+pub const ETH_UNSUBSCRIBE: &str = "Filecoin.EthUnsubscribe";
+pub const ETH_UNSUBSCRIBE_ALIAS: &str = "eth_unsubscribe";
+
+/// Capacity of the channel between the producer of a subscription and the connection. The
+/// producer waits for the client once it is full, so that no notification is dropped.
+const SUBSCRIPTION_CAPACITY: usize = 100;
+
+type SubscriptionSender = mpsc::Sender<Result<EthSubscriptionResult, ServerError>>;
+
+#[derive(PartialEq, Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EthSubscriptionKind {
+    NewHeads,
+    Logs,
+    NewPendingTransactions,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum EthSubscriptionResult {
+    Block(Box<Block>),
+    Log(EthLog),
+    TransactionHash(Hash),
+}
+
+pub(crate) fn eth_subscribe<DB: Blockstore + Send + Sync + 'static>(
+    params: Params<'_>,
+    ctx: &Ctx<DB>,
+) -> Result<mpsc::Receiver<Result<EthSubscriptionResult, ServerError>>, ServerError> {
+    let mut params = params.sequence();
+    let kind: EthSubscriptionKind = params.next()?;
+    let spec: Option<EthFilterSpec> = params.optional_next()?;
+
+    let (sender, receiver) = mpsc::channel(SUBSCRIPTION_CAPACITY);
+    let ctx = ctx.clone();
+    match kind {
+        EthSubscriptionKind::NewHeads => {
+            // Subscribe before reading the head, so that no head change is missed in between.
+            let head_changes = ctx.chain_store.publisher().subscribe();
+            let head = ctx.chain_store.heaviest_tipset();
+            tokio::spawn(stream_new_heads(ctx, head, head_changes, sender));
+        }
+        EthSubscriptionKind::Logs => {
+            // Subscribe before reading the head, so that no head change is missed in between.
+            let head_changes = ctx.chain_store.publisher().subscribe();
+            let head = ctx.chain_store.heaviest_tipset();
+            tokio::spawn(stream_logs(
+                ctx,
+                spec.unwrap_or_default(),
+                head,
+                head_changes,
+                sender,
+            ));
+        }
+        EthSubscriptionKind::NewPendingTransactions => {
+            let added = ctx.mpool.subscribe_added();
+            tokio::spawn(stream_pending_transactions(ctx, added, sender));
+        }
+    }
+    Ok(receiver)
+}
+
+async fn stream_new_heads<DB: Blockstore + Send + Sync + 'static>(
+    ctx: Ctx<DB>,
+    mut last: Arc<Tipset>,
+    mut head_changes: Subscriber<HeadChange>,
+    sender: SubscriptionSender,
+) {
+    loop {
+        let next = match head_changes.recv().await {
+            Ok(HeadChange::Apply(ts)) => ts,
+            // The missed head changes are recovered below, as the path is computed from the
+            // last head.
+            Err(RecvError::Lagged(_)) => {
+                head_changes = head_changes.resubscribe();
+                ctx.chain_store.heaviest_tipset()
+            }
+            Err(RecvError::Closed) => break,
+        };
+        let path = match impl_chain_get_path(&ctx.chain_store, last.key(), next.key()) {
+            Ok(path) => path,
+            Err(e) => {
+                // The error is reported to the client as the subscription is closed.
+                let _ = sender.send(Err(e.into())).await;
+                return;
+            }
+        };
+        last = next;
+        for change in path {
+            let PathChange::Apply(ts) = change else {
+                continue;
+            };
+            let block = match block_from_filecoin_tipset(ctx.clone(), ts, false).await {
+                Ok(block) => block,
+                Err(e) => {
+                    let _ = sender.send(Err(e.into())).await;
+                    return;
+                }
+            };
+            if sender
+                .send(Ok(EthSubscriptionResult::Block(Box::new(block))))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+}
+
+async fn stream_logs<DB: Blockstore + Send + Sync + 'static>(
+    ctx: Ctx<DB>,
+    spec: EthFilterSpec,
+    mut last: Arc<Tipset>,
+    mut head_changes: Subscriber<HeadChange>,
+    sender: SubscriptionSender,
+) {
+    loop {
+        let next = match head_changes.recv().await {
+            Ok(HeadChange::Apply(ts)) => ts,
+            // The missed head changes are recovered below, as the path is computed from the
+            // last head.
+            Err(RecvError::Lagged(_)) => {
+                head_changes = head_changes.resubscribe();
+                ctx.chain_store.heaviest_tipset()
+            }
+            Err(RecvError::Closed) => break,
+        };
+        let path = match impl_chain_get_path(&ctx.chain_store, last.key(), next.key()) {
+            Ok(path) => path,
+            Err(e) => {
+                // The error is reported to the client as the subscription is closed.
+                let _ = sender.send(Err(e.into())).await;
+                return;
+            }
+        };
+        last = next;
+        // The logs of the reverted tipsets are sent again, marked as removed.
+        for change in path {
+            let (ts, removed) = match change {
+                PathChange::Revert(ts) => (ts, true),
+                PathChange::Apply(ts) => (ts, false),
+            };
+            let logs = match applied_tipsets_logs(&ctx, &spec, vec![ts]).await {
+                Ok(logs) => logs,
+                Err(e) => {
+                    let _ = sender.send(Err(e.into())).await;
+                    return;
+                }
+            };
+            for log in logs {
+                let log = EthLog { removed, ..log };
+                if sender
+                    .send(Ok(EthSubscriptionResult::Log(log)))
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+async fn stream_pending_transactions<DB: Blockstore + Send + Sync + 'static>(
+    ctx: Ctx<DB>,
+    mut added: Subscriber<SignedMessage>,
+    sender: SubscriptionSender,
+) {
+    let chain_id = ctx.state_manager.chain_config().eth_chain_id;
+    loop {
+        let smsg = match added.recv().await {
+            Ok(smsg) => smsg,
+            // The skipped messages can't be recovered, the client has to subscribe again.
+            Err(RecvError::Lagged(n)) => {
+                let e = anyhow::anyhow!("subscription lagged behind, skipped {n} transactions");
+                let _ = sender.send(Err(e.into())).await;
+                return;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        let hash = match eth_tx_hash_from_signed_message(&smsg, chain_id) {
+            Ok(hash) => hash,
+            Err(e) => {
+                let _ = sender.send(Err(e.into())).await;
+                return;
+            }
+        };
+        if sender
+            .send(Ok(EthSubscriptionResult::TransactionHash(hash)))
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscription_kind_deserialize() {
+        for (kind, expected) in [
+            ("newHeads", EthSubscriptionKind::NewHeads),
+            ("logs", EthSubscriptionKind::Logs),
+            (
+                "newPendingTransactions",
+                EthSubscriptionKind::NewPendingTransactions,
+            ),
+        ] {
+            let actual: EthSubscriptionKind =
+                serde_json::from_value(serde_json::json!(kind)).unwrap();
+            assert_eq!(actual, expected);
+        }
+        serde_json::from_value::<EthSubscriptionKind>(serde_json::json!("syncing")).unwrap_err();
+    }
+}
@@ -0,0 +1,780 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Parity-style call traces of FEVM transactions, built from the FVM execution traces.
+//!
+//! The execution trace of a message is a tree of actor calls, in which the EVM implementation
+//! details (e.g., `DELEGATECALL` going through `GetBytecode` and `InvokeContractDelegate`, or
+//! contract creation going through the `EAM` and `Init` actors) show up as native calls. These
+//! are folded back into the `call`, `delegatecall` and `create` traces Ethereum tooling expects.
+
+use super::*;
+use crate::rpc::state::ActorTrace;
+use crate::shim::fvm_shared_latest::METHOD_CONSTRUCTOR;
+use serde::de::DeserializeOwned;
+
+/// Methods of the EVM actor that can only be called by the EVM itself are below this number.
+const EVM_PRIVATE_METHOD_LIMIT: MethodNum = 1024;
+
+/// The "impossible" bytecode reported for created contracts, see EIP-3541.
+const INVALID_BYTECODE: [u8; 1] = [0xFE];
+
+#[repr(u64)]
+enum InitMethod {
+    Exec = 2,
+    Exec4 = 3,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthCallTraceAction {
+    pub call_type: String,
+    pub from: EthAddress,
+    pub to: EthAddress,
+    pub gas: Uint64,
+    pub value: BigInt,
+    pub input: EthBytes,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthCreateTraceAction {
+    pub from: EthAddress,
+    pub gas: Uint64,
+    pub value: BigInt,
+    pub init: EthBytes,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum EthTraceAction {
+    Call(EthCallTraceAction),
+    Create(EthCreateTraceAction),
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthCallTraceResult {
+    pub gas_used: Uint64,
+    pub output: EthBytes,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthCreateTraceResult {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub address: Option<EthAddress>,
+    pub gas_used: Uint64,
+    pub code: EthBytes,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum EthTraceResult {
+    Call(EthCallTraceResult),
+    Create(EthCreateTraceResult),
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthTrace {
+    pub r#type: String,
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub error: String,
+    pub subtraces: i64,
+    pub trace_address: Vec<i64>,
+    pub action: EthTraceAction,
+    pub result: EthTraceResult,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthBlockTrace {
+    #[serde(flatten)]
+    pub trace: EthTrace,
+    pub block_hash: Hash,
+    pub block_number: i64,
+    pub transaction_hash: Hash,
+    pub transaction_position: i64,
+}
+lotus_json_with_self!(EthBlockTrace);
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthTraceReplayBlockTransaction {
+    pub output: EthBytes,
+    pub state_diff: Option<String>,
+    pub trace: Vec<EthTrace>,
+    pub transaction_hash: Hash,
+    pub vm_trace: Option<String>,
+}
+lotus_json_with_self!(EthTraceReplayBlockTransaction);
+
+/// A transaction of a tipset along with its call traces.
+pub(super) struct TransactionTraces {
+    pub hash: Hash,
+    /// 1-based position of the transaction within the tipset, system messages excluded, as
+    /// reported by Lotus.
+    pub position: i64,
+    pub traces: Vec<EthTrace>,
+}
+
+/// Executes the given tipset and returns the call traces of its transactions. Messages sent by
+/// the system actor, e.g., cron, are not transactions and are skipped.
+pub(super) async fn tipset_transaction_traces<DB: Blockstore + Send + Sync + 'static>(
+    ctx: &Ctx<DB>,
+    tipset: &Arc<Tipset>,
+) -> Result<Vec<TransactionTraces>> {
+    let (state_root, invoc_results) = ctx.state_manager.execution_trace(tipset).await?;
+    let state = StateTree::new_from_root(ctx.state_manager.blockstore_owned(), &state_root)?;
+    let chain_id = ctx.state_manager.chain_config().eth_chain_id;
+
+    let mut transactions = vec![];
+    for invoc_result in invoc_results {
+        if invoc_result.msg.from == FilecoinAddress::SYSTEM_ACTOR {
+            continue;
+        }
+        let hash =
+            filter::eth_tx_hash_from_message_cid(ctx.store(), &invoc_result.msg_cid, chain_id)
+                .with_context(|| {
+                    format!(
+                        "cannot find transaction hash for cid {}",
+                        invoc_result.msg_cid
+                    )
+                })?;
+        let mut env = Environment::new(&state, &invoc_result.msg.from)?;
+        if let Some(execution_trace) = &invoc_result.execution_trace {
+            build_traces(&mut env, &[], execution_trace)?;
+        }
+        transactions.push(TransactionTraces {
+            hash,
+            position: transactions.len() as i64 + 1,
+            traces: env.traces,
+        });
+    }
+    Ok(transactions)
+}
+
+/// The output of a transaction, i.e., the output of its top-level call or the code of the
+/// contract it created.
+pub(super) fn transaction_output(traces: &[EthTrace]) -> EthBytes {
+    match traces.first().map(|trace| &trace.result) {
+        Some(EthTraceResult::Call(result)) => result.output.clone(),
+        Some(EthTraceResult::Create(result)) => result.code.clone(),
+        None => EthBytes::default(),
+    }
+}
+
+#[derive(Default)]
+struct Environment {
+    caller: EthAddress,
+    is_evm: bool,
+    subtrace_count: i64,
+    traces: Vec<EthTrace>,
+    last_byte_code: Option<EthAddress>,
+}
+
+impl Environment {
+    /// The environment of the top-level call of a message sent by `from`.
+    fn new<DB: Blockstore>(state: &StateTree<DB>, from: &FilecoinAddress) -> Result<Self> {
+        let caller = lookup_eth_address(from, state)?
+            .with_context(|| format!("top-level message sender {from} could not be found"))?;
+        Ok(Self {
+            caller,
+            ..Default::default()
+        })
+    }
+}
+
+/// Walks the execution trace and appends the Ethereum traces it yields to the environment.
+/// `address` is the position of the trace in the call tree.
+fn build_traces(env: &mut Environment, address: &[i64], trace: &ExecutionTrace) -> Result<()> {
+    let (eth_trace, recurse_into) =
+        build_trace(env, address, trace).with_context(|| format!("at trace {address:?}"))?;
+    let trace_index = eth_trace.map(|eth_trace| {
+        env.traces.push(eth_trace);
+        env.subtrace_count += 1;
+        env.traces.len() - 1
+    });
+
+    let Some(recurse_into) = recurse_into else {
+        return Ok(());
+    };
+    let Some(invoked_actor) = &recurse_into.invoked_actor else {
+        return Ok(());
+    };
+    if recurse_into.subcalls.is_empty() {
+        return Ok(());
+    }
+
+    let mut sub_env = Environment {
+        caller: trace_to_address(invoked_actor),
+        is_evm: fil_actor_interface::is_evm_actor(&invoked_actor.state.code),
+        traces: std::mem::take(&mut env.traces),
+        ..Default::default()
+    };
+    for subcall in &recurse_into.subcalls {
+        let sub_address = [address, &[sub_env.subtrace_count]].concat();
+        build_traces(&mut sub_env, &sub_address, subcall)?;
+    }
+    env.traces = sub_env.traces;
+    if let Some(eth_trace) = trace_index.and_then(|index| env.traces.get_mut(index)) {
+        eth_trace.subtraces = sub_env.subtrace_count;
+    }
+    Ok(())
+}
+
+/// Converts a single node of the execution trace. Returns the Ethereum trace to record, if any,
+/// and the execution trace whose subcalls should be walked next, if any.
+///
+/// The call is first assumed to be a native call, then the special cases are handled:
+/// 1. Contract invocations, even from native actors, have their payloads decoded.
+/// 2. Calls to the `Init` actor and the `EAM` are treated as contract creations.
+/// 3. Calls from the EVM to its private methods are dropped, apart from the ones implementing
+///    `DELEGATECALL`.
+///
+/// If the special cases can't be decoded, the native call interpretation is kept.
+fn build_trace<'a>(
+    env: &mut Environment,
+    address: &[i64],
+    trace: &'a ExecutionTrace,
+) -> Result<(Option<EthTrace>, Option<&'a ExecutionTrace>)> {
+    // Ethereum records the traces after checking the funds of the caller and so on, so such
+    // failed subcalls are skipped entirely.
+    if !address.is_empty()
+        && [
+            ExitCode::SYS_INSUFFICIENT_FUNDS,
+            ExitCode::SYS_ASSERTION_FAILED,
+        ]
+        .contains(&trace.msg_rct.exit_code)
+    {
+        return Ok((None, None));
+    }
+
+    // When failing before invoking the actor, its address can't be reliably known, e.g., because
+    // of reverts. The call never really happened, so it is dropped.
+    let Some(invoked_actor) = &trace.invoked_actor else {
+        return Ok((None, None));
+    };
+
+    if trace.msg.method == EVMMethod::InvokeContract as MethodNum {
+        return Ok((
+            Some(trace_evm_call(env, address, invoked_actor, trace)),
+            Some(trace),
+        ));
+    }
+
+    // Only direct calls to the `Init` actor are caught here, the ones made by the `EAM` are
+    // skipped below as they are already part of the `EAM` create.
+    if trace.msg.to == FilecoinAddress::INIT_ACTOR
+        && [
+            InitMethod::Exec as MethodNum,
+            InitMethod::Exec4 as MethodNum,
+        ]
+        .contains(&trace.msg.method)
+    {
+        return trace_native_create(env, address, invoked_actor, trace);
+    }
+    if trace.msg.to == FilecoinAddress::ETHEREUM_ACCOUNT_MANAGER_ACTOR
+        && [
+            EAMMethod::Create as MethodNum,
+            EAMMethod::Create2 as MethodNum,
+            EAMMethod::CreateExternal as MethodNum,
+        ]
+        .contains(&trace.msg.method)
+    {
+        return trace_eth_create(env, address, trace);
+    }
+
+    // Contracts can't call the methods below 1024, only the EVM itself does.
+    if env.is_evm && trace.msg.method > 0 && trace.msg.method < EVM_PRIVATE_METHOD_LIMIT {
+        return trace_evm_private(env, address, invoked_actor, trace);
+    }
+
+    Ok((
+        Some(trace_native_call(env, address, invoked_actor, trace)),
+        Some(trace),
+    ))
+}
+
+fn trace_call(
+    env: &Environment,
+    address: &[i64],
+    invoked_actor: &ActorTrace,
+    trace: &ExecutionTrace,
+    input: EthBytes,
+    output: EthBytes,
+) -> EthTrace {
+    EthTrace {
+        r#type: "call".into(),
+        error: trace_error(trace),
+        subtraces: 0,
+        trace_address: address.to_vec(),
+        action: EthTraceAction::Call(EthCallTraceAction {
+            call_type: "call".into(),
+            from: env.caller.clone(),
+            to: trace_to_address(invoked_actor),
+            gas: Uint64(trace.msg.gas_limit.unwrap_or_default()),
+            value: trace.msg.value.clone().into(),
+            input,
+        }),
+        result: EthTraceResult::Call(EthCallTraceResult {
+            gas_used: Uint64(gas_used(trace)),
+            output,
+        }),
+    }
+}
+
+/// Traces a call, encoding its parameters and return value as the ABI of a native call.
+fn trace_native_call(
+    env: &Environment,
+    address: &[i64],
+    invoked_actor: &ActorTrace,
+    trace: &ExecutionTrace,
+) -> EthTrace {
+    let input =
+        encode_filecoin_params_as_abi(trace.msg.method, trace.msg.params_codec, &trace.msg.params)
+            .unwrap_or_default();
+    let output = EthBytes(encode_filecoin_returns_as_abi(
+        trace.msg_rct.exit_code.value().into(),
+        trace.msg_rct.return_codec,
+        &trace.msg_rct.r#return,
+    ));
+    trace_call(env, address, invoked_actor, trace, input, output)
+}
+
+/// Traces a contract invocation, falling back on a native call if its payloads can't be decoded.
+fn trace_evm_call(
+    env: &Environment,
+    address: &[i64],
+    invoked_actor: &ActorTrace,
+    trace: &ExecutionTrace,
+) -> EthTrace {
+    let payloads = decode_payload(&trace.msg.params, trace.msg.params_codec).and_then(|input| {
+        let output = decode_payload(&trace.msg_rct.r#return, trace.msg_rct.return_codec)?;
+        Ok((input, output))
+    });
+    match payloads {
+        Ok((input, output)) => trace_call(env, address, invoked_actor, trace, input, output),
+        Err(e) => {
+            tracing::debug!("failed to decode contract invocation payloads: {e}");
+            trace_native_call(env, address, invoked_actor, trace)
+        }
+    }
+}
+
+/// Traces a direct `Exec` or `Exec4` call to the `Init` actor, i.e., the creation of a native
+/// actor.
+fn trace_native_create<'a>(
+    env: &Environment,
+    address: &[i64],
+    invoked_actor: &ActorTrace,
+    trace: &'a ExecutionTrace,
+) -> Result<(Option<EthTrace>, Option<&'a ExecutionTrace>)> {
+    if !trace.msg_rct.exit_code.is_success() {
+        // If the init actor failed, there is nothing else to report.
+        return Ok((None, None));
+    }
+
+    let Some(constructor) = trace
+        .subcalls
+        .iter()
+        .find(|subcall| subcall.msg.method == METHOD_CONSTRUCTOR)
+    else {
+        // The creation may fail early, e.g., because of bad parameters or a lack of gas. This is
+        // not a create, but still a call to the init actor.
+        return Ok((
+            Some(trace_native_call(env, address, invoked_actor, trace)),
+            Some(trace),
+        ));
+    };
+    // Native actors can call `Exec4`, but such calls must fail before constructing anything.
+    if trace.msg.method == InitMethod::Exec4 as MethodNum {
+        bail!("direct call to Exec4 successfully called a constructor");
+    }
+
+    let exec_return: fil_actor_init_state::v13::ExecReturn =
+        decode_cbor(&trace.msg_rct.r#return, trace.msg_rct.return_codec)
+            .context("failed to decode the return value of a successful Init.Exec call")?;
+    let actor_id = exec_return
+        .id_address
+        .id()
+        .context("failed to extract created actor ID from address")?;
+
+    Ok((
+        Some(EthTrace {
+            r#type: "create".into(),
+            error: trace_error(trace),
+            subtraces: 0,
+            trace_address: address.to_vec(),
+            action: EthTraceAction::Create(EthCreateTraceAction {
+                from: env.caller.clone(),
+                gas: Uint64(trace.msg.gas_limit.unwrap_or_default()),
+                value: trace.msg.value.clone().into(),
+                // Only the EAM creates EVM contracts, so there is no real initcode here.
+                init: EthBytes(INVALID_BYTECODE.to_vec()),
+            }),
+            result: EthTraceResult::Create(EthCreateTraceResult {
+                address: Some(EthAddress::from_actor_id(actor_id)),
+                gas_used: Uint64(gas_used(trace)),
+                // This is the answer `EXTCODECOPY` gives for native actors.
+                code: EthBytes(INVALID_BYTECODE.to_vec()),
+            }),
+        }),
+        Some(constructor),
+    ))
+}
+
+/// Traces a `Create`, `Create2` or `CreateExternal` call to the `EAM`, i.e., the creation of a
+/// contract.
+fn trace_eth_create<'a>(
+    env: &Environment,
+    address: &[i64],
+    trace: &'a ExecutionTrace,
+) -> Result<(Option<EthTrace>, Option<&'a ExecutionTrace>)> {
+    // Look for the call to the constructor, made through the `Init` actor, or to the resurrect
+    // method of the EVM.
+    let constructor = trace.subcalls.iter().find_map(|subcall| {
+        if subcall.msg.to == FilecoinAddress::INIT_ACTOR {
+            subcall
+                .subcalls
+                .iter()
+                .find(|subcall| subcall.msg.method == METHOD_CONSTRUCTOR)
+        } else if subcall.msg.method == EVMMethod::Resurrect as MethodNum {
+            Some(subcall)
+        } else {
+            None
+        }
+    });
+    let Some(constructor) = constructor else {
+        if trace.msg_rct.exit_code.is_success() {
+            bail!("successful Create/Create2 call failed to call a constructor");
+        }
+        return Ok((None, None));
+    };
+
+    let (initcode, created_address) = decode_create_via_eam(trace).context(
+        "EAM called with invalid params or returned an invalid result, but it still tried to construct the contract",
+    )?;
+    let code = match trace.msg_rct.exit_code.value() {
+        // The deployed bytecode can't be reliably known, e.g., if part of the trace reverts.
+        0 => EthBytes(INVALID_BYTECODE.to_vec()),
+        // Report the revert message of the constructor, if any.
+        EVM_CONTRACT_REVERTED => decode_payload(
+            &constructor.msg_rct.r#return,
+            constructor.msg_rct.return_codec,
+        )
+        .unwrap_or_default(),
+        _ => EthBytes::default(),
+    };
+
+    Ok((
+        Some(EthTrace {
+            r#type: "create".into(),
+            error: trace_error(trace),
+            subtraces: 0,
+            trace_address: address.to_vec(),
+            action: EthTraceAction::Create(EthCreateTraceAction {
+                from: env.caller.clone(),
+                gas: Uint64(trace.msg.gas_limit.unwrap_or_default()),
+                value: trace.msg.value.clone().into(),
+                init: EthBytes(initcode),
+            }),
+            result: EthTraceResult::Create(EthCreateTraceResult {
+                address: created_address,
+                gas_used: Uint64(gas_used(trace)),
+                code,
+            }),
+        }),
+        Some(constructor),
+    ))
+}
+
+/// Decodes the initcode of a contract creation through the `EAM`, and the address of the created
+/// contract if it succeeded.
+fn decode_create_via_eam(trace: &ExecutionTrace) -> Result<(Vec<u8>, Option<EthAddress>)> {
+    let initcode = match trace.msg.method {
+        m if m == EAMMethod::Create as MethodNum => {
+            decode_cbor::<CreateParams>(&trace.msg.params, trace.msg.params_codec)?.initcode
+        }
+        m if m == EAMMethod::Create2 as MethodNum => {
+            decode_cbor::<Create2Params>(&trace.msg.params, trace.msg.params_codec)?.initcode
+        }
+        m if m == EAMMethod::CreateExternal as MethodNum => {
+            decode_payload(&trace.msg.params, trace.msg.params_codec)?.0
+        }
+        m => bail!("unexpected CREATE method {m}"),
+    };
+    let created_address =
+        decode_cbor::<CreateExternalReturn>(&trace.msg_rct.r#return, trace.msg_rct.return_codec)
+            .ok()
+            .and_then(|ret| EthAddress::try_from(ret.eth_address.as_slice()).ok());
+    Ok((initcode, created_address))
+}
+
+/// Handles the calls the EVM makes to its private methods. `DELEGATECALL` is implemented by
+/// getting the bytecode of the callee with `GetBytecode`, then by invoking the current contract on
+/// `InvokeContractDelegate`. It is reconstructed by remembering the last contract whose bytecode
+/// was fetched. Other private calls are implementation details and are dropped.
+fn trace_evm_private<'a>(
+    env: &mut Environment,
+    address: &[i64],
+    invoked_actor: &ActorTrace,
+    trace: &'a ExecutionTrace,
+) -> Result<(Option<EthTrace>, Option<&'a ExecutionTrace>)> {
+    match trace.msg.method {
+        METHOD_GET_BYTE_CODE => {
+            // `GetBytecode` is also called by, e.g., `EXTCODECOPY`, so it may be called several
+            // times before the delegate call.
+            env.last_byte_code = trace
+                .msg_rct
+                .exit_code
+                .is_success()
+                .then(|| trace_to_address(invoked_actor));
+            Ok((None, None))
+        }
+        m if m == EVMMethod::InvokeContractDelegate as MethodNum => {
+            // The caller is an EVM actor, so any inconsistency here is a bug in the EVM.
+            let to = env
+                .last_byte_code
+                .clone()
+                .context("unknown bytecode for delegate call")?;
+            let invoked = trace_to_address(invoked_actor);
+            if env.caller != invoked {
+                bail!(
+                    "delegate-call not from & to self: {:?} != {:?}",
+                    env.caller,
+                    invoked
+                );
+            }
+            let params: DelegateCallParams = decode_cbor(&trace.msg.params, trace.msg.params_codec)
+                .context("failed to decode delegate-call params")?;
+            let output = decode_payload(&trace.msg_rct.r#return, trace.msg_rct.return_codec)
+                .context("failed to decode delegate-call return")?;
+            Ok((
+                Some(EthTrace {
+                    r#type: "call".into(),
+                    error: trace_error(trace),
+                    subtraces: 0,
+                    trace_address: address.to_vec(),
+                    action: EthTraceAction::Call(EthCallTraceAction {
+                        call_type: "delegatecall".into(),
+                        from: env.caller.clone(),
+                        to,
+                        gas: Uint64(trace.msg.gas_limit.unwrap_or_default()),
+                        value: trace.msg.value.clone().into(),
+                        input: EthBytes(params.input),
+                    }),
+                    result: EthTraceResult::Call(EthCallTraceResult {
+                        gas_used: Uint64(gas_used(trace)),
+                        output,
+                    }),
+                }),
+                Some(trace),
+            ))
+        }
+        _ => Ok((None, None)),
+    }
+}
+
+fn decode_cbor<T: DeserializeOwned>(data: &RawBytes, codec: u64) -> Result<T> {
+    match codec {
+        DAG_CBOR | CBOR => Ok(fvm_ipld_encoding::from_slice(data)?),
+        _ => bail!("unexpected codec {codec}"),
+    }
+}
+
+fn trace_to_address(actor: &ActorTrace) -> EthAddress {
+    actor
+        .state
+        .delegated_address
+        .and_then(|addr| EthAddress::from_filecoin_address(&addr.into()).ok())
+        .unwrap_or_else(|| EthAddress::from_actor_id(actor.id))
+}
+
+fn gas_used(trace: &ExecutionTrace) -> u64 {
+    trace
+        .gas_charges
+        .iter()
+        .map(|charge| charge.total_gas)
+        .sum()
+}
+
+// Exit codes of the EVM actor, see
+// <https://github.com/filecoin-project/builtin-actors/blob/master/actors/evm/shared/src/lib.rs>.
+const EVM_CONTRACT_REVERTED: u32 = 33;
+const EVM_CONTRACT_INVALID_INSTRUCTION: u32 = 34;
+const EVM_CONTRACT_UNDEFINED_INSTRUCTION: u32 = 35;
+const EVM_CONTRACT_STACK_UNDERFLOW: u32 = 36;
+const EVM_CONTRACT_STACK_OVERFLOW: u32 = 37;
+const EVM_CONTRACT_ILLEGAL_MEMORY_ACCESS: u32 = 38;
+const EVM_CONTRACT_BAD_JUMPDEST: u32 = 39;
+const EVM_CONTRACT_SELFDESTRUCT_FAILED: u32 = 40;
+
+/// The error message of a failed call, in the format of Ethereum tooling.
+fn trace_error(trace: &ExecutionTrace) -> String {
+    let exit_code = trace.msg_rct.exit_code;
+    if exit_code.is_success() {
+        return String::new();
+    }
+    if exit_code == ExitCode::SYS_OUT_OF_GAS {
+        return "out of gas".into();
+    }
+    if exit_code.value() < ExitCode::FIRST_USER_EXIT_CODE {
+        return format!("vm error: {}", exit_code.value());
+    }
+    let is_evm_or_eam = trace.invoked_actor.as_ref().is_some_and(|actor| {
+        fil_actor_interface::is_evm_actor(&actor.state.code)
+            || FilecoinAddress::new_id(actor.id) == FilecoinAddress::ETHEREUM_ACCOUNT_MANAGER_ACTOR
+    });
+    if is_evm_or_eam {
+        let message = match exit_code.value() {
+            // Capitalized for compatibility.
+            EVM_CONTRACT_REVERTED => Some("Reverted"),
+            EVM_CONTRACT_INVALID_INSTRUCTION => Some("invalid instruction"),
+            EVM_CONTRACT_UNDEFINED_INSTRUCTION => Some("undefined instruction"),
+            EVM_CONTRACT_STACK_UNDERFLOW => Some("stack underflow"),
+            EVM_CONTRACT_STACK_OVERFLOW => Some("stack overflow"),
+            EVM_CONTRACT_ILLEGAL_MEMORY_ACCESS => Some("illegal memory access"),
+            EVM_CONTRACT_BAD_JUMPDEST => Some("invalid jump destination"),
+            EVM_CONTRACT_SELFDESTRUCT_FAILED => Some("self destruct failed"),
+            _ => None,
+        };
+        if let Some(message) = message {
+            return message.into();
+        }
+    }
+    format!("actor error: {}", exit_code.value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::state::{MessageTrace, ReturnTrace};
+    use crate::shim::state_tree::{ActorID, ActorState};
+
+    fn execution_trace(
+        from: ActorID,
+        to: ActorID,
+        method: MethodNum,
+        exit_code: u32,
+        subcalls: Vec<ExecutionTrace>,
+    ) -> ExecutionTrace {
+        ExecutionTrace {
+            msg: MessageTrace {
+                from: FilecoinAddress::new_id(from),
+                to: FilecoinAddress::new_id(to),
+                value: TokenAmount::default(),
+                method,
+                params: RawBytes::default(),
+                params_codec: IDENTITY,
+                gas_limit: Some(1000),
+                read_only: None,
+            },
+            msg_rct: ReturnTrace {
+                exit_code: exit_code.into(),
+                r#return: RawBytes::default(),
+                return_codec: IDENTITY,
+            },
+            invoked_actor: Some(ActorTrace {
+                id: to,
+                state: ActorState::new_empty(Cid::default(), None),
+            }),
+            gas_charges: vec![],
+            subcalls,
+        }
+    }
+
+    #[test]
+    fn build_traces_call_tree() {
+        // 100 -> 101 -> (102, 103 -> 104), where the call to 103 runs out of gas.
+        let trace = execution_trace(
+            100,
+            101,
+            2,
+            0,
+            vec![
+                execution_trace(101, 102, 2, 0, vec![]),
+                execution_trace(
+                    101,
+                    103,
+                    2,
+                    ExitCode::SYS_OUT_OF_GAS.value(),
+                    vec![execution_trace(103, 104, 2, 0, vec![])],
+                ),
+            ],
+        );
+        let mut env = Environment::default();
+        build_traces(&mut env, &[], &trace).unwrap();
+
+        let summary = env
+            .traces
+            .iter()
+            .map(|trace| {
+                let EthTraceAction::Call(action) = &trace.action else {
+                    panic!("expected a call");
+                };
+                (
+                    action.to.clone(),
+                    trace.trace_address.clone(),
+                    trace.subtraces,
+                    trace.error.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (EthAddress::from_actor_id(101), vec![], 2, ""),
+                (EthAddress::from_actor_id(102), vec![0], 0, ""),
+                (EthAddress::from_actor_id(103), vec![1], 1, "out of gas"),
+                (EthAddress::from_actor_id(104), vec![1, 0], 0, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_traces_skips_failed_subcalls() {
+        let trace = execution_trace(
+            100,
+            101,
+            2,
+            0,
+            vec![execution_trace(
+                101,
+                102,
+                2,
+                ExitCode::SYS_INSUFFICIENT_FUNDS.value(),
+                vec![],
+            )],
+        );
+        let mut env = Environment::default();
+        build_traces(&mut env, &[], &trace).unwrap();
+        assert_eq!(env.traces.len(), 1);
+        assert_eq!(env.traces.first().unwrap().subtraces, 0);
+    }
+
+    #[test]
+    fn eth_trace_serde_roundtrip() {
+        let trace = EthTrace {
+            r#type: "create".into(),
+            error: String::new(),
+            subtraces: 0,
+            trace_address: vec![],
+            action: EthTraceAction::Create(EthCreateTraceAction {
+                from: EthAddress::from_actor_id(100),
+                gas: Uint64(1000),
+                value: BigInt::default(),
+                init: EthBytes(INVALID_BYTECODE.to_vec()),
+            }),
+            result: EthTraceResult::Create(EthCreateTraceResult {
+                address: Some(EthAddress::from_actor_id(101)),
+                gas_used: Uint64(10),
+                code: EthBytes(INVALID_BYTECODE.to_vec()),
+            }),
+        };
+        let json = serde_json::to_value(&trace).unwrap();
+        assert!(json.get("error").is_none());
+        assert_eq!(serde_json::from_value::<EthTrace>(json).unwrap(), trace);
+    }
+}
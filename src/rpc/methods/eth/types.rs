@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use super::*;
+use serde_tuple::{self, Deserialize_tuple, Serialize_tuple};
 
 pub const METHOD_GET_BYTE_CODE: u64 = 3;
 pub const METHOD_GET_STORAGE_AT: u64 = 5;
@@ -17,6 +18,43 @@ lotus_json_with_self!(EthBytes);
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GetBytecodeReturn(pub Option<Cid>);
 
+/// Return value of the `EAM` actor `Create`, `Create2` and `CreateExternal` methods.
+#[derive(Debug, Deserialize_tuple, Serialize_tuple)]
+pub struct CreateExternalReturn {
+    pub actor_id: u64,
+    pub robust_address: Option<FilecoinAddress>,
+    #[serde(with = "fvm_ipld_encoding::strict_bytes")]
+    pub eth_address: Vec<u8>,
+}
+
+/// Parameters of the `EAM` actor `Create` method.
+#[derive(Debug, Deserialize_tuple, Serialize_tuple)]
+pub struct CreateParams {
+    #[serde(with = "fvm_ipld_encoding::strict_bytes")]
+    pub initcode: Vec<u8>,
+    pub nonce: u64,
+}
+
+/// Parameters of the `EAM` actor `Create2` method.
+#[derive(Debug, Deserialize_tuple, Serialize_tuple)]
+pub struct Create2Params {
+    #[serde(with = "fvm_ipld_encoding::strict_bytes")]
+    pub initcode: Vec<u8>,
+    #[serde(with = "fvm_ipld_encoding::strict_bytes")]
+    pub salt: [u8; EVM_WORD_LENGTH],
+}
+
+/// Parameters of the `EVM` actor `InvokeContractDelegate` method.
+#[derive(Debug, Deserialize_tuple, Serialize_tuple)]
+pub struct DelegateCallParams {
+    pub code: Cid,
+    #[serde(with = "fvm_ipld_encoding::strict_bytes")]
+    pub input: Vec<u8>,
+    #[serde(with = "fvm_ipld_encoding::strict_bytes")]
+    pub caller: [u8; ADDRESS_LENGTH],
+    pub value: TokenAmount,
+}
+
 const GET_STORAGE_AT_PARAMS_ARRAY_LENGTH: usize = 32;
 
 #[derive(Debug, Clone)]
@@ -171,6 +209,91 @@ impl TryFrom<EthAddress> for FilecoinAddress {
     }
 }
 
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthCallMessage {
+    #[schemars(with = "Option<EthAddress>")]
+    #[serde(
+        with = "crate::lotus_json",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub from: Option<EthAddress>,
+    #[schemars(with = "Option<EthAddress>")]
+    #[serde(
+        with = "crate::lotus_json",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub to: Option<EthAddress>,
+    #[serde(default)]
+    pub gas: Uint64,
+    #[serde(default)]
+    pub gas_price: BigInt,
+    #[serde(default)]
+    pub value: BigInt,
+    #[serde(default)]
+    pub data: EthBytes,
+}
+lotus_json_with_self!(EthCallMessage);
+
+impl EthCallMessage {
+    /// Wraps the call data in a CBOR byte string, as expected by the `EVM` and `EAM` actors.
+    pub fn convert_data_to_message_params(data: EthBytes) -> anyhow::Result<RawBytes> {
+        if data.0.is_empty() {
+            Ok(RawBytes::default())
+        } else {
+            Ok(RawBytes::new(fvm_ipld_encoding::to_vec(&RawBytes::new(
+                data.0,
+            ))?))
+        }
+    }
+}
+
+// See `ethCallToFilecoinMessage` in https://github.com/filecoin-project/lotus/blob/v1.26.2/node/impl/full/eth.go for reference implementation
+impl TryFrom<EthCallMessage> for Message {
+    type Error = anyhow::Error;
+
+    fn try_from(tx: EthCallMessage) -> Result<Self, Self::Error> {
+        let from = match &tx.from {
+            Some(addr) if addr != &EthAddress::default() => {
+                // The from address must be translatable to an f4 address.
+                let from = addr.to_filecoin_address()?;
+                if from.protocol() != Protocol::Delegated {
+                    bail!("expected a class 4 address, got: {}", from.protocol());
+                }
+                from
+            }
+            _ => {
+                // Send from the filecoin "system" address.
+                EthAddress::default().to_filecoin_address()?
+            }
+        };
+        let params = EthCallMessage::convert_data_to_message_params(tx.data)?;
+        let (to, method_num) = if let Some(to) = tx.to {
+            (
+                to.to_filecoin_address()?,
+                EVMMethod::InvokeContract as MethodNum,
+            )
+        } else {
+            // This is a contract creation.
+            (
+                FilecoinAddress::ETHEREUM_ACCOUNT_MANAGER_ACTOR,
+                EAMMethod::CreateExternal as MethodNum,
+            )
+        };
+        Ok(Message {
+            from,
+            to,
+            value: TokenAmount::from_atto(tx.value.0),
+            method_num,
+            params,
+            gas_limit: BLOCK_GAS_LIMIT,
+            ..Default::default()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,4 +318,29 @@ mod tests {
             "815820000000000000000000000000000000000000000000000000000000000000000a"
         );
     }
+
+    #[test]
+    fn eth_call_message_to_contract_creation() {
+        let msg = Message::try_from(EthCallMessage {
+            data: EthBytes(vec![0x60, 0x80]),
+            ..EthCallMessage::default()
+        })
+        .unwrap();
+        assert_eq!(msg.to, FilecoinAddress::ETHEREUM_ACCOUNT_MANAGER_ACTOR);
+        assert_eq!(msg.method_num, EAMMethod::CreateExternal as MethodNum);
+        assert_eq!(msg.params.bytes(), &[0x42, 0x60, 0x80]);
+        assert_eq!(
+            msg.from,
+            EthAddress::default().to_filecoin_address().unwrap()
+        );
+    }
+
+    #[test]
+    fn eth_call_message_rejects_id_sender() {
+        let tx = EthCallMessage {
+            from: Some(EthAddress::from_actor_id(1234)),
+            ..EthCallMessage::default()
+        };
+        assert!(Message::try_from(tx).is_err());
+    }
 }
@@ -1,8 +1,11 @@
 // Copyright 2019-2024 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use crate::blocks::Tipset;
 use crate::chain::{BASE_FEE_MAX_CHANGE_DENOM, BLOCK_GAS_TARGET};
+use crate::interpreter::VMTrace;
 use crate::message::{ChainMessage, Message as MessageTrait, SignedMessage};
+use crate::rpc::state::InvocResult;
 use crate::rpc::{error::ServerError, types::*, ApiVersion, Ctx, Permission, RpcMethod};
 use crate::shim::{
     address::{Address, Protocol},
@@ -15,8 +18,9 @@ use fvm_ipld_blockstore::Blockstore;
 use num::BigInt;
 use num_traits::{FromPrimitive, Zero};
 use rand_distr::{Distribution, Normal};
+use std::sync::Arc;
 
-const MIN_GAS_PREMIUM: f64 = 100000.0;
+pub(crate) const MIN_GAS_PREMIUM: f64 = 100000.0;
 
 /// Estimate the fee cap
 pub enum GasEstimateFeeCap {}
@@ -200,7 +204,7 @@ impl RpcMethod<3> for GasEstimateMessageGas {
 async fn estimate_gas_limit<DB>(
     data: &Ctx<DB>,
     msg: Message,
-    ApiTipsetKey(tsk): ApiTipsetKey,
+    tsk: ApiTipsetKey,
 ) -> Result<i64, ServerError>
 where
     DB: Blockstore + Send + Sync + 'static,
@@ -210,10 +214,35 @@ where
     msg.set_gas_fee_cap(TokenAmount::from_atto(0));
     msg.set_gas_premium(TokenAmount::from_atto(0));
 
+    let (res, _, _) = estimate_call_with_gas(data, msg, &tsk, VMTrace::NotTraced).await?;
+    match res.msg_rct {
+        Some(rct) => {
+            if rct.exit_code().value() != 0 {
+                return Ok(-1);
+            }
+            Ok(rct.gas_used() as i64)
+        }
+        None => Ok(-1),
+    }
+}
+
+/// Runs the message on top of the pending messages of its sender, as it would be
+/// executed in the next tipset. Returns the invocation result along with the prior
+/// messages and the tipset used for the execution, so that callers can re-run the
+/// message with different gas parameters.
+pub async fn estimate_call_with_gas<DB>(
+    data: &Ctx<DB>,
+    msg: Message,
+    ApiTipsetKey(tsk): &ApiTipsetKey,
+    trace_config: VMTrace,
+) -> Result<(InvocResult, Vec<ChainMessage>, Arc<Tipset>), ServerError>
+where
+    DB: Blockstore + Send + Sync + 'static,
+{
     let curr_ts = data
         .state_manager
         .chain_store()
-        .load_required_tipset_or_heaviest(&tsk)?;
+        .load_required_tipset_or_heaviest(tsk)?;
     let from_a = data
         .state_manager
         .resolve_to_key_addr(&msg.from, &curr_ts)
@@ -225,10 +254,24 @@ where
         .unwrap_or_default();
 
     let ts = data.mpool.cur_tipset.lock().clone();
-    // Pretend that the message is signed. This has an influence on the gas
-    // cost. We obviously can't generate a valid signature. Instead, we just
-    // fill the signature with zeros. The validity is not checked.
-    let mut chain_msg = match from_a.protocol() {
+    let mut chain_msg = pretend_signed(msg, from_a.protocol());
+    let res = data
+        .state_manager
+        .call_with_gas(
+            &mut chain_msg,
+            &prior_messages,
+            Some(ts.clone()),
+            trace_config,
+        )
+        .await?;
+    Ok((res, prior_messages, ts))
+}
+
+/// Pretend that the message is signed. This has an influence on the gas
+/// cost. We obviously can't generate a valid signature. Instead, we just
+/// fill the signature with zeros. The validity is not checked.
+pub fn pretend_signed(msg: Message, sender_protocol: Protocol) -> ChainMessage {
+    match sender_protocol {
         Protocol::Secp256k1 => ChainMessage::Signed(SignedMessage::new_unchecked(
             msg,
             Signature::new_secp256k1(vec![0; SECP_SIG_LEN]),
@@ -240,20 +283,6 @@ where
             Signature::new(SignatureType::Delegated, vec![0; SECP_SIG_LEN]),
         )),
         _ => ChainMessage::Unsigned(msg),
-    };
-
-    let res = data
-        .state_manager
-        .call_with_gas(&mut chain_msg, &prior_messages, Some(ts))
-        .await?;
-    match res.msg_rct {
-        Some(rct) => {
-            if rct.exit_code().value() != 0 {
-                return Ok(-1);
-            }
-            Ok(rct.gas_used() as i64)
-        }
-        None => Ok(-1),
     }
 }
 
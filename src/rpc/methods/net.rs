@@ -76,6 +76,7 @@ impl RpcMethod<0> for NetPeers {
 pub enum NetListening {}
 impl RpcMethod<0> for NetListening {
     const NAME: &'static str = "Filecoin.NetListening";
+    const NAME_ALIAS: Option<&'static str> = Some("net_listening");
     const PARAM_NAMES: [&'static str; 0] = [];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
@@ -224,6 +225,7 @@ impl RpcMethod<0> for NetAutoNatStatus {
 pub enum NetVersion {}
 impl RpcMethod<0> for NetVersion {
     const NAME: &'static str = "Filecoin.NetVersion";
+    const NAME_ALIAS: Option<&'static str> = Some("net_version");
     const PARAM_NAMES: [&'static str; 0] = [];
     const API_VERSION: ApiVersion = ApiVersion::V1;
     const PERMISSION: Permission = Permission::Read;
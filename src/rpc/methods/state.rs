@@ -1,6 +1,7 @@
 // Copyright 2019-2024 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+pub(crate) mod params;
 mod types;
 use fil_actor_interface::init;
 use fil_actors_shared::fvm_ipld_amt::Amt;
@@ -9,27 +10,30 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 pub use types::*;
 
-use crate::blocks::Tipset;
+use crate::blocks::{Tipset, TipsetKey};
 use crate::cid_collections::CidHashSet;
 use crate::libp2p::NetworkMessage;
-use crate::lotus_json::lotus_json_with_self;
-use crate::networks::{ChainConfig, NetworkChain};
+use crate::lotus_json::{lotus_json_with_self, LotusJson};
+use crate::networks::{ActorBundleMetadata, ChainConfig, NetworkChain};
+use crate::shim::actors::init::InitStateExt as _;
+use crate::shim::actors::market::DealProposalsExt as _;
 use crate::shim::actors::verifreg::VerifiedRegistryStateExt as _;
 use crate::shim::actors::{
     market::BalanceTableExt as _,
     miner::{MinerStateExt as _, PartitionExt as _},
 };
-use crate::shim::message::Message;
+use crate::shim::message::{Message, MethodNum};
 use crate::shim::piece::PaddedPieceSize;
 use crate::shim::sector::SectorNumber;
-use crate::shim::state_tree::StateTree;
+use crate::shim::state_tree::{for_each_actor_ranged, StateTree};
 use crate::shim::{
     address::Address, clock::ChainEpoch, deal::DealID, econ::TokenAmount, executor::Receipt,
     state_tree::ActorState, version::NetworkVersion,
 };
 use crate::state_manager::chain_rand::ChainRand;
 use crate::state_manager::circulating_supply::GenesisInfo;
-use crate::state_manager::MarketBalance;
+use crate::state_manager::{MarketBalance, StateManager};
+use crate::statediff::ActorChange;
 use crate::utils::db::{
     car_stream::{CarBlock, CarWriter},
     BlockstoreExt as _,
@@ -56,7 +60,7 @@ use futures::StreamExt;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::{CborStore, DAG_CBOR};
 pub use fvm_shared3::sector::StoragePower;
-use jsonrpsee::types::error::ErrorObject;
+use jsonrpsee::types::{error::ErrorObject, Params};
 use libipld_core::ipld::Ipld;
 use num_bigint::BigInt;
 use num_traits::Euclid;
@@ -65,6 +69,7 @@ use parking_lot::Mutex;
 use std::ops::Mul;
 use std::path::PathBuf;
 use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc;
 use tokio::task::JoinSet;
 
 const INITIAL_PLEDGE_NUM: u64 = 110;
@@ -120,6 +125,35 @@ impl RpcMethod<2> for StateReplay {
     }
 }
 
+pub enum StateCompute {}
+impl RpcMethod<3> for StateCompute {
+    const NAME: &'static str = "Filecoin.StateCompute";
+    const PARAM_NAMES: [&'static str; 3] = ["height", "messages", "tsk"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (ChainEpoch, Vec<Message>, ApiTipsetKey);
+    type Ok = ComputeStateOutput;
+
+    /// applies the given messages on top of the state resulting from the execution of the
+    /// indicated tipset, and returns the state root at the given height along with the
+    /// invocation results of every applied message.
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (height, messages, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let tipset = ctx
+            .state_manager
+            .chain_store()
+            .load_required_tipset_or_heaviest(&tsk)?;
+        let (root, trace) = ctx
+            .state_manager
+            .compute_state(height, &messages, &tipset)
+            .await?;
+        Ok(ComputeStateOutput { root, trace })
+    }
+}
+
 pub enum StateNetworkName {}
 impl RpcMethod<0> for StateNetworkName {
     const NAME: &'static str = "Filecoin.StateNetworkName";
@@ -157,6 +191,65 @@ impl RpcMethod<1> for StateNetworkVersion {
     }
 }
 
+/// Returns the code CIDs of the builtin actors at the given network version, keyed by actor name.
+/// See <https://github.com/filecoin-project/lotus/blob/master/documentation/en/api-v1-unstable-methods.md#StateActorCodeCIDs>
+pub enum StateActorCodeCIDs {}
+impl RpcMethod<1> for StateActorCodeCIDs {
+    const NAME: &'static str = "Filecoin.StateActorCodeCIDs";
+    const PARAM_NAMES: [&'static str; 1] = ["network_version"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (NetworkVersion,);
+    type Ok = HashMap<String, Cid>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (network_version,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let metadata = actor_bundle_metadata(ctx.state_manager.chain_config(), network_version)?;
+        Ok(metadata
+            .manifest
+            .builtin_actors()
+            .map(|(actor, cid)| (actor.name().to_string(), cid))
+            .collect())
+    }
+}
+
+/// Returns the CID of the builtin actors manifest at the given network version.
+/// See <https://github.com/filecoin-project/lotus/blob/master/documentation/en/api-v1-unstable-methods.md#StateActorManifestCID>
+pub enum StateActorManifestCID {}
+impl RpcMethod<1> for StateActorManifestCID {
+    const NAME: &'static str = "Filecoin.StateActorManifestCID";
+    const PARAM_NAMES: [&'static str; 1] = ["network_version"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (NetworkVersion,);
+    type Ok = Cid;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (network_version,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(actor_bundle_metadata(ctx.state_manager.chain_config(), network_version)?.bundle_cid)
+    }
+}
+
+fn actor_bundle_metadata(
+    chain_config: &ChainConfig,
+    network_version: NetworkVersion,
+) -> anyhow::Result<&'static ActorBundleMetadata> {
+    chain_config
+        .actor_bundle_metadata(network_version)
+        .with_context(|| {
+            format!(
+                "no actor bundle is available for network version {}",
+                *network_version
+            )
+        })
+}
+
 /// gets the public key address of the given ID address
 /// See <https://github.com/filecoin-project/lotus/blob/master/documentation/en/api-v0-methods.md#StateAccountKey>
 pub enum StateAccountKey {}
@@ -206,6 +299,30 @@ impl RpcMethod<2> for StateLookupID {
     }
 }
 
+/// retrieves the robust address of the given ID address from the init actor's address map
+/// See <https://github.com/filecoin-project/lotus/blob/master/documentation/en/api-v0-methods.md#StateLookupRobustAddress>
+pub enum StateLookupRobustAddress {}
+
+impl RpcMethod<2> for StateLookupRobustAddress {
+    const NAME: &'static str = "Filecoin.StateLookupRobustAddress";
+    const PARAM_NAMES: [&'static str; 2] = ["address", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Address, ApiTipsetKey);
+    type Ok = Address;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (address, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+        Ok(ctx
+            .state_manager
+            .lookup_robust_address(&address, ts.as_ref())?)
+    }
+}
+
 // StateVerifiedClientStatus returns the data cap for the given address.
 // Returns zero if there is no entry in the data cap table for the address.
 pub enum StateVerifierStatus {}
@@ -257,6 +374,67 @@ impl RpcMethod<2> for StateGetActor {
     }
 }
 
+/// Returns the actors that were added or modified between two state roots, keyed by address,
+/// along with their new state.
+pub enum StateChangedActors {}
+
+impl RpcMethod<2> for StateChangedActors {
+    const NAME: &'static str = "Filecoin.StateChangedActors";
+    const PARAM_NAMES: [&'static str; 2] = ["old_root", "new_root"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Cid, Cid);
+    type Ok = HashMap<String, ActorState>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (old_root, new_root): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let changes = crate::statediff::changed_actors(
+            &ctx.state_manager.blockstore_owned(),
+            &old_root,
+            &new_root,
+        )?;
+        // Like Lotus, removed actors are not reported.
+        Ok(changes
+            .into_iter()
+            .filter_map(|(address, ActorChange { new, .. })| Some((address.to_string(), new?)))
+            .collect())
+    }
+}
+
+/// Returns the actors that were added, removed, or modified between two state roots, keyed by
+/// address, along with their old and new states.
+pub enum StateActorChanges {}
+
+impl RpcMethod<2> for StateActorChanges {
+    const NAME: &'static str = "Forest.StateActorChanges";
+    const PARAM_NAMES: [&'static str; 2] = ["old_root", "new_root"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Cid, Cid);
+    type Ok = HashMap<String, ChangedActor>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (old_root, new_root): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let changes = crate::statediff::changed_actors(
+            &ctx.state_manager.blockstore_owned(),
+            &old_root,
+            &new_root,
+        )?;
+        Ok(changes
+            .into_iter()
+            .map(|(address, ActorChange { old, new })| {
+                (address.to_string(), ChangedActor { old, new })
+            })
+            .collect())
+    }
+}
+
 /// looks up the Escrow and Locked balances of the given address in the Storage
 /// Market
 pub enum StateMarketBalance {}
@@ -297,10 +475,7 @@ impl RpcMethod<1> for StateMarketDeals {
         (ApiTipsetKey(tsk),): Self::Params,
     ) -> Result<Self::Ok, ServerError> {
         let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
-        let actor = ctx
-            .state_manager
-            .get_required_actor(&Address::MARKET_ACTOR, *ts.parent_state())?;
-        let market_state = market::State::load(ctx.store(), actor.code, actor.state)?;
+        let market_state = load_market_state(&ctx, &ts)?;
 
         let da = market_state.proposals(ctx.store())?;
         let sa = market_state.states(ctx.store())?;
@@ -327,6 +502,190 @@ impl RpcMethod<1> for StateMarketDeals {
     }
 }
 
+/// Forest-specific paginated and filtered variant of [`StateMarketDeals`]. Pages are ordered by
+/// deal ID; pass the `Next` cursor and the `TipsetKey` of a page to fetch the one after it.
+pub enum StateMarketDealsPaginated {}
+
+impl RpcMethod<4> for StateMarketDealsPaginated {
+    const NAME: &'static str = "Forest.StateMarketDealsPaginated";
+    const PARAM_NAMES: [&'static str; 4] = ["filter", "cursor", "limit", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Option<MarketDealFilter>, Option<DealID>, u64, ApiTipsetKey);
+    type Ok = MarketDealsPage;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (filter, cursor, limit, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let limit = page_limit(limit)?;
+        ensure_page_tipset(cursor.is_some(), &tsk)?;
+        let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+        let market_state = load_market_state(&ctx, &ts)?;
+        let Some(filter) =
+            resolve_market_deal_filter(&ctx.state_manager, &ts, filter.unwrap_or_default())?
+        else {
+            return Ok(MarketDealsPage {
+                deals: HashMap::new(),
+                next: None,
+                tipset_key: ts.key().clone(),
+            });
+        };
+        Ok(market_deals_page(
+            ctx.store(),
+            &ts,
+            &market_state,
+            &filter,
+            cursor.unwrap_or_default(),
+            limit,
+        )?)
+    }
+}
+
+/// Streams the market deals matching a filter as a channel of [`MarketDealsPage`]s, which is
+/// closed after the last page.
+pub const STATE_MARKET_DEALS_STREAM: &str = "Forest.StateMarketDealsStream";
+
+pub(crate) fn state_market_deals_stream<DB: Blockstore + Send + Sync + 'static>(
+    params: Params<'_>,
+    ctx: &Ctx<DB>,
+) -> Result<mpsc::Receiver<Result<MarketDealsPage, ServerError>>, ServerError> {
+    let mut params = params.sequence();
+    let filter: Option<LotusJson<MarketDealFilter>> = params.optional_next()?;
+    let tsk: Option<LotusJson<ApiTipsetKey>> = params.optional_next()?;
+    let ApiTipsetKey(tsk) = tsk.map(LotusJson::into_inner).unwrap_or_default();
+
+    let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+    let market_state = load_market_state(ctx, &ts)?;
+    let filter = resolve_market_deal_filter(
+        &ctx.state_manager,
+        &ts,
+        filter.map(LotusJson::into_inner).unwrap_or_default(),
+    )?;
+
+    let (sender, receiver) = mpsc::channel(STREAM_CAPACITY);
+    let ctx = ctx.clone();
+    tokio::task::spawn_blocking(move || {
+        let Some(filter) = filter else {
+            return;
+        };
+        let mut cursor = 0;
+        loop {
+            let page = match market_deals_page(
+                ctx.store(),
+                &ts,
+                &market_state,
+                &filter,
+                cursor,
+                STREAM_PAGE_SIZE,
+            ) {
+                Ok(page) => page,
+                Err(e) => {
+                    // The error is reported to the client as the stream is closed.
+                    let _ = sender.blocking_send(Err(e.into()));
+                    break;
+                }
+            };
+            let next = page.next;
+            // Fails only if the client went away.
+            if sender.blocking_send(Ok(page)).is_err() {
+                break;
+            }
+            match next {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+    });
+    Ok(receiver)
+}
+
+fn load_market_state(ctx: &Ctx<impl Blockstore>, ts: &Tipset) -> anyhow::Result<market::State> {
+    let actor = ctx
+        .state_manager
+        .get_required_actor(&Address::MARKET_ACTOR, *ts.parent_state())?;
+    market::State::load(ctx.store(), actor.code, actor.state)
+}
+
+/// Resolves the addresses of the filter to the ID addresses stored by the market actor. Returns
+/// `None` if any of them does not exist, in which case no deal can match.
+fn resolve_market_deal_filter(
+    state_manager: &StateManager<impl Blockstore + Send + Sync + 'static>,
+    ts: &Tipset,
+    mut filter: MarketDealFilter,
+) -> anyhow::Result<Option<MarketDealFilter>> {
+    for address in [&mut filter.provider, &mut filter.client]
+        .into_iter()
+        .flatten()
+    {
+        match state_manager.lookup_id(address, ts)? {
+            Some(id) => *address = id,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(filter))
+}
+
+/// Collects up to `limit` deals matching `filter`, starting at deal ID `cursor`, from the market
+/// state of the tipset `ts`.
+fn market_deals_page(
+    store: &impl Blockstore,
+    ts: &Tipset,
+    market_state: &market::State,
+    filter: &MarketDealFilter,
+    cursor: DealID,
+    limit: usize,
+) -> anyhow::Result<MarketDealsPage> {
+    let proposals = market_state.proposals(store)?;
+    let states = market_state.states(store)?;
+
+    let mut deals = HashMap::new();
+    let mut next = None;
+    proposals.for_each_while_from(cursor, |deal_id, proposal| {
+        let state = states.get(deal_id)?.unwrap_or(market::DealState::empty());
+        let deal = ApiMarketDeal::from(MarketDeal { proposal, state });
+        if filter.matches(&deal) {
+            if deals.len() == limit {
+                next = Some(deal_id);
+                return Ok(false);
+            }
+            deals.insert(deal_id.to_string(), deal);
+        }
+        Ok(true)
+    })?;
+    Ok(MarketDealsPage {
+        deals,
+        next,
+        tipset_key: ts.key().clone(),
+    })
+}
+
+/// Maximum number of entries in a page of a paginated method.
+const MAX_PAGE_LIMIT: u64 = 10_000;
+/// Number of entries in each page of a streaming method.
+const STREAM_PAGE_SIZE: usize = 1_000;
+/// Number of pages a streaming method may buffer before waiting for the client to catch up.
+const STREAM_CAPACITY: usize = 4;
+
+fn page_limit(limit: u64) -> anyhow::Result<usize> {
+    anyhow::ensure!(
+        (1..=MAX_PAGE_LIMIT).contains(&limit),
+        "page limit must be between 1 and {MAX_PAGE_LIMIT}"
+    );
+    Ok(limit as usize)
+}
+
+/// Cursors are only meaningful at the tipset of the page they come from, so they must be passed
+/// along with the `TipsetKey` of that page rather than resolved against the current head.
+fn ensure_page_tipset(has_cursor: bool, tsk: &Option<TipsetKey>) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !has_cursor || tsk.is_some(),
+        "a tipset key is required along with a cursor"
+    );
+    Ok(())
+}
+
 /// looks up the miner info of the given address.
 pub enum StateMinerInfo {}
 
@@ -511,6 +870,81 @@ impl RpcMethod<3> for StateMinerSectorAllocated {
     }
 }
 
+/// Returns the bitfield of sector numbers allocated by a miner
+pub enum StateMinerAllocated {}
+
+impl RpcMethod<2> for StateMinerAllocated {
+    const NAME: &'static str = "Filecoin.StateMinerAllocated";
+    const PARAM_NAMES: [&'static str; 2] = ["miner_address", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Address, ApiTipsetKey);
+    type Ok = BitField;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (miner_address, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+        let actor = ctx
+            .state_manager
+            .get_required_actor(&miner_address, *ts.parent_state())?;
+        let miner_state = miner::State::load(ctx.store(), actor.code, actor.state)?;
+        Ok(miner_state.load_allocated_sector_numbers(ctx.store())?)
+    }
+}
+
+/// Checks a batch of sector numbers against the allocated bitfield of a miner,
+/// returning the subset that is allocated
+pub enum StateMinerSectorsAllocated {}
+
+impl RpcMethod<3> for StateMinerSectorsAllocated {
+    const NAME: &'static str = "Forest.StateMinerSectorsAllocated";
+    const PARAM_NAMES: [&'static str; 3] = ["miner_address", "sector_numbers", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Address, BitField, ApiTipsetKey);
+    type Ok = BitField;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (miner_address, sector_numbers, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+        Ok(miner_sectors_allocated(
+            ctx.store(),
+            ts.parent_state(),
+            &miner_address,
+            &sector_numbers,
+        )?)
+    }
+}
+
+/// Returns the sectors of `sector_numbers` allocated by the miner in the state
+/// tree `state_root`.
+fn miner_sectors_allocated(
+    store: &impl Blockstore,
+    state_root: &Cid,
+    miner_address: &Address,
+    sector_numbers: &BitField,
+) -> anyhow::Result<BitField> {
+    let miner_state = load_miner_state(store, state_root, miner_address)?;
+    let allocated_sector_numbers = miner_state.load_allocated_sector_numbers(store)?;
+    Ok(&allocated_sector_numbers & sector_numbers)
+}
+
+fn load_miner_state(
+    store: &impl Blockstore,
+    state_root: &Cid,
+    miner_address: &Address,
+) -> anyhow::Result<miner::State> {
+    let actor =
+        StateTree::new_from_root(Arc::new(store), state_root)?.get_required_actor(miner_address)?;
+    miner::State::load(store, actor.code, actor.state)
+}
+
 /// looks up the miner power of the given address.
 pub enum StateMinerPower {}
 
@@ -1204,6 +1638,64 @@ impl RpcMethod<4> for StateGetRandomnessFromBeacon {
     }
 }
 
+/// Get the raw randomness digest from tickets, without entropy mixing
+pub enum StateGetRandomnessDigestFromTickets {}
+
+impl RpcMethod<2> for StateGetRandomnessDigestFromTickets {
+    const NAME: &'static str = "Filecoin.StateGetRandomnessDigestFromTickets";
+    const PARAM_NAMES: [&'static str; 2] = ["rand_epoch", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (ChainEpoch, ApiTipsetKey);
+    type Ok = Vec<u8>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (rand_epoch, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let tipset = ctx
+            .state_manager
+            .chain_store()
+            .load_required_tipset_or_heaviest(&tsk)?;
+        let chain_config = ctx.state_manager.chain_config();
+        let chain_index = &ctx.chain_store.chain_index;
+        let beacon = ctx.state_manager.beacon_schedule();
+        let chain_rand = ChainRand::new(chain_config.clone(), tipset, chain_index.clone(), beacon);
+        let digest = chain_rand.get_chain_randomness(rand_epoch, false)?;
+        Ok(digest.to_vec())
+    }
+}
+
+/// Get the raw randomness digest from beacon, without entropy mixing
+pub enum StateGetRandomnessDigestFromBeacon {}
+
+impl RpcMethod<2> for StateGetRandomnessDigestFromBeacon {
+    const NAME: &'static str = "Filecoin.StateGetRandomnessDigestFromBeacon";
+    const PARAM_NAMES: [&'static str; 2] = ["rand_epoch", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (ChainEpoch, ApiTipsetKey);
+    type Ok = Vec<u8>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (rand_epoch, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let tipset = ctx
+            .state_manager
+            .chain_store()
+            .load_required_tipset_or_heaviest(&tsk)?;
+        let chain_config = ctx.state_manager.chain_config();
+        let chain_index = &ctx.chain_store.chain_index;
+        let beacon = ctx.state_manager.beacon_schedule();
+        let chain_rand = ChainRand::new(chain_config.clone(), tipset, chain_index.clone(), beacon);
+        let digest = chain_rand.get_beacon_randomness_v3(rand_epoch)?;
+        Ok(digest.to_vec())
+    }
+}
+
 /// Get read state
 pub enum StateReadState {}
 
@@ -1236,6 +1728,51 @@ impl RpcMethod<2> for StateReadState {
     }
 }
 
+pub enum StateDecodeParams {}
+
+impl RpcMethod<4> for StateDecodeParams {
+    const NAME: &'static str = "Filecoin.StateDecodeParams";
+    const PARAM_NAMES: [&'static str; 4] = ["address", "method", "params", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Address, MethodNum, Vec<u8>, ApiTipsetKey);
+    type Ok = serde_json::Value;
+
+    /// decodes the CBOR parameters of a method of the actor at the given address, according
+    /// to the actor type and version.
+    async fn handle(
+        ctx: Ctx<impl Blockstore>,
+        (address, method, params, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+        let actor = ctx
+            .state_manager
+            .get_required_actor(&address, *ts.parent_state())?;
+        Ok(params::decode_params(&actor.code, method, &params)?)
+    }
+}
+
+pub enum StateEncodeParams {}
+
+impl RpcMethod<3> for StateEncodeParams {
+    const NAME: &'static str = "Filecoin.StateEncodeParams";
+    const PARAM_NAMES: [&'static str; 3] = ["actor_code", "method", "params"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Cid, MethodNum, serde_json::Value);
+    type Ok = Vec<u8>;
+
+    /// encodes the Lotus JSON parameters of a method of the actor with the given code into CBOR.
+    async fn handle(
+        _: Ctx<impl Blockstore>,
+        (actor_code, method, params): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(params::encode_params(&actor_code, method, params)?)
+    }
+}
+
 pub enum StateCirculatingSupply {}
 
 impl RpcMethod<1> for StateCirculatingSupply {
@@ -1365,6 +1902,117 @@ impl RpcMethod<1> for StateListActors {
     }
 }
 
+/// Forest-specific paginated and filtered variant of [`StateListActors`]. Pages follow the order
+/// of the state tree; pass the `Next` cursor and the `TipsetKey` of a page to fetch the one after
+/// it.
+pub enum StateListActorsPaginated {}
+
+impl RpcMethod<4> for StateListActorsPaginated {
+    const NAME: &'static str = "Forest.StateListActorsPaginated";
+    const PARAM_NAMES: [&'static str; 4] = ["filter", "cursor", "limit", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Option<ActorFilter>, Option<Address>, u64, ApiTipsetKey);
+    type Ok = ActorsPage;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (filter, cursor, limit, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let limit = page_limit(limit)?;
+        ensure_page_tipset(cursor.is_some(), &tsk)?;
+        let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+        Ok(actors_page(
+            ctx.store(),
+            &ts,
+            &filter.unwrap_or_default(),
+            cursor,
+            limit,
+        )?)
+    }
+}
+
+/// Streams the actors matching a filter as a channel of [`ActorsPage`]s, which is closed after
+/// the last page.
+pub const STATE_LIST_ACTORS_STREAM: &str = "Forest.StateListActorsStream";
+
+pub(crate) fn state_list_actors_stream<DB: Blockstore + Send + Sync + 'static>(
+    params: Params<'_>,
+    ctx: &Ctx<DB>,
+) -> Result<mpsc::Receiver<Result<ActorsPage, ServerError>>, ServerError> {
+    let mut params = params.sequence();
+    let filter: Option<LotusJson<ActorFilter>> = params.optional_next()?;
+    let filter = filter.map(LotusJson::into_inner).unwrap_or_default();
+    let tsk: Option<LotusJson<ApiTipsetKey>> = params.optional_next()?;
+    let ApiTipsetKey(tsk) = tsk.map(LotusJson::into_inner).unwrap_or_default();
+    let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+
+    let (sender, receiver) = mpsc::channel(STREAM_CAPACITY);
+    let ctx = ctx.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut cursor = None;
+        loop {
+            let page = match actors_page(ctx.store(), &ts, &filter, cursor, STREAM_PAGE_SIZE) {
+                Ok(page) => page,
+                Err(e) => {
+                    // The error is reported to the client as the stream is closed.
+                    let _ = sender.blocking_send(Err(e.into()));
+                    break;
+                }
+            };
+            cursor = page.next;
+            // Fails only if the client went away.
+            if sender.blocking_send(Ok(page)).is_err() || cursor.is_none() {
+                break;
+            }
+        }
+    });
+    Ok(receiver)
+}
+
+/// Collects up to `limit` actors matching `filter`, starting at the actor `cursor`, from the
+/// parent state of the tipset `ts`.
+fn actors_page(
+    store: &impl Blockstore,
+    ts: &Tipset,
+    filter: &ActorFilter,
+    cursor: Option<Address>,
+    limit: usize,
+) -> anyhow::Result<ActorsPage> {
+    let mut actors = vec![];
+    let mut overflow = None;
+    let mut cursor = cursor;
+    // The state tree is walked in chunks, as the number of actors to visit before `limit` of
+    // them match is unknown.
+    loop {
+        cursor = for_each_actor_ranged(
+            store,
+            ts.parent_state(),
+            cursor.as_ref(),
+            limit,
+            |addr, actor| {
+                if overflow.is_none() && filter.matches(&actor) {
+                    if actors.len() < limit {
+                        actors.push(addr);
+                    } else {
+                        overflow = Some(addr);
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        if overflow.is_some() || cursor.is_none() {
+            break;
+        }
+    }
+    Ok(ActorsPage {
+        actors,
+        next: overflow,
+        tipset_key: ts.key().clone(),
+    })
+}
+
 pub enum StateMarketStorageDeal {}
 
 impl RpcMethod<2> for StateMarketStorageDeal {
@@ -1586,6 +2234,45 @@ impl RpcMethod<3> for StateSectorPreCommitInfo {
     }
 }
 
+/// Looks up the pre-commit info of a batch of sectors. Sectors that are not
+/// pre-committed are omitted from the result.
+pub enum StateSectorPreCommitInfoBatch {}
+
+impl RpcMethod<3> for StateSectorPreCommitInfoBatch {
+    const NAME: &'static str = "Forest.StateSectorPreCommitInfoBatch";
+    const PARAM_NAMES: [&'static str; 3] = ["miner_address", "sector_numbers", "tipset_key"];
+    const API_VERSION: ApiVersion = ApiVersion::V0;
+    const PERMISSION: Permission = Permission::Read;
+
+    type Params = (Address, BitField, ApiTipsetKey);
+    type Ok = Vec<SectorPreCommitOnChainInfo>;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (miner_address, sector_numbers, ApiTipsetKey(tsk)): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        let ts = ctx.chain_store.load_required_tipset_or_heaviest(&tsk)?;
+        Ok(sector_pre_commit_info_batch(
+            ctx.store(),
+            ts.parent_state(),
+            &miner_address,
+            &sector_numbers,
+        )?)
+    }
+}
+
+/// Returns the pre-commit info of the sectors of `sector_numbers` pre-committed
+/// by the miner in the state tree `state_root`, ordered by sector number.
+fn sector_pre_commit_info_batch(
+    store: &impl Blockstore,
+    state_root: &Cid,
+    miner_address: &Address,
+    sector_numbers: &BitField,
+) -> anyhow::Result<Vec<SectorPreCommitOnChainInfo>> {
+    load_miner_state(store, state_root, miner_address)?
+        .load_precommitted_sectors(store, Some(sector_numbers))
+}
+
 impl StateSectorPreCommitInfo {
     pub fn get_sectors(
         store: &Arc<impl Blockstore>,
@@ -2087,65 +2774,10 @@ impl StateGetAllocations {
         if addresses.is_empty() {
             let init_actor = state_tree.get_required_actor(&Address::INIT_ACTOR)?;
             let init_state = init::State::load(store, init_actor.code, init_actor.state)?;
-            match init_state {
-                init::State::V0(_) => unimplemented!(),
-                init::State::V8(s) => {
-                    let map =
-                        fil_actors_shared::v8::make_map_with_root::<_, u64>(&s.address_map, store)?;
-                    map.for_each(|_k, v| {
-                        addresses.insert(Address::new_id(*v));
-                        Ok(())
-                    })?;
-                }
-                init::State::V9(s) => {
-                    let map =
-                        fil_actors_shared::v9::make_map_with_root::<_, u64>(&s.address_map, store)?;
-                    map.for_each(|_k, v| {
-                        addresses.insert(Address::new_id(*v));
-                        Ok(())
-                    })?;
-                }
-                init::State::V10(s) => {
-                    let map = fil_actors_shared::v10::make_map_with_root::<_, u64>(
-                        &s.address_map,
-                        store,
-                    )?;
-                    map.for_each(|_k, v| {
-                        addresses.insert(Address::new_id(*v));
-                        Ok(())
-                    })?;
-                }
-                init::State::V11(s) => {
-                    let map = fil_actors_shared::v11::make_map_with_root::<_, u64>(
-                        &s.address_map,
-                        store,
-                    )?;
-                    map.for_each(|_k, v| {
-                        addresses.insert(Address::new_id(*v));
-                        Ok(())
-                    })?;
-                }
-                init::State::V12(s) => {
-                    let map = fil_actors_shared::v12::make_map_with_root::<_, u64>(
-                        &s.address_map,
-                        store,
-                    )?;
-                    map.for_each(|_k, v| {
-                        addresses.insert(Address::new_id(*v));
-                        Ok(())
-                    })?;
-                }
-                init::State::V13(s) => {
-                    let map = fil_actors_shared::v13::make_map_with_root::<_, u64>(
-                        &s.address_map,
-                        store,
-                    )?;
-                    map.for_each(|_k, v| {
-                        addresses.insert(Address::new_id(*v));
-                        Ok(())
-                    })?;
-                }
-            };
+            init_state.for_each_address(store, |_, id| {
+                addresses.insert(Address::new_id(id));
+                Ok(())
+            })?;
         }
 
         Ok(addresses
@@ -2323,3 +2955,106 @@ impl TryFrom<&ChainConfig> for ForkUpgradeParams {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryDB;
+    use crate::shim::state_tree::{ActorState, StateTreeVersion};
+    use crate::utils::db::CborStoreExt as _;
+    use cid::multihash::{Code::Identity, MultihashDigest as _};
+    use fil_actor_interface::KNOWN_CIDS;
+    use fil_actor_miner_state::v13::{
+        CollisionPolicy, CompactCommD, SectorPreCommitInfo,
+        SectorPreCommitOnChainInfo as SectorPreCommitOnChainInfoV13,
+    };
+    use fil_actors_shared::v13::runtime::Policy;
+    use fvm_shared4::sector::RegisteredSealProof as RegisteredSealProofV4;
+
+    /// Stores a state tree with a single miner, which allocated sectors 1 to 5
+    /// and pre-committed sectors 2 and 4, and returns its root.
+    fn miner_state_root(store: &MemoryDB, miner_address: &Address) -> Cid {
+        let placeholder = Cid::new_v1(DAG_CBOR, Identity.digest(&[]));
+        let mut st =
+            fil_actor_miner_state::v13::State::new(&Policy::default(), store, placeholder, 0, 0)
+                .unwrap();
+        st.allocate_sector_numbers(
+            store,
+            &BitField::try_from_bits(1..=5).unwrap(),
+            CollisionPolicy::DenyCollisions,
+        )
+        .unwrap();
+        let precommit = |sector_number| SectorPreCommitOnChainInfoV13 {
+            info: SectorPreCommitInfo {
+                seal_proof: RegisteredSealProofV4::StackedDRG32GiBV1P1,
+                sector_number,
+                sealed_cid: placeholder,
+                seal_rand_epoch: 0,
+                deal_ids: vec![],
+                expiration: 0,
+                unsealed_cid: CompactCommD::default(),
+            },
+            pre_commit_deposit: Default::default(),
+            pre_commit_epoch: 0,
+        };
+        st.put_precommitted_sectors(store, vec![precommit(4), precommit(2)])
+            .unwrap();
+
+        let mut state_tree = StateTree::new(Arc::new(store), StateTreeVersion::V5).unwrap();
+        state_tree
+            .set_actor(
+                miner_address,
+                ActorState::new(
+                    KNOWN_CIDS.actor.miner.v13.calibnet,
+                    store.put_cbor_default(&st).unwrap(),
+                    Default::default(),
+                    0,
+                    None,
+                ),
+            )
+            .unwrap();
+        state_tree.flush().unwrap()
+    }
+
+    #[test]
+    fn miner_sectors_allocated_intersects_the_allocated_sectors() {
+        let store = MemoryDB::default();
+        let miner_address = Address::new_id(1000);
+        let root = miner_state_root(&store, &miner_address);
+
+        let allocated = miner_sectors_allocated(
+            &store,
+            &root,
+            &miner_address,
+            &BitField::try_from_bits([0, 2, 5, 6]).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(allocated.iter().collect::<Vec<_>>(), [2, 5]);
+        assert!(
+            miner_sectors_allocated(&store, &root, &Address::new_id(1001), &BitField::new())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn sector_pre_commit_info_batch_skips_sectors_not_pre_committed() {
+        let store = MemoryDB::default();
+        let miner_address = Address::new_id(1000);
+        let root = miner_state_root(&store, &miner_address);
+
+        let infos = sector_pre_commit_info_batch(
+            &store,
+            &root,
+            &miner_address,
+            &BitField::try_from_bits([1, 2, 3, 4]).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            infos
+                .iter()
+                .map(|info| info.info.sector_number)
+                .collect::<Vec<_>>(),
+            [2, 4]
+        );
+    }
+}
@@ -0,0 +1,986 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Decoding and encoding of the parameters of built-in actor methods, between their CBOR
+//! encoding and their Lotus JSON representation.
+//!
+//! The actor and its version are resolved from the actor code CID. The parameters are decoded
+//! into, and encoded from, the parameter types of that actor version, which are then converted
+//! to a Lotus JSON representation shared by all the versions.
+
+use std::fmt;
+
+use crate::lotus_json::HasLotusJson;
+use crate::shim::address::Address;
+use crate::shim::crypto::{Signature, SignatureType};
+use crate::shim::econ::TokenAmount;
+use crate::shim::message::MethodNum;
+use crate::shim::sector::{PoStProof, RegisteredPoStProof, RegisteredSealProof};
+use ahash::HashMap;
+use anyhow::Context as _;
+use cid::Cid;
+use fil_actors_shared::fvm_ipld_bitfield::{BitField, UnvalidatedBitField};
+use fvm_ipld_encoding::{BytesDe, RawBytes};
+use num::BigInt;
+use num_traits::FromPrimitive as _;
+use once_cell::sync::Lazy;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Decodes the CBOR `params` of the given method of the actor with the given code, into their
+/// Lotus JSON representation.
+pub fn decode_params(
+    code: &Cid,
+    method: MethodNum,
+    params: &[u8],
+) -> anyhow::Result<serde_json::Value> {
+    (params_codec(code, method)?.decode)(params)
+}
+
+/// Encodes the Lotus JSON `params` of the given method of the actor with the given code, into
+/// CBOR.
+pub fn encode_params(
+    code: &Cid,
+    method: MethodNum,
+    params: serde_json::Value,
+) -> anyhow::Result<Vec<u8>> {
+    (params_codec(code, method)?.encode)(params)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BuiltinActor {
+    Datacap,
+    Init,
+    Market,
+    Miner,
+    Multisig,
+    Power,
+    VerifiedRegistry,
+}
+
+impl fmt::Display for BuiltinActor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Datacap => "datacap",
+            Self::Init => "init",
+            Self::Market => "storagemarket",
+            Self::Miner => "storageminer",
+            Self::Multisig => "multisig",
+            Self::Power => "storagepower",
+            Self::VerifiedRegistry => "verifiedregistry",
+        })
+    }
+}
+
+type IsActorCode = fn(&Cid) -> bool;
+
+/// Resolves the built-in actor, and its version, having the given code CID.
+fn builtin_actor(code: &Cid) -> Option<(BuiltinActor, u64)> {
+    use fil_actor_interface::{datacap, init, market, miner, multisig, power, verifreg};
+    use BuiltinActor::*;
+    let known: [(IsActorCode, BuiltinActor, u64); 41] = [
+        (datacap::is_v9_datacap_cid, Datacap, 9),
+        (datacap::is_v10_datacap_cid, Datacap, 10),
+        (datacap::is_v11_datacap_cid, Datacap, 11),
+        (datacap::is_v12_datacap_cid, Datacap, 12),
+        (datacap::is_v13_datacap_cid, Datacap, 13),
+        (init::is_v8_init_cid, Init, 8),
+        (init::is_v9_init_cid, Init, 9),
+        (init::is_v10_init_cid, Init, 10),
+        (init::is_v11_init_cid, Init, 11),
+        (init::is_v12_init_cid, Init, 12),
+        (init::is_v13_init_cid, Init, 13),
+        (market::is_v8_market_cid, Market, 8),
+        (market::is_v9_market_cid, Market, 9),
+        (market::is_v10_market_cid, Market, 10),
+        (market::is_v11_market_cid, Market, 11),
+        (market::is_v12_market_cid, Market, 12),
+        (market::is_v13_market_cid, Market, 13),
+        (miner::is_v8_miner_cid, Miner, 8),
+        (miner::is_v9_miner_cid, Miner, 9),
+        (miner::is_v10_miner_cid, Miner, 10),
+        (miner::is_v11_miner_cid, Miner, 11),
+        (miner::is_v12_miner_cid, Miner, 12),
+        (miner::is_v13_miner_cid, Miner, 13),
+        (multisig::is_v8_multisig_cid, Multisig, 8),
+        (multisig::is_v9_multisig_cid, Multisig, 9),
+        (multisig::is_v10_multisig_cid, Multisig, 10),
+        (multisig::is_v11_multisig_cid, Multisig, 11),
+        (multisig::is_v12_multisig_cid, Multisig, 12),
+        (multisig::is_v13_multisig_cid, Multisig, 13),
+        (power::is_v8_power_cid, Power, 8),
+        (power::is_v9_power_cid, Power, 9),
+        (power::is_v10_power_cid, Power, 10),
+        (power::is_v11_power_cid, Power, 11),
+        (power::is_v12_power_cid, Power, 12),
+        (power::is_v13_power_cid, Power, 13),
+        (verifreg::is_v8_verifreg_cid, VerifiedRegistry, 8),
+        (verifreg::is_v9_verifreg_cid, VerifiedRegistry, 9),
+        (verifreg::is_v10_verifreg_cid, VerifiedRegistry, 10),
+        (verifreg::is_v11_verifreg_cid, VerifiedRegistry, 11),
+        (verifreg::is_v12_verifreg_cid, VerifiedRegistry, 12),
+        (verifreg::is_v13_verifreg_cid, VerifiedRegistry, 13),
+    ];
+    known
+        .into_iter()
+        .find(|(is_actor, _, _)| is_actor(code))
+        .map(|(_, actor, version)| (actor, version))
+}
+
+/// Converts the parameters of a method between CBOR and Lotus JSON.
+#[derive(Clone, Copy)]
+struct ParamsCodec {
+    decode: fn(&[u8]) -> anyhow::Result<serde_json::Value>,
+    encode: fn(serde_json::Value) -> anyhow::Result<Vec<u8>>,
+}
+
+impl ParamsCodec {
+    /// The codec of the actor parameter type `T`, represented in Lotus JSON by `J`.
+    fn of<T: Serialize + DeserializeOwned + ParamsField<J>, J: HasLotusJson>() -> Self {
+        Self {
+            decode: |params| {
+                Ok(fvm_ipld_encoding::from_slice::<T>(params)?
+                    .to_json()?
+                    .into_lotus_json_value()?)
+            },
+            encode: |params| {
+                let params = T::from_json(J::from_lotus_json(serde_json::from_value(params)?))?;
+                Ok(fvm_ipld_encoding::to_vec(&params)?)
+            },
+        }
+    }
+}
+
+/// Conversion between a type of the actor parameters, as defined by a given actor version, and
+/// its counterpart in the Lotus JSON representation shared by all the versions.
+trait ParamsField<T>: Sized {
+    fn to_json(self) -> anyhow::Result<T>;
+    fn from_json(json: T) -> anyhow::Result<Self>;
+}
+
+impl<T, U: ParamsField<T>> ParamsField<Vec<T>> for Vec<U> {
+    fn to_json(self) -> anyhow::Result<Vec<T>> {
+        self.into_iter().map(ParamsField::to_json).collect()
+    }
+
+    fn from_json(json: Vec<T>) -> anyhow::Result<Self> {
+        json.into_iter().map(ParamsField::from_json).collect()
+    }
+}
+
+impl<T, U: ParamsField<T>> ParamsField<Option<T>> for Option<U> {
+    fn to_json(self) -> anyhow::Result<Option<T>> {
+        self.map(ParamsField::to_json).transpose()
+    }
+
+    fn from_json(json: Option<T>) -> anyhow::Result<Self> {
+        json.map(ParamsField::from_json).transpose()
+    }
+}
+
+/// Types shared by all the actor versions.
+macro_rules! identity_fields {
+    ($($ty:ty),* $(,)?) => {$(
+        impl ParamsField<$ty> for $ty {
+            fn to_json(self) -> anyhow::Result<$ty> {
+                Ok(self)
+            }
+
+            fn from_json(json: $ty) -> anyhow::Result<Self> {
+                Ok(json)
+            }
+        }
+    )*};
+}
+
+identity_fields!(bool, u8, u64, i64, String, Cid, RawBytes, BitField, BigInt, Address);
+
+/// Types of the different `fvm_shared` versions, converted to their shim.
+macro_rules! shim_fields {
+    ($shim:ty: $($ty:ty),+ $(,)?) => {$(
+        impl ParamsField<$shim> for $ty {
+            fn to_json(self) -> anyhow::Result<$shim> {
+                Ok(self.into())
+            }
+
+            fn from_json(json: $shim) -> anyhow::Result<Self> {
+                Ok(json.into())
+            }
+        }
+    )+};
+}
+
+shim_fields!(
+    Address: fvm_shared2::address::Address,
+    fvm_shared3::address::Address,
+    fvm_shared4::address::Address,
+);
+shim_fields!(
+    TokenAmount: fvm_shared2::econ::TokenAmount,
+    fvm_shared3::econ::TokenAmount,
+    fvm_shared4::econ::TokenAmount,
+);
+shim_fields!(
+    RegisteredSealProof: fvm_shared2::sector::RegisteredSealProof,
+    fvm_shared3::sector::RegisteredSealProof,
+    fvm_shared4::sector::RegisteredSealProof,
+);
+shim_fields!(Vec<u8>: RawBytes);
+
+/// Newtypes, converted through the type they wrap.
+macro_rules! newtype_fields {
+    ($json:ty: $($ty:ty),+ $(,)?) => {$(
+        impl ParamsField<$json> for $ty {
+            fn to_json(self) -> anyhow::Result<$json> {
+                ParamsField::to_json(self.0)
+            }
+
+            fn from_json(json: $json) -> anyhow::Result<Self> {
+                Ok(Self(ParamsField::from_json(json)?))
+            }
+        }
+    )+};
+}
+
+newtype_fields!(Vec<u8>: BytesDe);
+newtype_fields!(
+    Vec<u8>: fvm_shared2::randomness::Randomness,
+    fvm_shared3::randomness::Randomness,
+    fvm_shared4::randomness::Randomness,
+);
+newtype_fields!(
+    u64: fvm_shared2::piece::PaddedPieceSize,
+    fvm_shared3::piece::PaddedPieceSize,
+    fvm_shared4::piece::PaddedPieceSize,
+);
+
+macro_rules! proof_fields {
+    ($($version:ident),+) => {$(
+        impl ParamsField<RegisteredPoStProof> for $version::sector::RegisteredPoStProof {
+            fn to_json(self) -> anyhow::Result<RegisteredPoStProof> {
+                Ok(i64::from(self).into())
+            }
+
+            fn from_json(json: RegisteredPoStProof) -> anyhow::Result<Self> {
+                Ok(i64::from(*json).into())
+            }
+        }
+
+        impl ParamsField<PoStProof> for $version::sector::PoStProof {
+            fn to_json(self) -> anyhow::Result<PoStProof> {
+                Ok(PoStProof::new(self.post_proof.to_json()?, self.proof_bytes))
+            }
+
+            fn from_json(json: PoStProof) -> anyhow::Result<Self> {
+                let fvm_shared3::sector::PoStProof {
+                    post_proof,
+                    proof_bytes,
+                } = json.into();
+                Ok(Self {
+                    post_proof: ParamsField::from_json(RegisteredPoStProof::from(post_proof))?,
+                    proof_bytes,
+                })
+            }
+        }
+
+        impl ParamsField<Signature> for $version::crypto::signature::Signature {
+            fn to_json(self) -> anyhow::Result<Signature> {
+                let sig_type = SignatureType::from_u8(self.sig_type as u8)
+                    .context("unsupported signature type")?;
+                Ok(Signature::new(sig_type, self.bytes))
+            }
+
+            fn from_json(json: Signature) -> anyhow::Result<Self> {
+                let sig_type =
+                    $version::crypto::signature::SignatureType::from_u8(json.sig_type as u8)
+                        .context("unsupported signature type")?;
+                Ok(Self {
+                    sig_type,
+                    bytes: json.bytes,
+                })
+            }
+        }
+    )+};
+}
+
+proof_fields!(fvm_shared2, fvm_shared3, fvm_shared4);
+
+impl ParamsField<BitField> for UnvalidatedBitField {
+    fn to_json(self) -> anyhow::Result<BitField> {
+        Ok(match self {
+            Self::Validated(bit_field) => bit_field,
+            Self::Unvalidated(bytes) => BitField::from_bytes(&bytes)?,
+        })
+    }
+
+    fn from_json(json: BitField) -> anyhow::Result<Self> {
+        Ok(json.into())
+    }
+}
+
+/// Maps a version module of an actor crate to its version number.
+macro_rules! actor_version {
+    (v8) => {
+        8
+    };
+    (v9) => {
+        9
+    };
+    (v10) => {
+        10
+    };
+    (v11) => {
+        11
+    };
+    (v12) => {
+        12
+    };
+    (v13) => {
+        13
+    };
+}
+
+/// The methods whose parameters can be decoded and encoded, by actor, actor version and method
+/// number.
+static METHOD_PARAMS: Lazy<HashMap<(BuiltinActor, u64, MethodNum), ParamsCodec>> = Lazy::new(
+    || {
+        use BuiltinActor::*;
+
+        // Method numbers are stable across versions: the legacy ones are taken from the first
+        // supported version, the exported ones from the latest.
+        use fil_actor_datacap_state::{
+            v13::Method as DatacapMethod, v9::Method as DatacapV9Method,
+        };
+        use fil_actor_init_state::v13::Method as InitMethod;
+        use fil_actor_market_state::{v13::Method as MarketMethod, v8::Method as MarketV8Method};
+        use fil_actor_miner_state::{v13::Method as MinerMethod, v8::Method as MinerV8Method};
+        use fil_actor_multisig_state::v8::Method as MultisigMethod;
+        use fil_actor_power_state::{v13::Method as PowerMethod, v8::Method as PowerV8Method};
+        use fil_actor_verifreg_state::{
+            v13::Method as VerifregMethod, v8::Method as VerifregV8Method,
+        };
+
+        let mut methods = HashMap::default();
+
+        // Registers the parameters of a method for the given actor versions, either as the
+        // parameter type defined by each version, or as a type shared by all of them.
+        macro_rules! register {
+            ($actor:ident, $method:expr, $krate:ident::[$($version:ident),+]::$params:ident as $json:ty) => {$(
+                methods.insert(
+                    ($actor, actor_version!($version), $method as MethodNum),
+                    ParamsCodec::of::<$krate::$version::$params, $json>(),
+                );
+            )+};
+            ($actor:ident, $method:expr, [$($version:ident),+] $params:ty as $json:ty) => {$(
+                methods.insert(
+                    ($actor, actor_version!($version), $method as MethodNum),
+                    ParamsCodec::of::<$params, $json>(),
+                );
+            )+};
+        }
+
+        // datacap
+        register!(Datacap, DatacapV9Method::Mint, fil_actor_datacap_state::[v9]::MintParams as datacap::MintParamsLotusJson);
+        register!(Datacap, DatacapV9Method::BalanceOf, [v9] Address as Address);
+        register!(Datacap, DatacapV9Method::Transfer, [v9] frc46_token::token::types::TransferParams as datacap::TransferParamsLotusJson);
+        register!(Datacap, DatacapV9Method::Burn, [v9] frc46_token::token::types::BurnParams as datacap::BurnParamsLotusJson);
+        register!(Datacap, DatacapMethod::MintExported, fil_actor_datacap_state::[v10, v11, v12, v13]::MintParams as datacap::MintParamsLotusJson);
+        register!(Datacap, DatacapMethod::BalanceExported, [v10, v11, v12, v13] Address as Address);
+        register!(Datacap, DatacapMethod::TransferExported, [v10, v11, v12, v13] frc46_token::token::types::TransferParams as datacap::TransferParamsLotusJson);
+        register!(Datacap, DatacapMethod::BurnExported, [v10, v11, v12, v13] frc46_token::token::types::BurnParams as datacap::BurnParamsLotusJson);
+        // init
+        register!(Init, InitMethod::Exec, fil_actor_init_state::[v8, v9, v10, v11, v12, v13]::ExecParams as init::ExecParamsLotusJson);
+        register!(Init, InitMethod::Exec4, fil_actor_init_state::[v10, v11, v12, v13]::Exec4Params as init::Exec4ParamsLotusJson);
+        // storagemarket
+        register!(Market, MarketV8Method::AddBalance, [v8, v9, v10, v11, v12, v13] Address as Address);
+        // The v8 market actor does not export its parameter types, encoded as in v9.
+        register!(Market, MarketV8Method::WithdrawBalance, [v8] fil_actor_market_state::v9::WithdrawBalanceParams as market::WithdrawBalanceParamsLotusJson);
+        register!(Market, MarketV8Method::PublishStorageDeals, [v8] fil_actor_market_state::v9::PublishStorageDealsParams as market::PublishStorageDealsParamsLotusJson);
+        register!(Market, MarketV8Method::WithdrawBalance, fil_actor_market_state::[v9, v10, v11, v12, v13]::WithdrawBalanceParams as market::WithdrawBalanceParamsLotusJson);
+        register!(Market, MarketV8Method::PublishStorageDeals, fil_actor_market_state::[v9, v10, v11, v12, v13]::PublishStorageDealsParams as market::PublishStorageDealsParamsLotusJson);
+        register!(Market, MarketMethod::AddBalanceExported, [v10, v11, v12, v13] Address as Address);
+        register!(Market, MarketMethod::WithdrawBalanceExported, fil_actor_market_state::[v10, v11, v12, v13]::WithdrawBalanceParams as market::WithdrawBalanceParamsLotusJson);
+        register!(Market, MarketMethod::PublishStorageDealsExported, fil_actor_market_state::[v10, v11, v12, v13]::PublishStorageDealsParams as market::PublishStorageDealsParamsLotusJson);
+        // storageminer
+        register!(Miner, MinerV8Method::ChangeWorkerAddress, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::ChangeWorkerAddressParams as miner::ChangeWorkerAddressParamsLotusJson);
+        register!(Miner, MinerV8Method::ChangePeerID, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::ChangePeerIDParams as miner::ChangePeerIDParamsLotusJson);
+        register!(Miner, MinerV8Method::SubmitWindowedPoSt, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::SubmitWindowedPoStParams as miner::SubmitWindowedPoStParamsLotusJson);
+        register!(Miner, MinerV8Method::PreCommitSector, fil_actor_miner_state::[v8]::SectorPreCommitInfo as miner::PreCommitSectorParamsLotusJson);
+        register!(Miner, MinerV8Method::PreCommitSector, fil_actor_miner_state::[v9, v10, v11, v12]::PreCommitSectorParams as miner::PreCommitSectorParamsLotusJson);
+        register!(Miner, MinerV8Method::ProveCommitSector, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::ProveCommitSectorParams as miner::ProveCommitSectorParamsLotusJson);
+        register!(Miner, MinerV8Method::TerminateSectors, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::TerminateSectorsParams as miner::TerminateSectorsParamsLotusJson);
+        register!(Miner, MinerV8Method::DeclareFaultsRecovered, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::DeclareFaultsRecoveredParams as miner::DeclareFaultsRecoveredParamsLotusJson);
+        register!(Miner, MinerV8Method::WithdrawBalance, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::WithdrawBalanceParams as miner::WithdrawBalanceParamsLotusJson);
+        register!(Miner, MinerV8Method::ChangeMultiaddrs, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::ChangeMultiaddrsParams as miner::ChangeMultiaddrsParamsLotusJson);
+        register!(Miner, MinerV8Method::ChangeOwnerAddress, [v8, v9, v10, v11, v12, v13] Address as Address);
+        register!(Miner, MinerV8Method::PreCommitSectorBatch, fil_actor_miner_state::[v8, v9, v10, v11, v12]::PreCommitSectorBatchParams as miner::PreCommitSectorBatchParamsLotusJson);
+        register!(Miner, MinerV8Method::ProveCommitAggregate, fil_actor_miner_state::[v8, v9, v10, v11, v12, v13]::ProveCommitAggregateParams as miner::ProveCommitAggregateParamsLotusJson);
+        register!(Miner, MinerMethod::PreCommitSectorBatch2, fil_actor_miner_state::[v9, v10, v11, v12, v13]::PreCommitSectorBatchParams2 as miner::PreCommitSectorBatchParams2LotusJson);
+        register!(Miner, MinerMethod::ChangeWorkerAddressExported, fil_actor_miner_state::[v10, v11, v12, v13]::ChangeWorkerAddressParams as miner::ChangeWorkerAddressParamsLotusJson);
+        register!(Miner, MinerMethod::ChangePeerIDExported, fil_actor_miner_state::[v10, v11, v12, v13]::ChangePeerIDParams as miner::ChangePeerIDParamsLotusJson);
+        register!(Miner, MinerMethod::WithdrawBalanceExported, fil_actor_miner_state::[v10, v11, v12, v13]::WithdrawBalanceParams as miner::WithdrawBalanceParamsLotusJson);
+        register!(Miner, MinerMethod::ChangeMultiaddrsExported, fil_actor_miner_state::[v10, v11, v12, v13]::ChangeMultiaddrsParams as miner::ChangeMultiaddrsParamsLotusJson);
+        register!(Miner, MinerMethod::ChangeOwnerAddressExported, [v10, v11, v12, v13] Address as Address);
+        // multisig
+        register!(Multisig, MultisigMethod::Constructor, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::ConstructorParams as multisig::ConstructorParamsLotusJson);
+        register!(Multisig, MultisigMethod::Propose, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::ProposeParams as multisig::ProposeParamsLotusJson);
+        register!(Multisig, MultisigMethod::Approve, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::TxnIDParams as multisig::TxnIDParamsLotusJson);
+        register!(Multisig, MultisigMethod::Cancel, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::TxnIDParams as multisig::TxnIDParamsLotusJson);
+        register!(Multisig, MultisigMethod::AddSigner, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::AddSignerParams as multisig::AddSignerParamsLotusJson);
+        register!(Multisig, MultisigMethod::RemoveSigner, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::RemoveSignerParams as multisig::RemoveSignerParamsLotusJson);
+        register!(Multisig, MultisigMethod::SwapSigner, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::SwapSignerParams as multisig::SwapSignerParamsLotusJson);
+        register!(Multisig, MultisigMethod::ChangeNumApprovalsThreshold, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::ChangeNumApprovalsThresholdParams as multisig::ChangeNumApprovalsThresholdParamsLotusJson);
+        register!(Multisig, MultisigMethod::LockBalance, fil_actor_multisig_state::[v8, v9, v10, v11, v12, v13]::LockBalanceParams as multisig::LockBalanceParamsLotusJson);
+        // storagepower
+        register!(Power, PowerV8Method::CreateMiner, fil_actor_power_state::[v8, v9, v10, v11, v12, v13]::CreateMinerParams as power::CreateMinerParamsLotusJson);
+        register!(Power, PowerMethod::CreateMinerExported, fil_actor_power_state::[v10, v11, v12, v13]::CreateMinerParams as power::CreateMinerParamsLotusJson);
+        // verifiedregistry
+        register!(VerifiedRegistry, VerifregV8Method::AddVerifier, fil_actor_verifreg_state::[v8, v9, v10, v11, v12, v13]::VerifierParams as verifreg::VerifierParamsLotusJson);
+        register!(VerifiedRegistry, VerifregV8Method::RemoveVerifier, [v8, v9, v10, v11, v12, v13] Address as Address);
+        register!(VerifiedRegistry, VerifregV8Method::AddVerifiedClient, fil_actor_verifreg_state::[v8, v9, v10, v11, v12, v13]::VerifierParams as verifreg::VerifierParamsLotusJson);
+        register!(VerifiedRegistry, VerifregMethod::AddVerifiedClientExported, fil_actor_verifreg_state::[v10, v11, v12, v13]::VerifierParams as verifreg::VerifierParamsLotusJson);
+
+        methods
+    },
+);
+
+fn params_codec(code: &Cid, method: MethodNum) -> anyhow::Result<ParamsCodec> {
+    let (actor, version) =
+        builtin_actor(code).with_context(|| format!("unsupported actor code {code}"))?;
+    METHOD_PARAMS
+        .get(&(actor, version, method))
+        .copied()
+        .with_context(|| {
+            format!("unsupported method {method} of the {actor} actor (version {version})")
+        })
+}
+
+/// Declares the Lotus JSON representation of method parameters, along with its conversions from
+/// and to the parameter types of each listed actor version. The fields of the parameter types
+/// are converted with [`ParamsField`].
+macro_rules! params {
+    (@struct $lotus_json:ident { $($field:ident: $ty:ty => $json_name:literal),* $(,)? }) => {
+        #[derive(Clone, serde::Serialize, serde::Deserialize)]
+        pub struct $lotus_json {
+            $(
+                #[serde(rename = $json_name, with = "crate::lotus_json")]
+                $field: $ty,
+            )*
+        }
+
+        crate::lotus_json::lotus_json_with_self!($lotus_json);
+    };
+    (@convert $lotus_json:ident $version:ident::$name:ident { $($field:ident: $ty:ty => $json_name:literal),* $(,)? }) => {
+        impl super::ParamsField<$lotus_json> for $version::$name {
+            fn to_json(self) -> anyhow::Result<$lotus_json> {
+                let Self { $($field,)* } = self;
+                Ok($lotus_json { $($field: super::ParamsField::to_json($field)?,)* })
+            }
+
+            fn from_json(json: $lotus_json) -> anyhow::Result<Self> {
+                let $lotus_json { $($field,)* } = json;
+                Ok(Self { $($field: super::ParamsField::from_json($field)?,)* })
+            }
+        }
+    };
+    ($(
+        $lotus_json:ident $fields:tt for $([$($version:ident),+]::$name:ident),+
+    )*) => {$(
+        params!(@struct $lotus_json $fields);
+        $($(params!(@convert $lotus_json $version::$name $fields);)+)+
+    )*};
+}
+
+mod datacap {
+    use crate::shim::{address::Address, econ::TokenAmount};
+    use fil_actor_datacap_state::{v10, v11, v12, v13, v9};
+    use frc46_token::token::types as frc46;
+    use fvm_ipld_encoding::RawBytes;
+
+    params! {
+        MintParamsLotusJson {
+            to: Address => "To",
+            amount: TokenAmount => "Amount",
+            operators: Vec<Address> => "Operators",
+        } for [v9, v10, v11, v12, v13]::MintParams
+        TransferParamsLotusJson {
+            to: Address => "To",
+            amount: TokenAmount => "Amount",
+            operator_data: RawBytes => "OperatorData",
+        } for [frc46]::TransferParams
+        BurnParamsLotusJson {
+            amount: TokenAmount => "Amount",
+        } for [frc46]::BurnParams
+    }
+}
+
+mod init {
+    use cid::Cid;
+    use fil_actor_init_state::{v10, v11, v12, v13, v8, v9};
+    use fvm_ipld_encoding::RawBytes;
+
+    params! {
+        ExecParamsLotusJson {
+            code_cid: Cid => "CodeCID",
+            constructor_params: RawBytes => "ConstructorParams",
+        } for [v8, v9, v10, v11, v12, v13]::ExecParams
+        Exec4ParamsLotusJson {
+            code_cid: Cid => "CodeCID",
+            constructor_params: RawBytes => "ConstructorParams",
+            subaddress: RawBytes => "SubAddress",
+        } for [v10, v11, v12, v13]::Exec4Params
+    }
+}
+
+mod market {
+    use super::ParamsField;
+    use crate::shim::{address::Address, crypto::Signature, econ::TokenAmount};
+    use anyhow::Context as _;
+    use cid::Cid;
+    use fil_actor_market_state::{v10, v11, v12, v13, v9};
+
+    params! {
+        WithdrawBalanceParamsLotusJson {
+            provider_or_client: Address => "ProviderOrClientAddress",
+            amount: TokenAmount => "Amount",
+        } for [v9, v10, v11, v12, v13]::WithdrawBalanceParams
+        PublishStorageDealsParamsLotusJson {
+            deals: Vec<ClientDealProposalLotusJson> => "Deals",
+        } for [v9, v10, v11, v12, v13]::PublishStorageDealsParams
+        ClientDealProposalLotusJson {
+            proposal: DealProposalLotusJson => "Proposal",
+            client_signature: Signature => "ClientSignature",
+        } for [v9, v10, v11, v12, v13]::ClientDealProposal
+        DealProposalLotusJson {
+            piece_cid: Cid => "PieceCID",
+            piece_size: u64 => "PieceSize",
+            verified_deal: bool => "VerifiedDeal",
+            client: Address => "Client",
+            provider: Address => "Provider",
+            label: String => "Label",
+            start_epoch: i64 => "StartEpoch",
+            end_epoch: i64 => "EndEpoch",
+            storage_price_per_epoch: TokenAmount => "StoragePricePerEpoch",
+            provider_collateral: TokenAmount => "ProviderCollateral",
+            client_collateral: TokenAmount => "ClientCollateral",
+        } for [v9, v10, v11, v12, v13]::DealProposal
+    }
+
+    // Like Lotus, deal labels are represented as strings, so only labels holding UTF-8 bytes can
+    // be decoded.
+    macro_rules! label_fields {
+        ($($version:ident),+) => {$(
+            impl ParamsField<String> for $version::Label {
+                fn to_json(self) -> anyhow::Result<String> {
+                    match self {
+                        Self::String(label) => Ok(label),
+                        Self::Bytes(label) => {
+                            String::from_utf8(label).context("deal label is not valid UTF-8")
+                        }
+                    }
+                }
+
+                fn from_json(json: String) -> anyhow::Result<Self> {
+                    Ok(Self::String(json))
+                }
+            }
+        )+};
+    }
+
+    label_fields!(v9, v10, v11, v12, v13);
+}
+
+mod miner {
+    use super::ParamsField;
+    use crate::shim::{
+        address::Address,
+        econ::TokenAmount,
+        sector::{PoStProof, RegisteredSealProof},
+    };
+    use cid::Cid;
+    use fil_actor_miner_state::{v10, v11, v12, v13, v8, v9};
+    use fil_actors_shared::fvm_ipld_bitfield::BitField;
+
+    params! {
+        ChangeWorkerAddressParamsLotusJson {
+            new_worker: Address => "NewWorker",
+            new_control_addresses: Vec<Address> => "NewControlAddrs",
+        } for [v8, v9, v10, v11, v12, v13]::ChangeWorkerAddressParams
+        ChangePeerIDParamsLotusJson {
+            new_id: Vec<u8> => "NewID",
+        } for [v8, v9, v10, v11, v12, v13]::ChangePeerIDParams
+        ChangeMultiaddrsParamsLotusJson {
+            new_multi_addrs: Vec<Vec<u8>> => "NewMultiaddrs",
+        } for [v8, v9, v10, v11, v12, v13]::ChangeMultiaddrsParams
+        WithdrawBalanceParamsLotusJson {
+            amount_requested: TokenAmount => "AmountRequested",
+        } for [v8, v9, v10, v11, v12, v13]::WithdrawBalanceParams
+        TerminateSectorsParamsLotusJson {
+            terminations: Vec<TerminationDeclarationLotusJson> => "Terminations",
+        } for [v8, v9, v10, v11, v12, v13]::TerminateSectorsParams
+        TerminationDeclarationLotusJson {
+            deadline: u64 => "Deadline",
+            partition: u64 => "Partition",
+            sectors: BitField => "Sectors",
+        } for [v8, v9, v10, v11, v12, v13]::TerminationDeclaration
+        DeclareFaultsRecoveredParamsLotusJson {
+            recoveries: Vec<RecoveryDeclarationLotusJson> => "Recoveries",
+        } for [v8, v9, v10, v11, v12, v13]::DeclareFaultsRecoveredParams
+        RecoveryDeclarationLotusJson {
+            deadline: u64 => "Deadline",
+            partition: u64 => "Partition",
+            sectors: BitField => "Sectors",
+        } for [v8, v9, v10, v11, v12, v13]::RecoveryDeclaration
+        SubmitWindowedPoStParamsLotusJson {
+            deadline: u64 => "Deadline",
+            partitions: Vec<PoStPartitionLotusJson> => "Partitions",
+            proofs: Vec<PoStProof> => "Proofs",
+            chain_commit_epoch: i64 => "ChainCommitEpoch",
+            chain_commit_rand: Vec<u8> => "ChainCommitRand",
+        } for [v8, v9, v10, v11, v12, v13]::SubmitWindowedPoStParams
+        PoStPartitionLotusJson {
+            index: u64 => "Index",
+            skipped: BitField => "Skipped",
+        } for [v8, v9, v10, v11, v12, v13]::PoStPartition
+        PreCommitSectorParamsLotusJson {
+            seal_proof: RegisteredSealProof => "SealProof",
+            sector_number: u64 => "SectorNumber",
+            sealed_cid: Cid => "SealedCID",
+            seal_rand_epoch: i64 => "SealRandEpoch",
+            deal_ids: Vec<u64> => "DealIDs",
+            expiration: i64 => "Expiration",
+            replace_capacity: bool => "ReplaceCapacity",
+            replace_sector_deadline: u64 => "ReplaceSectorDeadline",
+            replace_sector_partition: u64 => "ReplaceSectorPartition",
+            replace_sector_number: u64 => "ReplaceSectorNumber",
+        } for [v8]::SectorPreCommitInfo, [v9, v10, v11, v12]::PreCommitSectorParams
+        PreCommitSectorBatchParamsLotusJson {
+            sectors: Vec<PreCommitSectorParamsLotusJson> => "Sectors",
+        } for [v8, v9, v10, v11, v12]::PreCommitSectorBatchParams
+        PreCommitSectorBatchParams2LotusJson {
+            sectors: Vec<SectorPreCommitInfoLotusJson> => "Sectors",
+        } for [v9, v10, v11, v12, v13]::PreCommitSectorBatchParams2
+        SectorPreCommitInfoLotusJson {
+            seal_proof: RegisteredSealProof => "SealProof",
+            sector_number: u64 => "SectorNumber",
+            sealed_cid: Cid => "SealedCID",
+            seal_rand_epoch: i64 => "SealRandEpoch",
+            deal_ids: Vec<u64> => "DealIDs",
+            expiration: i64 => "Expiration",
+            unsealed_cid: Option<Cid> => "UnsealedCid",
+        } for [v9, v10, v11, v12, v13]::SectorPreCommitInfo
+        ProveCommitSectorParamsLotusJson {
+            sector_number: u64 => "SectorNumber",
+            proof: Vec<u8> => "Proof",
+        } for [v8, v9, v10, v11, v12, v13]::ProveCommitSectorParams
+        ProveCommitAggregateParamsLotusJson {
+            sector_numbers: BitField => "SectorNumbers",
+            aggregate_proof: Vec<u8> => "AggregateProof",
+        } for [v8, v9, v10, v11, v12, v13]::ProveCommitAggregateParams
+    }
+
+    macro_rules! commd_fields {
+        ($($version:ident),+) => {$(
+            impl ParamsField<Option<Cid>> for $version::CompactCommD {
+                fn to_json(self) -> anyhow::Result<Option<Cid>> {
+                    Ok(self.0)
+                }
+
+                fn from_json(json: Option<Cid>) -> anyhow::Result<Self> {
+                    Ok(Self(json))
+                }
+            }
+        )+};
+    }
+
+    commd_fields!(v9, v10, v11, v12, v13);
+}
+
+mod multisig {
+    use super::ParamsField;
+    use crate::shim::{address::Address, econ::TokenAmount};
+    use fil_actor_multisig_state::{v10, v11, v12, v13, v8, v9};
+    use fvm_ipld_encoding::RawBytes;
+
+    params! {
+        ConstructorParamsLotusJson {
+            signers: Vec<Address> => "Signers",
+            num_approvals_threshold: u64 => "NumApprovalsThreshold",
+            unlock_duration: i64 => "UnlockDuration",
+            start_epoch: i64 => "StartEpoch",
+        } for [v8, v9, v10, v11, v12, v13]::ConstructorParams
+        ProposeParamsLotusJson {
+            to: Address => "To",
+            value: TokenAmount => "Value",
+            method: u64 => "Method",
+            params: RawBytes => "Params",
+        } for [v8, v9, v10, v11, v12, v13]::ProposeParams
+        TxnIDParamsLotusJson {
+            id: i64 => "ID",
+            proposal_hash: Vec<u8> => "ProposalHash",
+        } for [v8, v9, v10, v11, v12, v13]::TxnIDParams
+        AddSignerParamsLotusJson {
+            signer: Address => "Signer",
+            increase: bool => "Increase",
+        } for [v8, v9, v10, v11, v12, v13]::AddSignerParams
+        RemoveSignerParamsLotusJson {
+            signer: Address => "Signer",
+            decrease: bool => "Decrease",
+        } for [v8, v9, v10, v11, v12, v13]::RemoveSignerParams
+        SwapSignerParamsLotusJson {
+            from: Address => "From",
+            to: Address => "To",
+        } for [v8, v9, v10, v11, v12, v13]::SwapSignerParams
+        ChangeNumApprovalsThresholdParamsLotusJson {
+            new_threshold: u64 => "NewThreshold",
+        } for [v8, v9, v10, v11, v12, v13]::ChangeNumApprovalsThresholdParams
+        LockBalanceParamsLotusJson {
+            start_epoch: i64 => "StartEpoch",
+            unlock_duration: i64 => "UnlockDuration",
+            amount: TokenAmount => "Amount",
+        } for [v8, v9, v10, v11, v12, v13]::LockBalanceParams
+    }
+
+    macro_rules! txn_id_fields {
+        ($($version:ident),+) => {$(
+            impl ParamsField<i64> for $version::TxnID {
+                fn to_json(self) -> anyhow::Result<i64> {
+                    Ok(self.0)
+                }
+
+                fn from_json(json: i64) -> anyhow::Result<Self> {
+                    Ok(Self(json))
+                }
+            }
+        )+};
+    }
+
+    txn_id_fields!(v8, v9, v10, v11, v12, v13);
+}
+
+mod power {
+    use crate::shim::{address::Address, sector::RegisteredPoStProof};
+    use fil_actor_power_state::{v10, v11, v12, v13, v8, v9};
+
+    params! {
+        CreateMinerParamsLotusJson {
+            owner: Address => "Owner",
+            worker: Address => "Worker",
+            window_post_proof_type: RegisteredPoStProof => "WindowPoStProofType",
+            peer: Vec<u8> => "Peer",
+            multiaddrs: Vec<Vec<u8>> => "Multiaddrs",
+        } for [v8, v9, v10, v11, v12, v13]::CreateMinerParams
+    }
+}
+
+mod verifreg {
+    use crate::shim::address::Address;
+    use fil_actor_verifreg_state::{v10, v11, v12, v13, v8, v9};
+    use num::BigInt;
+
+    params! {
+        VerifierParamsLotusJson {
+            address: Address => "Address",
+            allowance: BigInt => "Allowance",
+        } for [v8, v9, v10, v11, v12, v13]::VerifierParams
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fil_actor_interface::KNOWN_CIDS;
+    use serde_json::json;
+
+    #[test]
+    fn decode_params_across_versions() {
+        let worker = Address::new_id(1000);
+        let control = Address::new_id(1001);
+        let expected = json!({
+            "NewWorker": "f01000",
+            "NewControlAddrs": ["f01001"],
+        });
+
+        let v8_code = KNOWN_CIDS.actor.miner.v8.mainnet;
+        let v8_params =
+            fvm_ipld_encoding::to_vec(&fil_actor_miner_state::v8::ChangeWorkerAddressParams {
+                new_worker: worker.into(),
+                new_control_addresses: vec![control.into()],
+            })
+            .unwrap();
+        let method = fil_actor_miner_state::v8::Method::ChangeWorkerAddress as MethodNum;
+        assert_eq!(
+            decode_params(&v8_code, method, &v8_params).unwrap(),
+            expected
+        );
+
+        let v13_code = KNOWN_CIDS.actor.miner.v13.calibnet;
+        let v13_params =
+            fvm_ipld_encoding::to_vec(&fil_actor_miner_state::v13::ChangeWorkerAddressParams {
+                new_worker: worker.into(),
+                new_control_addresses: vec![control.into()],
+            })
+            .unwrap();
+        let exported = fil_actor_miner_state::v13::Method::ChangeWorkerAddressExported as MethodNum;
+        assert_eq!(
+            decode_params(&v13_code, exported, &v13_params).unwrap(),
+            expected
+        );
+        // Exported methods only exist from v10 onwards.
+        decode_params(&v8_code, exported, &v8_params).unwrap_err();
+    }
+
+    #[test]
+    fn encode_params_matches_actor_encoding() {
+        let code = KNOWN_CIDS.actor.init.v10.mainnet;
+        let method = fil_actor_init_state::v13::Method::Exec4 as MethodNum;
+        let code_cid = KNOWN_CIDS.actor.evm.v10.mainnet;
+        let params = fil_actor_init_state::v10::Exec4Params {
+            code_cid,
+            constructor_params: RawBytes::new(vec![1, 2, 3]),
+            subaddress: RawBytes::new(vec![4, 5]),
+        };
+        let expected = fvm_ipld_encoding::to_vec(&params).unwrap();
+
+        let json = json!({
+            "CodeCID": { "/": code_cid.to_string() },
+            "ConstructorParams": "AQID",
+            "SubAddress": "BAU=",
+        });
+        assert_eq!(
+            encode_params(&code, method, json.clone()).unwrap(),
+            expected
+        );
+        assert_eq!(decode_params(&code, method, &expected).unwrap(), json);
+    }
+
+    #[test]
+    fn big_int_params_roundtrip() {
+        let code = KNOWN_CIDS.actor.verifreg.v13.mainnet;
+        let method = fil_actor_verifreg_state::v13::Method::AddVerifiedClient as MethodNum;
+        let params = fvm_ipld_encoding::to_vec(&fil_actor_verifreg_state::v13::VerifierParams {
+            address: Address::new_id(1234).into(),
+            allowance: 1_000_000.into(),
+        })
+        .unwrap();
+
+        let json = decode_params(&code, method, &params).unwrap();
+        assert_eq!(json, json!({ "Address": "f01234", "Allowance": "1000000" }));
+        assert_eq!(encode_params(&code, method, json).unwrap(), params);
+    }
+
+    #[test]
+    fn windowed_post_params_across_versions() {
+        let method = fil_actor_miner_state::v8::Method::SubmitWindowedPoSt as MethodNum;
+        let skipped = BitField::try_from_bits([1, 3]).unwrap();
+        let expected = json!({
+            "Deadline": 2,
+            "Partitions": [{ "Index": 0, "Skipped": [1, 1, 1, 1] }],
+            "Proofs": [{ "PoStProof": 0, "ProofBytes": "AQI=" }],
+            "ChainCommitEpoch": 100,
+            "ChainCommitRand": "AwQ=",
+        });
+
+        let v8_code = KNOWN_CIDS.actor.miner.v8.mainnet;
+        let v8_params =
+            fvm_ipld_encoding::to_vec(&fil_actor_miner_state::v8::SubmitWindowedPoStParams {
+                deadline: 2,
+                partitions: vec![fil_actor_miner_state::v8::PoStPartition {
+                    index: 0,
+                    skipped: skipped.clone().into(),
+                }],
+                proofs: vec![fvm_shared2::sector::PoStProof {
+                    post_proof: fvm_shared2::sector::RegisteredPoStProof::StackedDRGWinning2KiBV1,
+                    proof_bytes: vec![1, 2],
+                }],
+                chain_commit_epoch: 100,
+                chain_commit_rand: fvm_shared2::randomness::Randomness(vec![3, 4]),
+            })
+            .unwrap();
+        assert_eq!(
+            decode_params(&v8_code, method, &v8_params).unwrap(),
+            expected
+        );
+
+        let v13_code = KNOWN_CIDS.actor.miner.v13.mainnet;
+        let v13_params =
+            fvm_ipld_encoding::to_vec(&fil_actor_miner_state::v13::SubmitWindowedPoStParams {
+                deadline: 2,
+                partitions: vec![fil_actor_miner_state::v13::PoStPartition { index: 0, skipped }],
+                proofs: vec![fvm_shared4::sector::PoStProof {
+                    post_proof: fvm_shared4::sector::RegisteredPoStProof::StackedDRGWinning2KiBV1,
+                    proof_bytes: vec![1, 2],
+                }],
+                chain_commit_epoch: 100,
+                chain_commit_rand: fvm_shared4::randomness::Randomness(vec![3, 4]),
+            })
+            .unwrap();
+        assert_eq!(
+            decode_params(&v13_code, method, &v13_params).unwrap(),
+            expected
+        );
+        assert_eq!(
+            encode_params(&v13_code, method, expected).unwrap(),
+            v13_params
+        );
+    }
+
+    #[test]
+    fn publish_storage_deals_params_roundtrip() {
+        use fil_actor_market_state::v13::{
+            ClientDealProposal, DealProposal, Label, PublishStorageDealsParams,
+        };
+
+        let code = KNOWN_CIDS.actor.market.v13.mainnet;
+        let method = fil_actor_market_state::v13::Method::PublishStorageDeals as MethodNum;
+        let piece_cid = KNOWN_CIDS.actor.market.v13.mainnet;
+        let params = fvm_ipld_encoding::to_vec(&PublishStorageDealsParams {
+            deals: vec![ClientDealProposal {
+                proposal: DealProposal {
+                    piece_cid,
+                    piece_size: fvm_shared4::piece::PaddedPieceSize(2048),
+                    verified_deal: true,
+                    client: Address::new_id(1000).into(),
+                    provider: Address::new_id(1001).into(),
+                    label: Label::String("deal".into()),
+                    start_epoch: 10,
+                    end_epoch: 20,
+                    storage_price_per_epoch: fvm_shared4::econ::TokenAmount::from_atto(1),
+                    provider_collateral: fvm_shared4::econ::TokenAmount::from_atto(2),
+                    client_collateral: fvm_shared4::econ::TokenAmount::from_atto(3),
+                },
+                client_signature: fvm_shared4::crypto::signature::Signature::new_bls(vec![5, 6]),
+            }],
+        })
+        .unwrap();
+
+        let json = decode_params(&code, method, &params).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "Deals": [{
+                    "Proposal": {
+                        "PieceCID": { "/": piece_cid.to_string() },
+                        "PieceSize": 2048,
+                        "VerifiedDeal": true,
+                        "Client": "f01000",
+                        "Provider": "f01001",
+                        "Label": "deal",
+                        "StartEpoch": 10,
+                        "EndEpoch": 20,
+                        "StoragePricePerEpoch": "1",
+                        "ProviderCollateral": "2",
+                        "ClientCollateral": "3",
+                    },
+                    "ClientSignature": { "Type": 2, "Data": "BQY=" },
+                }],
+            })
+        );
+        assert_eq!(encode_params(&code, method, json).unwrap(), params);
+    }
+
+    #[test]
+    fn unsupported_actor() {
+        let code = KNOWN_CIDS.actor.account.v13.mainnet;
+        decode_params(&code, 2, &[]).unwrap_err();
+    }
+}
@@ -1,8 +1,11 @@
 // Copyright 2019-2024 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use crate::blocks::TipsetKey;
 use crate::lotus_json::{lotus_json_with_self, LotusJson};
 use crate::message::Message as _;
+use crate::rpc::types::{ApiDealState, ApiMarketDeal};
+use crate::shim::deal::DealID;
 use crate::shim::executor::ApplyRet;
 use crate::shim::{
     address::Address,
@@ -13,6 +16,7 @@ use crate::shim::{
     message::Message,
     state_tree::{ActorID, ActorState},
 };
+use ahash::HashMap;
 use cid::Cid;
 use fvm_ipld_encoding::RawBytes;
 use schemars::JsonSchema;
@@ -192,9 +196,36 @@ pub struct InvocResult {
     #[schemars(with = "LotusJson<Option<Receipt>>")]
     pub msg_rct: Option<Receipt>,
     pub error: Option<String>,
+    pub execution_trace: Option<ExecutionTrace>,
 }
 lotus_json_with_self!(InvocResult);
 
+#[derive(PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct ComputeStateOutput {
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<Cid>")]
+    pub root: Cid,
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<Vec<ApiInvocResult>>")]
+    pub trace: Vec<ApiInvocResult>,
+}
+lotus_json_with_self!(ComputeStateOutput);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct ChangedActor {
+    /// `None` if the actor was added.
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<Option<ActorState>>")]
+    pub old: Option<ActorState>,
+    /// `None` if the actor was removed.
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<Option<ActorState>>")]
+    pub new: Option<ActorState>,
+}
+lotus_json_with_self!(ChangedActor);
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 pub struct SectorExpiration {
@@ -210,3 +241,115 @@ pub struct SectorLocation {
     pub partition: u64,
 }
 lotus_json_with_self!(SectorLocation);
+
+/// Lifecycle stage of a storage deal, as recorded by the market actor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum MarketDealStatus {
+    /// Published, but not yet included in a proven sector.
+    Published,
+    /// Included in a proven sector.
+    Active,
+    /// Terminated early by the provider.
+    Slashed,
+}
+
+impl MarketDealStatus {
+    pub fn of(state: &ApiDealState) -> Self {
+        if state.slash_epoch != -1 {
+            Self::Slashed
+        } else if state.sector_start_epoch == -1 {
+            Self::Published
+        } else {
+            Self::Active
+        }
+    }
+}
+
+/// Criteria a market deal must satisfy to be returned. Unset fields match any deal.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct MarketDealFilter {
+    #[serde(
+        with = "crate::lotus_json",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(with = "LotusJson<Option<Address>>")]
+    pub provider: Option<Address>,
+    #[serde(
+        with = "crate::lotus_json",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(with = "LotusJson<Option<Address>>")]
+    pub client: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<MarketDealStatus>,
+}
+lotus_json_with_self!(MarketDealFilter);
+
+impl MarketDealFilter {
+    /// Addresses must be ID addresses, as those are what the market actor stores.
+    pub fn matches(&self, deal: &ApiMarketDeal) -> bool {
+        self.provider
+            .map_or(true, |provider| provider == deal.proposal.provider)
+            && self
+                .client
+                .map_or(true, |client| client == deal.proposal.client)
+            && self
+                .status
+                .map_or(true, |status| status == MarketDealStatus::of(&deal.state))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct MarketDealsPage {
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<HashMap<String, ApiMarketDeal>>")]
+    pub deals: HashMap<String, ApiMarketDeal>,
+    /// Cursor of the next page, or `None` if this is the last page.
+    pub next: Option<DealID>,
+    /// Key of the tipset the page was read at, to pass along with the cursor.
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<TipsetKey>")]
+    pub tipset_key: TipsetKey,
+}
+lotus_json_with_self!(MarketDealsPage);
+
+/// Criteria an actor must satisfy to be returned. Unset fields match any actor.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActorFilter {
+    #[serde(
+        with = "crate::lotus_json",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(with = "LotusJson<Option<Cid>>")]
+    pub code: Option<Cid>,
+}
+lotus_json_with_self!(ActorFilter);
+
+impl ActorFilter {
+    pub fn matches(&self, actor: &ActorState) -> bool {
+        self.code.map_or(true, |code| code == actor.code)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActorsPage {
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<Vec<Address>>")]
+    pub actors: Vec<Address>,
+    /// Cursor of the next page, or `None` if this is the last page.
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<Option<Address>>")]
+    pub next: Option<Address>,
+    /// Key of the tipset the page was read at, to pass along with the cursor.
+    #[serde(with = "crate::lotus_json")]
+    #[schemars(with = "LotusJson<TipsetKey>")]
+    pub tipset_key: TipsetKey,
+}
+lotus_json_with_self!(ActorsPage);
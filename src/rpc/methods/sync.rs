@@ -178,6 +178,7 @@ mod tests {
 
         let cs_arc = Arc::new(
             ChainStore::new(
+                db.clone(),
                 db.clone(),
                 db.clone(),
                 db,
@@ -235,6 +236,8 @@ mod tests {
             beacon,
             shutdown: mpsc::channel(1).0, // dummy for tests
             tipset_send,
+            eth_filters: Default::default(),
+            gc: None,
         });
         (state, network_rx)
     }
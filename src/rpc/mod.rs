@@ -37,15 +37,20 @@ macro_rules! for_each_method {
 
         // beacon vertical
         $callback!(crate::rpc::beacon::BeaconGetEntry);
+        $callback!(crate::rpc::beacon::BeaconGetEntries);
 
         // chain vertical
         $callback!(crate::rpc::chain::ChainGetMessage);
         $callback!(crate::rpc::chain::ChainGetParentMessages);
         $callback!(crate::rpc::chain::ChainGetParentReceipts);
+        $callback!(crate::rpc::chain::ChainGetEvents);
         $callback!(crate::rpc::chain::ChainGetMessagesInTipset);
         $callback!(crate::rpc::chain::ChainExport);
         $callback!(crate::rpc::chain::ChainReadObj);
         $callback!(crate::rpc::chain::ChainHasObj);
+        $callback!(crate::rpc::chain::ChainStatObj);
+        $callback!(crate::rpc::chain::ChainPutObj);
+        $callback!(crate::rpc::chain::ChainGetNode);
         $callback!(crate::rpc::chain::ChainGetBlockMessages);
         $callback!(crate::rpc::chain::ChainGetPath);
         $callback!(crate::rpc::chain::ChainGetTipSetByHeight);
@@ -55,6 +60,9 @@ macro_rules! for_each_method {
         $callback!(crate::rpc::chain::ChainGetBlock);
         $callback!(crate::rpc::chain::ChainGetTipSet);
         $callback!(crate::rpc::chain::ChainSetHead);
+        $callback!(crate::rpc::chain::ChainPrune);
+        $callback!(crate::rpc::chain::ChainPruneStatus);
+        $callback!(crate::rpc::chain::ChainPruneCancel);
         $callback!(crate::rpc::chain::ChainGetMinBaseFee);
         $callback!(crate::rpc::chain::ChainTipSetWeight);
 
@@ -69,15 +77,36 @@ macro_rules! for_each_method {
         $callback!(crate::rpc::eth::EthSyncing);
         $callback!(crate::rpc::eth::EthAccounts);
         $callback!(crate::rpc::eth::EthBlockNumber);
+        $callback!(crate::rpc::eth::EthCall);
         $callback!(crate::rpc::eth::EthChainId);
+        $callback!(crate::rpc::eth::EthEstimateGas);
         $callback!(crate::rpc::eth::EthGetCode);
         $callback!(crate::rpc::eth::EthGetStorageAt);
         $callback!(crate::rpc::eth::EthGasPrice);
+        $callback!(crate::rpc::eth::EthFeeHistory);
+        $callback!(crate::rpc::eth::EthMaxPriorityFeePerGas);
         $callback!(crate::rpc::eth::EthGetBalance);
         $callback!(crate::rpc::eth::EthGetBlockByHash);
         $callback!(crate::rpc::eth::EthGetBlockByNumber);
         $callback!(crate::rpc::eth::EthGetBlockTransactionCountByHash);
         $callback!(crate::rpc::eth::EthGetBlockTransactionCountByNumber);
+        $callback!(crate::rpc::eth::EthGetTransactionByHash);
+        $callback!(crate::rpc::eth::EthGetTransactionCount);
+        $callback!(crate::rpc::eth::EthGetMessageCidByTransactionHash);
+        $callback!(crate::rpc::eth::EthGetTransactionHashByCid);
+        $callback!(crate::rpc::eth::EthGetTransactionByBlockHashAndIndex);
+        $callback!(crate::rpc::eth::EthGetTransactionByBlockNumberAndIndex);
+        $callback!(crate::rpc::eth::EthGetTransactionReceipt);
+        $callback!(crate::rpc::eth::EthGetLogs);
+        $callback!(crate::rpc::eth::EthNewFilter);
+        $callback!(crate::rpc::eth::EthNewBlockFilter);
+        $callback!(crate::rpc::eth::EthNewPendingTransactionFilter);
+        $callback!(crate::rpc::eth::EthGetFilterChanges);
+        $callback!(crate::rpc::eth::EthGetFilterLogs);
+        $callback!(crate::rpc::eth::EthUninstallFilter);
+        $callback!(crate::rpc::eth::EthSendRawTransaction);
+        $callback!(crate::rpc::eth::EthTraceBlock);
+        $callback!(crate::rpc::eth::EthTraceReplayBlockTransactions);
 
         // gas vertical
         $callback!(crate::rpc::gas::GasEstimateGasLimit);
@@ -119,6 +148,9 @@ macro_rules! for_each_method {
 
         // state vertical
         $callback!(crate::rpc::state::StateCall);
+        $callback!(crate::rpc::state::StateCompute);
+        $callback!(crate::rpc::state::StateDecodeParams);
+        $callback!(crate::rpc::state::StateEncodeParams);
         $callback!(crate::rpc::state::StateGetBeaconEntry);
         $callback!(crate::rpc::state::StateListMessages);
         $callback!(crate::rpc::state::StateGetNetworkParams);
@@ -126,15 +158,21 @@ macro_rules! for_each_method {
         $callback!(crate::rpc::state::StateReplay);
         $callback!(crate::rpc::state::StateSectorGetInfo);
         $callback!(crate::rpc::state::StateSectorPreCommitInfo);
+        $callback!(crate::rpc::state::StateSectorPreCommitInfoBatch);
         $callback!(crate::rpc::state::StateAccountKey);
         $callback!(crate::rpc::state::StateLookupID);
+        $callback!(crate::rpc::state::StateLookupRobustAddress);
         $callback!(crate::rpc::state::StateGetActor);
+        $callback!(crate::rpc::state::StateChangedActors);
+        $callback!(crate::rpc::state::StateActorChanges);
         $callback!(crate::rpc::state::StateMinerInfo);
         $callback!(crate::rpc::state::StateMinerActiveSectors);
         $callback!(crate::rpc::state::StateMinerPartitions);
         $callback!(crate::rpc::state::StateMinerSectors);
         $callback!(crate::rpc::state::StateMinerSectorCount);
         $callback!(crate::rpc::state::StateMinerSectorAllocated);
+        $callback!(crate::rpc::state::StateMinerSectorsAllocated);
+        $callback!(crate::rpc::state::StateMinerAllocated);
         $callback!(crate::rpc::state::StateMinerPower);
         $callback!(crate::rpc::state::StateMinerDeadlines);
         $callback!(crate::rpc::state::StateMinerProvingDeadline);
@@ -145,16 +183,22 @@ macro_rules! for_each_method {
         $callback!(crate::rpc::state::StateGetReceipt);
         $callback!(crate::rpc::state::StateGetRandomnessFromTickets);
         $callback!(crate::rpc::state::StateGetRandomnessFromBeacon);
+        $callback!(crate::rpc::state::StateGetRandomnessDigestFromTickets);
+        $callback!(crate::rpc::state::StateGetRandomnessDigestFromBeacon);
         $callback!(crate::rpc::state::StateReadState);
         $callback!(crate::rpc::state::StateCirculatingSupply);
         $callback!(crate::rpc::state::StateVerifiedClientStatus);
         $callback!(crate::rpc::state::StateVMCirculatingSupplyInternal);
         $callback!(crate::rpc::state::StateListMiners);
         $callback!(crate::rpc::state::StateListActors);
+        $callback!(crate::rpc::state::StateListActorsPaginated);
         $callback!(crate::rpc::state::StateNetworkVersion);
+        $callback!(crate::rpc::state::StateActorCodeCIDs);
+        $callback!(crate::rpc::state::StateActorManifestCID);
         $callback!(crate::rpc::state::StateMarketBalance);
         $callback!(crate::rpc::state::StateMarketParticipants);
         $callback!(crate::rpc::state::StateMarketDeals);
+        $callback!(crate::rpc::state::StateMarketDealsPaginated);
         $callback!(crate::rpc::state::StateDealProviderCollateralBounds);
         $callback!(crate::rpc::state::StateMarketStorageDeal);
         $callback!(crate::rpc::state::StateWaitMsg);
@@ -321,6 +365,9 @@ pub struct RPCState<DB> {
     pub start_time: chrono::DateTime<chrono::Utc>,
     pub beacon: Arc<crate::beacon::BeaconSchedule>,
     pub shutdown: mpsc::Sender<()>,
+    pub eth_filters: Arc<crate::rpc::eth::filter::EthFilterManager>,
+    /// Handle to the database garbage collector, if it is enabled.
+    pub gc: Option<crate::db::GcHandle>,
 }
 
 impl<DB: Blockstore> RPCState<DB> {
@@ -352,6 +399,30 @@ where
         let state_clone = state.clone();
         move |params| chain::chain_notify(params, &state_clone)
     })?;
+    pubsub_module.register_stream(chain::CHAIN_NOTIFY_FROM, {
+        let state_clone = state.clone();
+        move |params| chain::chain_notify_from(params, &state_clone)
+    })?;
+    pubsub_module.register_stream(state::STATE_MARKET_DEALS_STREAM, {
+        let state_clone = state.clone();
+        move |params| state::state_market_deals_stream(params, &state_clone)
+    })?;
+    pubsub_module.register_stream(state::STATE_LIST_ACTORS_STREAM, {
+        let state_clone = state.clone();
+        move |params| state::state_list_actors_stream(params, &state_clone)
+    })?;
+    for name in [eth::pubsub::ETH_SUBSCRIBE, eth::pubsub::ETH_SUBSCRIBE_ALIAS] {
+        pubsub_module.register_eth_subscription(name, {
+            let state_clone = state.clone();
+            move |params| eth::pubsub::eth_subscribe(params, &state_clone)
+        })?;
+    }
+    for name in [
+        eth::pubsub::ETH_UNSUBSCRIBE,
+        eth::pubsub::ETH_UNSUBSCRIBE_ALIAS,
+    ] {
+        pubsub_module.register_eth_unsubscription(name)?;
+    }
     module.merge(pubsub_module)?;
 
     let (stop_handle, _server_handle) = stop_channel();
@@ -477,10 +548,13 @@ pub fn openrpc() -> openrpc_types::OpenRPC {
     let mut gen = SchemaGenerator::new(settings);
     macro_rules! callback {
         ($ty:ty) => {
-            methods.push(openrpc_types::ReferenceOr::Item(<$ty>::openrpc(
-                &mut gen,
-                ParamStructure::ByPosition,
-            )));
+            let method = <$ty>::openrpc(&mut gen, ParamStructure::ByPosition);
+            let alias = <$ty>::NAME_ALIAS.map(|alias| openrpc_types::Method {
+                name: String::from(alias),
+                ..method.clone()
+            });
+            methods.push(openrpc_types::ReferenceOr::Item(method));
+            methods.extend(alias.map(openrpc_types::ReferenceOr::Item));
         };
     }
     for_each_method!(callback);
@@ -524,4 +598,28 @@ mod tests {
         #[cfg(never)]
         insta::assert_yaml_snapshot!(_spec);
     }
+
+    #[test]
+    fn openrpc_lists_aliases() {
+        let names = super::openrpc()
+            .methods
+            .into_iter()
+            .filter_map(|it| match it {
+                openrpc_types::ReferenceOr::Item(method) => Some(method.name),
+                openrpc_types::ReferenceOr::Reference { .. } => None,
+            })
+            .collect::<Vec<_>>();
+        for name in [
+            "Filecoin.EthChainId",
+            "eth_chainId",
+            "eth_getMessageCidByTransactionHash",
+            "eth_getTransactionHashByCid",
+            "net_version",
+            "web3_clientVersion",
+        ] {
+            assert!(names.iter().any(|it| it == name), "{name} is not listed");
+        }
+        let unique = names.iter().collect::<ahash::HashSet<_>>();
+        assert_eq!(unique.len(), names.len(), "method names must be unique");
+    }
 }
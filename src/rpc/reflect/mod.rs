@@ -69,6 +69,9 @@ pub type Ctx<T> = Arc<crate::rpc::RPCState<T>>;
 pub trait RpcMethod<const ARITY: usize> {
     /// Method name.
     const NAME: &'static str;
+    /// Alternative name the method is also served under, e.g. the `eth_*` name used by
+    /// Ethereum tooling.
+    const NAME_ALIAS: Option<&'static str> = None;
     /// Name of each argument, MUST be unique.
     const PARAM_NAMES: [&'static str; ARITY];
     /// See [`ApiVersion`].
@@ -189,11 +192,11 @@ pub trait RpcMethodExt<const ARITY: usize>: RpcMethod<ARITY> {
             ..Default::default()
         }
     }
-    /// Register this method with an [`RpcModule`].
+    /// Register this method, and its alias if any, with an [`RpcModule`].
     fn register(
         module: &mut RpcModule<crate::rpc::RPCState<impl Blockstore + Send + Sync + 'static>>,
         calling_convention: ParamStructure,
-    ) -> Result<&mut jsonrpsee::MethodCallback, jsonrpsee::core::RegisterMethodError>
+    ) -> Result<(), jsonrpsee::core::RegisterMethodError>
     where
         <Self::Ok as HasLotusJson>::LotusJson: Clone + 'static,
     {
@@ -206,7 +209,11 @@ pub trait RpcMethodExt<const ARITY: usize>: RpcMethod<ARITY> {
             let params = Self::Params::parse(raw, Self::PARAM_NAMES, calling_convention)?;
             let ok = Self::handle(ctx, params).await?;
             Result::<_, jsonrpsee::types::ErrorObjectOwned>::Ok(ok.into_lotus_json())
-        })
+        })?;
+        if let Some(alias) = Self::NAME_ALIAS {
+            module.register_alias(alias, Self::NAME)?;
+        }
+        Ok(())
     }
     /// Returns [`Err`] if any of the parameters fail to serialize.
     fn request(params: Self::Params) -> Result<crate::rpc::Request<Self::Ok>, serde_json::Error> {
@@ -0,0 +1,18 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+mod state;
+
+use crate::shim::{address::Address, state_tree::ActorID};
+use fil_actor_interface::init::State;
+use fvm_ipld_blockstore::Blockstore;
+
+pub trait InitStateExt {
+    /// Calls `f` for each robust address in the init actor's address map, along with the ID
+    /// it is mapped to.
+    fn for_each_address<BS: Blockstore>(
+        &self,
+        store: &BS,
+        f: impl FnMut(Address, ActorID) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()>;
+}
@@ -0,0 +1,33 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use super::*;
+
+macro_rules! for_each_address {
+    ($version:ident, $state:expr, $store:expr, $f:expr) => {{
+        let map = fil_actors_shared::$version::make_map_with_root::<_, ActorID>(
+            &$state.address_map,
+            $store,
+        )?;
+        map.for_each(|k, v| $f(Address::from_bytes(k)?, *v))?;
+        Ok(())
+    }};
+}
+
+impl InitStateExt for State {
+    fn for_each_address<BS: Blockstore>(
+        &self,
+        store: &BS,
+        mut f: impl FnMut(Address, ActorID) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        match self {
+            State::V0(_) => anyhow::bail!("address map of init actor v0 is not supported"),
+            State::V8(st) => for_each_address!(v8, st, store, f),
+            State::V9(st) => for_each_address!(v9, st, store, f),
+            State::V10(st) => for_each_address!(v10, st, store, f),
+            State::V11(st) => for_each_address!(v11, st, store, f),
+            State::V12(st) => for_each_address!(v12, st, store, f),
+            State::V13(st) => for_each_address!(v13, st, store, f),
+        }
+    }
+}
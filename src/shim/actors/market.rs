@@ -2,12 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 mod balance_table;
+mod deal_proposals;
 
 use crate::shim::address::Address;
+use crate::shim::deal::DealID;
 use crate::shim::econ::TokenAmount;
+use fil_actor_interface::market::DealProposal;
 
 pub trait BalanceTableExt {
     fn for_each<F>(&self, f: F) -> anyhow::Result<()>
     where
         F: FnMut(&Address, &TokenAmount) -> anyhow::Result<()>;
 }
+
+pub trait DealProposalsExt {
+    /// Iterates over the deal proposals in ascending order of deal ID, starting at the first deal
+    /// whose ID is at least `start_at`, for as long as `f` returns `true`.
+    fn for_each_while_from<F>(&self, start_at: DealID, f: F) -> anyhow::Result<()>
+    where
+        F: FnMut(DealID, DealProposal) -> anyhow::Result<bool>;
+}
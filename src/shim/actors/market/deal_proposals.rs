@@ -0,0 +1,32 @@
+// Copyright 2019-2024 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use super::*;
+use fil_actor_interface::market::DealProposals;
+use fvm_ipld_blockstore::Blockstore;
+
+impl<BS: Blockstore> DealProposalsExt for DealProposals<'_, BS> {
+    fn for_each_while_from<F>(&self, start_at: DealID, mut f: F) -> anyhow::Result<()>
+    where
+        F: FnMut(DealID, DealProposal) -> anyhow::Result<bool>,
+    {
+        match self {
+            Self::V9(a) => a.for_each_while_ranged(Some(start_at), None, |id, proposal| {
+                f(id, DealProposal::try_from(proposal)?)
+            })?,
+            Self::V10(a) => a.for_each_while_ranged(Some(start_at), None, |id, proposal| {
+                f(id, DealProposal::try_from(proposal)?)
+            })?,
+            Self::V11(a) => a.for_each_while_ranged(Some(start_at), None, |id, proposal| {
+                f(id, DealProposal::try_from(proposal)?)
+            })?,
+            Self::V12(a) => a.for_each_while_ranged(Some(start_at), None, |id, proposal| {
+                f(id, DealProposal::try_from(proposal)?)
+            })?,
+            Self::V13(a) => a.for_each_while_ranged(Some(start_at), None, |id, proposal| {
+                f(id, DealProposal::try_from(proposal)?)
+            })?,
+        };
+        Ok(())
+    }
+}
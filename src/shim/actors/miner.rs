@@ -9,7 +9,7 @@ use fil_actor_interface::miner::State;
 use fil_actors_shared::fvm_ipld_bitfield::BitField;
 use fvm_ipld_blockstore::Blockstore;
 
-use crate::rpc::types::SectorOnChainInfo;
+use crate::rpc::types::{SectorOnChainInfo, SectorPreCommitOnChainInfo};
 use crate::utils::db::CborStoreExt as _;
 
 pub trait MinerStateExt {
@@ -24,6 +24,15 @@ pub trait MinerStateExt {
     /// Loads the allocated sector numbers
     fn load_allocated_sector_numbers<BS: Blockstore>(&self, store: &BS)
         -> anyhow::Result<BitField>;
+
+    /// Loads pre-committed sectors in a single pass over the pre-commit HAMT,
+    /// keeping those in the bitfield. If no bitfield is passed in, return all.
+    /// The result is ordered by sector number.
+    fn load_precommitted_sectors<BS: Blockstore>(
+        &self,
+        store: &BS,
+        sectors: Option<&BitField>,
+    ) -> anyhow::Result<Vec<SectorPreCommitOnChainInfo>>;
 }
 
 pub trait PartitionExt {
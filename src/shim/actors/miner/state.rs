@@ -129,4 +129,97 @@ impl MinerStateExt for State {
         };
         store.get_cbor_required(&allocated_sectors)
     }
+
+    fn load_precommitted_sectors<BS: Blockstore>(
+        &self,
+        store: &BS,
+        sectors: Option<&BitField>,
+    ) -> anyhow::Result<Vec<SectorPreCommitOnChainInfo>> {
+        macro_rules! load_precommitted_sectors {
+            ($st:expr, $version:ident) => {{
+                let precommitted = fil_actors_shared::$version::make_map_with_root::<
+                    _,
+                    fil_actor_miner_state::$version::SectorPreCommitOnChainInfo,
+                >(&$st.pre_committed_sectors, store)?;
+                let mut infos = vec![];
+                precommitted.for_each(|_, info| {
+                    if sectors.map_or(true, |sectors| sectors.get(info.info.sector_number)) {
+                        infos.push(SectorPreCommitOnChainInfo::from(info.clone()));
+                    }
+                    Ok(())
+                })?;
+                infos
+            }};
+        }
+
+        let mut infos = match self {
+            Self::V8(st) => load_precommitted_sectors!(st, v8),
+            Self::V9(st) => load_precommitted_sectors!(st, v9),
+            Self::V10(st) => load_precommitted_sectors!(st, v10),
+            Self::V11(st) => load_precommitted_sectors!(st, v11),
+            Self::V12(st) => load_precommitted_sectors!(st, v12),
+            Self::V13(st) => load_precommitted_sectors!(st, v13),
+        };
+        infos.sort_by_key(|info| info.info.sector_number);
+        Ok(infos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryDB;
+    use cid::multihash::{Code::Identity, MultihashDigest as _};
+    use fil_actor_miner_state::v13::{
+        CompactCommD, SectorPreCommitInfo, SectorPreCommitOnChainInfo,
+    };
+    use fil_actors_shared::v13::runtime::Policy;
+    use fvm_shared4::econ::TokenAmount;
+    use fvm_shared4::sector::RegisteredSealProof;
+
+    #[test]
+    fn load_precommitted_sectors_filters_and_orders() {
+        let store = MemoryDB::default();
+        let info_cid = Cid::new_v1(fvm_ipld_encoding::DAG_CBOR, Identity.digest(&[]));
+        let mut st =
+            fil_actor_miner_state::v13::State::new(&Policy::default(), &store, info_cid, 0, 0)
+                .unwrap();
+        let precommit = |sector_number| SectorPreCommitOnChainInfo {
+            info: SectorPreCommitInfo {
+                seal_proof: RegisteredSealProof::StackedDRG32GiBV1P1,
+                sector_number,
+                sealed_cid: info_cid,
+                seal_rand_epoch: 0,
+                deal_ids: vec![],
+                expiration: 0,
+                unsealed_cid: CompactCommD::default(),
+            },
+            pre_commit_deposit: TokenAmount::default(),
+            pre_commit_epoch: 0,
+        };
+        st.put_precommitted_sectors(&store, [7, 3, 12, 5].map(precommit).to_vec())
+            .unwrap();
+        let st = State::V13(st);
+
+        let sector_numbers = |infos: Vec<crate::rpc::types::SectorPreCommitOnChainInfo>| {
+            infos
+                .into_iter()
+                .map(|info| info.info.sector_number)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sector_numbers(st.load_precommitted_sectors(&store, None).unwrap()),
+            [3, 5, 7, 12]
+        );
+        let filter = BitField::try_from_bits([9, 7, 3]).unwrap();
+        assert_eq!(
+            sector_numbers(st.load_precommitted_sectors(&store, Some(&filter)).unwrap()),
+            [3, 7]
+        );
+        let filter = BitField::try_from_bits([1, 2]).unwrap();
+        assert!(st
+            .load_precommitted_sectors(&store, Some(&filter))
+            .unwrap()
+            .is_empty());
+    }
 }
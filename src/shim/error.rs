@@ -28,6 +28,15 @@ impl ExitCode {
     /// The lowest exit code that an actor may abort with.
     pub const FIRST_USER_EXIT_CODE: u32 = ExitCodeV3::FIRST_USER_EXIT_CODE;
 
+    /// The message sender didn't have the requisite funds.
+    pub const SYS_INSUFFICIENT_FUNDS: Self = Self(ExitCodeV3::SYS_INSUFFICIENT_FUNDS);
+
+    /// The message ran out of gas.
+    pub const SYS_OUT_OF_GAS: Self = Self(ExitCodeV3::SYS_OUT_OF_GAS);
+
+    /// An unknown or fatal system error occurred.
+    pub const SYS_ASSERTION_FAILED: Self = Self(ExitCodeV3::SYS_ASSERTION_FAILED);
+
     pub fn value(&self) -> u32 {
         self.0.value()
     }
@@ -4,7 +4,7 @@
 use super::trace::ExecutionEvent;
 use crate::shim::econ::TokenAmount;
 use cid::Cid;
-use fil_actors_shared::fvm_ipld_amt::Amtv0;
+use fil_actors_shared::fvm_ipld_amt::{Amt, Amtv0};
 use fvm2::executor::ApplyRet as ApplyRet_v2;
 use fvm3::executor::ApplyRet as ApplyRet_v3;
 use fvm4::executor::ApplyRet as ApplyRet_v4;
@@ -16,6 +16,11 @@ pub use fvm_shared3::receipt::Receipt as Receipt_v3;
 use fvm_shared4::receipt::Receipt as Receipt_v4;
 use serde::Serialize;
 
+/// Event emitted by an actor, stamped with the ID of the emitter. These are stored in an AMT
+/// referenced by the `events_root` of the message receipt.
+// `StampedEvent_v4` and `StampedEvent_v3` are identical, use v4 here.
+pub use fvm_shared4::event::{Entry, StampedEvent};
+
 #[derive(Clone, Debug)]
 pub enum ApplyRet {
     V2(Box<ApplyRet_v2>),
@@ -150,6 +155,14 @@ impl Receipt {
         }
     }
 
+    /// Loads the events emitted while executing the message, if any.
+    pub fn events(&self, db: &impl Blockstore) -> anyhow::Result<Vec<StampedEvent>> {
+        match self.events_root() {
+            Some(events_root) => get_events(db, &events_root),
+            None => Ok(vec![]),
+        }
+    }
+
     pub fn get_receipt(
         db: &impl Blockstore,
         receipts: &Cid,
@@ -190,6 +203,17 @@ impl Receipt {
     }
 }
 
+/// Loads the events stored in the AMT rooted at `events_root`.
+pub fn get_events(db: &impl Blockstore, events_root: &Cid) -> anyhow::Result<Vec<StampedEvent>> {
+    let mut events = Vec::new();
+    let amt = Amt::<StampedEvent, _>::load(events_root, db)?;
+    amt.for_each(|_, event| {
+        events.push(event.clone());
+        Ok(())
+    })?;
+    Ok(events)
+}
+
 impl From<Receipt_v3> for Receipt {
     fn from(other: Receipt_v3) -> Self {
         Receipt::V3(other)
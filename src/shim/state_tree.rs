@@ -7,6 +7,7 @@ use std::{
 
 use anyhow::{anyhow, bail, Context as _};
 use cid::Cid;
+use fil_actors_shared::fvm_ipld_hamt::{BytesKey, Hamt};
 pub use fvm2::state_tree::{ActorState as ActorStateV2, StateTree as StateTreeV2};
 pub use fvm3::state_tree::{ActorState as ActorStateV3, StateTree as StateTreeV3};
 pub use fvm4::state_tree::{
@@ -27,6 +28,9 @@ use serde::{Deserialize, Serialize};
 pub use super::fvm_shared_latest::{state::StateRoot, ActorID};
 use crate::shim::{address::Address, econ::TokenAmount};
 
+/// Bit width of the HAMT holding the actors of a state tree.
+const HAMT_BIT_WIDTH: u32 = 5;
+
 #[derive(
     Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Serialize_repr, Deserialize_repr, FromPrimitive,
 )]
@@ -343,6 +347,51 @@ where
     }
 }
 
+/// Iterates over at most `limit` actors of the state tree at `root`, in the order of the
+/// underlying HAMT, starting at `start_at`, which must be an address in the tree. Returns the
+/// address to resume the iteration from, or `None` once all actors have been visited.
+pub fn for_each_actor_ranged<S: Blockstore>(
+    store: &S,
+    root: &Cid,
+    start_at: Option<&Address>,
+    limit: usize,
+    f: impl FnMut(Address, ActorState) -> anyhow::Result<()>,
+) -> anyhow::Result<Option<Address>> {
+    fn for_each_ranged<S: Blockstore, V>(
+        store: &S,
+        actors: &Cid,
+        start_at: Option<&Address>,
+        limit: usize,
+        mut f: impl FnMut(Address, ActorState) -> anyhow::Result<()>,
+    ) -> anyhow::Result<Option<Address>>
+    where
+        V: Serialize + serde::de::DeserializeOwned,
+        for<'a> &'a V: Into<ActorState>,
+    {
+        let hamt = Hamt::<_, V>::load_with_bit_width(actors, store, HAMT_BIT_WIDTH)?;
+        let start_at = start_at.map(|address| BytesKey(address.to_bytes()));
+        let (_, next) = hamt.for_each_ranged(start_at.as_ref(), Some(limit), |key, actor| {
+            f(Address::from_bytes(key)?, actor.into())
+        })?;
+        Ok(next.map(|key| Address::from_bytes(&key.0)).transpose()?)
+    }
+
+    let StateRoot {
+        version, actors, ..
+    } = store
+        .get_cbor(root)?
+        .with_context(|| format!("state root {root} not found"))?;
+    match StateTreeVersion::from(version) {
+        StateTreeVersion::V5 => {
+            for_each_ranged::<_, ActorStateV4>(store, &actors, start_at, limit, f)
+        }
+        StateTreeVersion::V3 | StateTreeVersion::V4 => {
+            for_each_ranged::<_, ActorStateV2>(store, &actors, start_at, limit, f)
+        }
+        version => bail!("ranged iteration is not supported on state tree {version:?}"),
+    }
+}
+
 /// `Newtype` to wrap different versions of `fvm::state_tree::ActorState`
 ///
 /// # Examples
@@ -506,10 +555,11 @@ impl From<ActorState> for ActorStateV4 {
 
 #[cfg(test)]
 mod tests {
-    use super::StateTree;
+    use super::{for_each_actor_ranged, ActorState, StateTree, StateTreeVersion};
     use crate::blocks::CachingBlockHeader;
     use crate::db::car::AnyCar;
     use crate::networks::{calibnet, mainnet};
+    use crate::shim::{address::Address, econ::TokenAmount};
     use cid::Cid;
     use fil_actor_interface::init::{self, State};
     use std::sync::Arc;
@@ -545,4 +595,40 @@ mod tests {
             "testnetnet"
         );
     }
+
+    #[test]
+    fn for_each_actor_ranged_visits_all_actors_once() {
+        let db = Arc::new(crate::db::MemoryDB::default());
+        let mut tree = StateTree::new(db.clone(), StateTreeVersion::V5).unwrap();
+        let expected = (100..150).map(Address::new_id).collect::<Vec<_>>();
+        for address in &expected {
+            let actor = ActorState::new(
+                Cid::default(),
+                Cid::default(),
+                TokenAmount::default(),
+                0,
+                None,
+            );
+            tree.set_actor(address, actor).unwrap();
+        }
+        let root = tree.flush().unwrap();
+
+        let mut visited = vec![];
+        let mut cursor = None;
+        loop {
+            let mut page = 0;
+            cursor = for_each_actor_ranged(&db, &root, cursor.as_ref(), 7, |address, _| {
+                visited.push(address);
+                page += 1;
+                Ok(())
+            })
+            .unwrap();
+            assert!(page <= 7);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        visited.sort();
+        assert_eq!(visited, expected);
+    }
 }
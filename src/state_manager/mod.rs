@@ -27,6 +27,7 @@ use crate::metrics::HistogramTimerExt;
 use crate::networks::ChainConfig;
 use crate::rpc::state::{ApiInvocResult, InvocResult, MessageGasCost};
 use crate::rpc::types::{MiningBaseInfo, SectorOnChainInfo};
+use crate::shim::actors::init::InitStateExt as _;
 use crate::shim::actors::miner::MinerStateExt as _;
 use crate::shim::{
     address::{Address, Payload, Protocol},
@@ -35,7 +36,7 @@ use crate::shim::{
     executor::Receipt,
     message::Message,
     randomness::Randomness,
-    state_tree::{ActorState, StateTree},
+    state_tree::{ActorID, ActorState, StateTree},
     version::NetworkVersion,
 };
 use crate::state_manager::chain_rand::draw_randomness;
@@ -75,6 +76,7 @@ use tracing::{error, info, instrument, trace, warn};
 pub use utils::is_valid_for_sending;
 
 const DEFAULT_TIPSET_CACHE_SIZE: NonZeroUsize = nonzero!(1024usize);
+const DEFAULT_ROBUST_ADDRESS_CACHE_SIZE: NonZeroUsize = nonzero!(4096usize);
 
 /// Intermediary for retrieving state objects and updating actor states.
 type CidPair = (Cid, Cid);
@@ -212,6 +214,10 @@ pub struct StateManager<DB> {
 
     /// This is a cache which indexes tipsets to their calculated state.
     cache: TipsetStateCache,
+    /// Cache for [`StateManager::lookup_robust_address`], keyed by ID. A binding never changes
+    /// once created, but may differ between forks, so cached addresses are checked against the
+    /// state they are looked up in. Misses are not cached, as they can't be checked that way.
+    robust_address_cache: SyncMutex<LruCache<ActorID, Address>>,
     // Beacon can be cheaply crated from the `chain_config`. The only reason we
     // store it here is because it has a look-up cache.
     beacon: Arc<crate::beacon::BeaconSchedule>,
@@ -238,6 +244,7 @@ where
         Ok(Self {
             cs,
             cache: TipsetStateCache::new(),
+            robust_address_cache: SyncMutex::new(LruCache::new(DEFAULT_ROBUST_ADDRESS_CACHE_SIZE)),
             beacon,
             chain_config,
             sync_config,
@@ -401,6 +408,12 @@ where
                     .compute_tipset_state(Arc::clone(tipset), NO_CALLBACK, VMTrace::NotTraced)
                     .await?;
                 trace!("Completed tipset state calculation {:?}", tipset.cids());
+                if let Err(e) =
+                    self.chain_store()
+                        .index_tipset_events(tipset, &ts_state.0, &ts_state.1)
+                {
+                    warn!("Failed to index events of tipset {:?}: {e}", tipset.cids());
+                }
                 Ok(ts_state)
             })
             .await
@@ -505,6 +518,7 @@ where
         message: &mut ChainMessage,
         prior_messages: &[ChainMessage],
         tipset: Option<Arc<Tipset>>,
+        trace_config: VMTrace,
     ) -> Result<InvocResult, Error> {
         let ts = tipset.unwrap_or_else(|| self.cs.heaviest_tipset());
         let (st, _) = self
@@ -537,7 +551,7 @@ where
                     timestamp: ts.min_timestamp(),
                 },
                 &self.engine,
-                VMTrace::NotTraced,
+                trace_config,
             )?;
 
             for msg in prior_messages {
@@ -556,6 +570,7 @@ where
             msg: message.message().clone(),
             msg_rct: Some(ret.msg_receipt()),
             error: ret.failure_info(),
+            execution_trace: structured::parse_events(ret.exec_trace()).unwrap_or_default(),
         })
     }
 
@@ -610,6 +625,116 @@ where
             .map_err(|err| Error::Other(format!("failed to replay: {err}")))
     }
 
+    /// Executes the messages of the given tipset with tracing enabled, returning the resulting
+    /// state root and the invocation results of every applied message, including the implicit
+    /// ones, in execution order.
+    pub async fn execution_trace(
+        self: &Arc<Self>,
+        ts: &Arc<Tipset>,
+    ) -> Result<(Cid, Vec<ApiInvocResult>), Error> {
+        let (tx, rx) = flume::unbounded();
+        let callback = move |ctx: &MessageCallbackCtx| {
+            tx.send(ApiInvocResult {
+                msg_cid: ctx.message.cid()?,
+                msg: ctx.message.message().clone(),
+                msg_rct: Some(ctx.apply_ret.msg_receipt()),
+                error: ctx.apply_ret.failure_info().unwrap_or_default(),
+                duration: ctx.duration.as_nanos().clamp(0, u64::MAX as u128) as u64,
+                gas_cost: MessageGasCost::new(ctx.message.message(), ctx.apply_ret)?,
+                execution_trace: structured::parse_events(ctx.apply_ret.exec_trace())
+                    .unwrap_or_default(),
+            })?;
+            Ok(())
+        };
+        let (state_root, _) = self
+            .compute_tipset_state(Arc::clone(ts), Some(callback), VMTrace::Traced)
+            .await?;
+        Ok((state_root, rx.drain().collect()))
+    }
+
+    /// Computes the state at the given height: executes the messages of the given tipset, runs
+    /// the migrations scheduled up to `height`, then applies the extra `messages` on top.
+    /// Returns the resulting state root and the invocation results of every applied message,
+    /// in execution order.
+    pub async fn compute_state(
+        self: &Arc<Self>,
+        height: ChainEpoch,
+        messages: &[Message],
+        ts: &Arc<Tipset>,
+    ) -> Result<(Cid, Vec<ApiInvocResult>), Error> {
+        if height < ts.epoch() {
+            return Err(Error::Other(format!(
+                "cannot compute state at height {height}, before the tipset at epoch {}",
+                ts.epoch()
+            )));
+        }
+        let (mut state_root, mut trace) = self.execution_trace(ts).await?;
+
+        for epoch in ts.epoch()..height {
+            if let Some(new_state) = run_state_migrations(
+                epoch,
+                self.chain_config(),
+                &self.blockstore_owned(),
+                &state_root,
+            )? {
+                state_root = new_state;
+            }
+        }
+
+        if messages.is_empty() {
+            return Ok((state_root, trace));
+        }
+
+        let chain_rand = self.chain_rand(Arc::clone(ts));
+        let genesis_info = GenesisInfo::from_chain_config(self.chain_config().clone());
+        // FVM requires a stack size of 64MiB. The alternative is to use `ThreadedExecutor` from
+        // FVM, but that introduces some constraints, and possible deadlocks.
+        let state_root = stacker::grow(64 << 20, || -> anyhow::Result<Cid> {
+            let mut vm = VM::new(
+                ExecutionContext {
+                    heaviest_tipset: Arc::clone(ts),
+                    state_tree_root: state_root,
+                    epoch: height,
+                    rand: Box::new(chain_rand),
+                    base_fee: ts.block_headers().first().parent_base_fee.clone(),
+                    circ_supply: genesis_info.get_vm_circulating_supply(
+                        height,
+                        &self.blockstore_owned(),
+                        &state_root,
+                    )?,
+                    chain_config: self.chain_config().clone(),
+                    chain_index: Arc::clone(&self.chain_store().chain_index),
+                    timestamp: ts.min_timestamp(),
+                },
+                &self.engine,
+                VMTrace::Traced,
+            )?;
+
+            for msg in messages {
+                let (ret, duration) = vm.apply_message(&ChainMessage::Unsigned(msg.clone()))?;
+                if !ret.msg_receipt().exit_code().is_success() {
+                    info!(
+                        "compute state: message {} failed (exit: {})",
+                        msg.cid()?,
+                        ret.msg_receipt().exit_code()
+                    );
+                }
+                trace.push(ApiInvocResult {
+                    msg_cid: msg.cid()?,
+                    msg: msg.clone(),
+                    msg_rct: Some(ret.msg_receipt()),
+                    error: ret.failure_info().unwrap_or_default(),
+                    duration: duration.as_nanos().clamp(0, u64::MAX as u128) as u64,
+                    gas_cost: MessageGasCost::new(msg, &ret)?,
+                    execution_trace: structured::parse_events(ret.exec_trace()).unwrap_or_default(),
+                });
+            }
+            vm.flush()
+        })?;
+
+        Ok((state_root, trace))
+    }
+
     /// Checks the eligibility of the miner. This is used in the validation that
     /// a block's miner has the requirements to mine a block.
     pub fn eligible_to_mine(
@@ -1395,6 +1520,44 @@ where
         }
     }
 
+    /// Looks up the robust address of an ID address by scanning the init actor's address map
+    /// in the parent state of the given tipset. Unlike
+    /// [`StateManager::resolve_to_deterministic_address`], this works for actors of any type.
+    pub fn lookup_robust_address(&self, address: &Address, ts: &Tipset) -> anyhow::Result<Address> {
+        let id = address
+            .id()
+            .with_context(|| format!("failed to decode {address} as an ID address"))?;
+
+        let state = self.get_state_tree(ts.parent_state())?;
+        let cached = self.robust_address_cache.lock().get(&id).copied();
+        if let Some(robust) = cached {
+            if state.lookup_id(&robust)? == Some(id) {
+                return Ok(robust);
+            }
+        }
+
+        let init_actor = state
+            .get_actor(&Address::INIT_ACTOR)?
+            .context("init actor not found")?;
+        let init_state = init::State::load(self.blockstore(), init_actor.code, init_actor.state)?;
+        let mut robust = None;
+        // Bail out of the iteration as soon as the address is found.
+        let result = init_state.for_each_address(self.blockstore(), |addr, actor_id| {
+            if actor_id == id {
+                robust = Some(addr);
+                bail!("address found");
+            }
+            Ok(())
+        });
+        if let (None, Err(e)) = (robust, result) {
+            return Err(e);
+        }
+
+        let robust = robust.with_context(|| format!("address {address} not found"))?;
+        self.robust_address_cache.lock().put(id, robust);
+        Ok(robust)
+    }
+
     fn chain_rand(&self, tipset: Arc<Tipset>) -> ChainRand<DB> {
         ChainRand::new(
             self.chain_config.clone(),
@@ -1638,3 +1801,107 @@ where
         Ok((state_root, receipt_root))
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{CachingBlockHeader, RawBlockHeader};
+    use crate::db::MemoryDB;
+    use crate::shim::{econ::TokenAmount, machine::BuiltinActor, state_tree::StateTreeVersion};
+    use crate::utils::db::CborStoreExt as _;
+
+    #[test]
+    fn lookup_robust_address_cache() {
+        let db = Arc::new(MemoryDB::default());
+        let manifest = &ChainConfig::mainnet()
+            .actor_bundle_metadata(NetworkVersion::V22)
+            .unwrap()
+            .manifest;
+        let robust = Address::new_secp256k1(&[1; 65]).unwrap();
+        let mut init_state =
+            fil_actor_init_state::v13::State::new(&db, "test".to_string()).unwrap();
+        let (id, _) = init_state
+            .map_addresses_to_id(&db, &robust.into(), None)
+            .unwrap();
+        // An actor without a robust address, such as a singleton.
+        let unbound = id + 1;
+
+        let mut state = StateTree::new(db.clone(), StateTreeVersion::V5).unwrap();
+        state
+            .set_actor(
+                &Address::INIT_ACTOR,
+                ActorState::new(
+                    manifest.get(BuiltinActor::Init).unwrap(),
+                    db.put_cbor_default(&init_state).unwrap(),
+                    TokenAmount::default(),
+                    0,
+                    None,
+                ),
+            )
+            .unwrap();
+        state
+            .set_actor(
+                &Address::new_id(unbound),
+                ActorState::new_empty(manifest.get(BuiltinActor::Account).unwrap(), None),
+            )
+            .unwrap();
+        let state_root = state.flush().unwrap();
+
+        let chain_config = Arc::new(ChainConfig::default());
+        let genesis_header = CachingBlockHeader::new(RawBlockHeader {
+            miner_address: Address::new_id(0),
+            timestamp: 7777,
+            ..Default::default()
+        });
+        let cs = Arc::new(
+            ChainStore::new(
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                chain_config.clone(),
+                genesis_header,
+            )
+            .unwrap(),
+        );
+        let sm = StateManager::new(cs, chain_config, Arc::new(SyncConfig::default())).unwrap();
+        let ts = Tipset::from(CachingBlockHeader::new(RawBlockHeader {
+            miner_address: Address::new_id(0),
+            state_root,
+            ..Default::default()
+        }));
+        let cached = |id| sm.robust_address_cache.lock().peek(&id).copied();
+
+        // miss, resolved by scanning the address map
+        assert_eq!(
+            sm.lookup_robust_address(&Address::new_id(id), &ts).unwrap(),
+            robust
+        );
+        assert_eq!(cached(id), Some(robust));
+
+        // hit
+        assert_eq!(
+            sm.lookup_robust_address(&Address::new_id(id), &ts).unwrap(),
+            robust
+        );
+
+        // a binding from another fork is not served
+        let other = Address::new_secp256k1(&[2; 65]).unwrap();
+        sm.robust_address_cache.lock().put(id, other);
+        assert_eq!(
+            sm.lookup_robust_address(&Address::new_id(id), &ts).unwrap(),
+            robust
+        );
+        assert_eq!(cached(id), Some(robust));
+
+        // not found, not cached, as the ID may be bound on another fork
+        assert!(sm
+            .lookup_robust_address(&Address::new_id(unbound), &ts)
+            .is_err());
+        assert_eq!(cached(unbound), None);
+        assert!(sm
+            .lookup_robust_address(&Address::new_id(unbound + 1), &ts)
+            .is_err());
+        assert_eq!(cached(unbound + 1), None);
+    }
+}
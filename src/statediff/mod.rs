@@ -4,6 +4,7 @@
 mod resolve;
 
 use std::{
+    collections::BTreeMap,
     fmt::Write as FmtWrite,
     io::{stdout, Write},
     sync::Arc,
@@ -13,10 +14,11 @@ use crate::{
     lotus_json::HasLotusJson as _,
     shim::{
         address::Address,
-        state_tree::{ActorState, StateTree},
+        state_tree::{ActorState, StateRoot, StateTree},
     },
+    utils::db::CborStoreExt as _,
 };
-use ahash::HashMap;
+use anyhow::Context as _;
 use cid::Cid;
 use colored::*;
 use fil_actor_interface::{
@@ -26,6 +28,7 @@ use fil_actor_interface::{
     reward::State as RewardState, system::State as SystemState,
 };
 use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore as _;
 use libipld_core::ipld::Ipld;
 use resolve::resolve_cids_recursive;
 use serde::{Deserialize, Serialize};
@@ -56,18 +59,188 @@ fn actor_to_resolved(
     }
 }
 
-fn root_to_state_map<BS: Blockstore>(
+/// The state of an actor before and after a state transition. `old` is `None`
+/// for added actors, and `new` is `None` for removed actors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActorChange {
+    pub old: Option<ActorState>,
+    pub new: Option<ActorState>,
+}
+
+/// Returns all actors that were added, removed, or modified between two state
+/// trees, ordered by address. The actors HAMTs are diffed node by node, so the
+/// subtrees the two state trees share are skipped.
+pub fn changed_actors<BS: Blockstore>(
     bs: &Arc<BS>,
-    root: &Cid,
-) -> Result<HashMap<Address, ActorState>, anyhow::Error> {
-    let mut actors = HashMap::default();
-    let state_tree = StateTree::new_from_root(bs.clone(), root)?;
-    state_tree.for_each(|addr: Address, actor: &ActorState| {
-        actors.insert(addr, actor.clone());
-        Ok(())
-    })?;
-
-    Ok(actors)
+    old_root: &Cid,
+    new_root: &Cid,
+) -> Result<BTreeMap<Address, ActorChange>, anyhow::Error> {
+    let mut changes = BTreeMap::new();
+    if old_root == new_root {
+        return Ok(changes);
+    }
+
+    let mut entries = BTreeMap::new();
+    diff_hamt_nodes(
+        bs.as_ref(),
+        &actors_hamt_root(bs.as_ref(), old_root)?,
+        &actors_hamt_root(bs.as_ref(), new_root)?,
+        &mut entries,
+    )?;
+
+    let old_tree = StateTree::new_from_root(bs.clone(), old_root)?;
+    let new_tree = StateTree::new_from_root(bs.clone(), new_root)?;
+    for (key, (old, new)) in entries {
+        if old == new {
+            continue;
+        }
+        let addr = Address::from_bytes(&key)?;
+        changes.insert(
+            addr,
+            ActorChange {
+                old: old_tree.get_actor(&addr)?,
+                new: new_tree.get_actor(&addr)?,
+            },
+        );
+    }
+    Ok(changes)
+}
+
+/// Returns the root of the actors HAMT of a state tree. State trees of version
+/// 0 are the HAMT itself.
+fn actors_hamt_root(bs: &impl Blockstore, root: &Cid) -> anyhow::Result<Cid> {
+    Ok(match bs.get_cbor::<StateRoot>(root) {
+        Ok(Some(state_root)) => state_root.actors,
+        _ => *root,
+    })
+}
+
+/// A pointer of a HAMT node, with the entries kept as raw IPLD.
+enum HamtPointer {
+    Link(Cid),
+    Bucket(Vec<(Vec<u8>, Ipld)>),
+}
+
+impl HamtPointer {
+    fn parse(ipld: Ipld) -> anyhow::Result<Self> {
+        match ipld {
+            Ipld::Link(cid) => Ok(Self::Link(cid)),
+            Ipld::List(entries) => entries
+                .into_iter()
+                .map(|entry| match entry {
+                    Ipld::List(kv) => match <[Ipld; 2]>::try_from(kv) {
+                        Ok([Ipld::Bytes(key), value]) => Ok((key, value)),
+                        Ok([Ipld::String(key), value]) => Ok((key.into_bytes(), value)),
+                        _ => anyhow::bail!("invalid HAMT entry"),
+                    },
+                    _ => anyhow::bail!("invalid HAMT entry"),
+                })
+                .collect::<anyhow::Result<_>>()
+                .map(Self::Bucket),
+            // Pointers of older HAMTs are unions keyed by `"0"` for links and `"1"` for buckets.
+            Ipld::Map(mut map) if map.len() == 1 => map
+                .remove("0")
+                .or_else(|| map.remove("1"))
+                .map(Self::parse)
+                .context("invalid HAMT pointer")?,
+            _ => anyhow::bail!("invalid HAMT pointer"),
+        }
+    }
+}
+
+/// Loads a HAMT node, returning its pointers along with their index in the node.
+fn load_hamt_node(bs: &impl Blockstore, cid: &Cid) -> anyhow::Result<Vec<(usize, HamtPointer)>> {
+    let node: Ipld = bs.get_cbor_required(cid)?;
+    let Ipld::List(fields) = node else {
+        anyhow::bail!("invalid HAMT node {cid}");
+    };
+    let Ok([Ipld::Bytes(bitfield), Ipld::List(pointers)]) = <[Ipld; 2]>::try_from(fields) else {
+        anyhow::bail!("invalid HAMT node {cid}");
+    };
+    // The bitfield is a big-endian integer, whose set bits are the indices of the pointers.
+    let indices = bitfield
+        .iter()
+        .rev()
+        .enumerate()
+        .flat_map(|(byte_index, byte)| {
+            (0..8)
+                .filter(move |bit| byte & (1 << bit) != 0)
+                .map(move |bit| byte_index * 8 + bit)
+        })
+        .collect::<Vec<_>>();
+    anyhow::ensure!(
+        indices.len() == pointers.len(),
+        "invalid HAMT node {cid}: bitfield does not match the pointers"
+    );
+    indices
+        .into_iter()
+        .zip(pointers)
+        .map(|(index, pointer)| Ok((index, HamtPointer::parse(pointer)?)))
+        .collect()
+}
+
+/// Entries of two HAMTs that may differ, by key, with their old and new values.
+type HamtEntries = BTreeMap<Vec<u8>, (Option<Ipld>, Option<Ipld>)>;
+
+/// Collects the entries of the HAMT nodes `old` and `new` that may differ,
+/// descending only into the pointers that differ between them.
+fn diff_hamt_nodes(
+    bs: &impl Blockstore,
+    old: &Cid,
+    new: &Cid,
+    entries: &mut HamtEntries,
+) -> anyhow::Result<()> {
+    if old == new {
+        return Ok(());
+    }
+    let mut old = load_hamt_node(bs, old)?.into_iter().peekable();
+    let mut new = load_hamt_node(bs, new)?.into_iter().peekable();
+    loop {
+        let (old, new) = match (old.peek(), new.peek()) {
+            (None, None) => return Ok(()),
+            (Some((o, _)), Some((n, _))) if o == n => (old.next(), new.next()),
+            (Some((o, _)), Some((n, _))) if o < n => (old.next(), None),
+            (Some(_), None) => (old.next(), None),
+            _ => (None, new.next()),
+        };
+        match (old, new) {
+            (Some((_, HamtPointer::Link(old))), Some((_, HamtPointer::Link(new)))) => {
+                diff_hamt_nodes(bs, &old, &new, entries)?
+            }
+            (old, new) => {
+                if let Some((_, pointer)) = old {
+                    for_each_hamt_entry(bs, pointer, &mut |key, value| {
+                        entries.entry(key).or_default().0 = Some(value);
+                    })?;
+                }
+                if let Some((_, pointer)) = new {
+                    for_each_hamt_entry(bs, pointer, &mut |key, value| {
+                        entries.entry(key).or_default().1 = Some(value);
+                    })?;
+                }
+            }
+        }
+    }
+}
+
+fn for_each_hamt_entry(
+    bs: &impl Blockstore,
+    pointer: HamtPointer,
+    f: &mut impl FnMut(Vec<u8>, Ipld),
+) -> anyhow::Result<()> {
+    match pointer {
+        HamtPointer::Link(cid) => {
+            for (_, pointer) in load_hamt_node(bs, &cid)? {
+                for_each_hamt_entry(bs, pointer, f)?;
+            }
+        }
+        HamtPointer::Bucket(bucket) => {
+            for (key, value) in bucket {
+                f(key, value);
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Tries to resolve state tree actors, if all data exists in store.
@@ -80,20 +253,12 @@ fn try_print_actor_states<BS: Blockstore>(
     expected_root: &Cid,
     depth: Option<u64>,
 ) -> Result<(), anyhow::Error> {
-    // For now, resolving to a map, because we need to use go implementation's
-    // inefficient caching this would probably be faster in most cases.
-    let mut e_state = root_to_state_map(bs, expected_root)?;
-
-    // Compare state with expected
-    let state_tree = StateTree::new_from_root(bs.clone(), root)?;
-
-    state_tree.for_each(|addr: Address, actor| {
-        let calc_pp = pp_actor_state(bs, actor, depth)?;
-
-        if let Some(other) = e_state.remove(&addr) {
-            if &other != actor {
+    for (addr, change) in changed_actors(bs, expected_root, root)? {
+        match (change.old, change.new) {
+            (Some(expected), Some(actor)) => {
                 let comma = ",";
-                let expected_pp = pp_actor_state(bs, &other, depth)?;
+                let expected_pp = pp_actor_state(bs, &expected, depth)?;
+                let calc_pp = pp_actor_state(bs, &actor, depth)?;
                 let expected = expected_pp
                     .split(comma)
                     .map(|s| s.trim_start_matches('\n'))
@@ -108,18 +273,19 @@ fn try_print_actor_states<BS: Blockstore>(
                 writeln!(handle, "Address {addr} changed: ")?;
                 print_diffs(&mut handle, diffs)?;
             }
-        } else {
-            // Added actor, print out the json format actor state.
-            println!("{}", format!("+ Address {addr}:\n{calc_pp}").green());
+            (None, Some(actor)) => {
+                // Added actor, print out the json format actor state.
+                let calc_pp = pp_actor_state(bs, &actor, depth)?;
+                println!("{}", format!("+ Address {addr}:\n{calc_pp}").green());
+            }
+            (Some(expected), None) => {
+                // Removed actor, it no longer has actor state.
+                let expected_json =
+                    serde_json::to_string_pretty(&actor_to_resolved(bs, &expected, depth))?;
+                println!("{}", format!("- Address {addr}:\n{expected_json}").red())
+            }
+            (None, None) => {}
         }
-
-        Ok(())
-    })?;
-
-    // Print all addresses that no longer have actor state
-    for (addr, state) in e_state.into_iter() {
-        let expected_json = serde_json::to_string_pretty(&actor_to_resolved(bs, &state, depth))?;
-        println!("{}", format!("- Address {addr}:\n{expected_json}").red())
     }
 
     Ok(())
@@ -229,13 +395,21 @@ where
 #[cfg(test)]
 mod tests {
     use crate::db::MemoryDB;
-    use crate::shim::{address::Address, econ::TokenAmount, state_tree::ActorState};
+    use crate::shim::{
+        address::Address,
+        econ::TokenAmount,
+        state_tree::{ActorState, StateTree, StateTreeVersion},
+    };
     use crate::utils::db::CborStoreExt;
     use cid::Cid;
     use fil_actor_account_state::v10::State as AccountState;
     use fvm_ipld_blockstore::Blockstore;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
 
-    use super::pp_actor_state;
+    use super::{changed_actors, pp_actor_state, ActorChange};
 
     fn mk_account_v10(db: &impl Blockstore, account: &AccountState) -> ActorState {
         // mainnet v10 account actor cid
@@ -307,4 +481,118 @@ mod tests {
 }"
         );
     }
+
+    #[test]
+    fn changed_actors_reports_added_removed_and_modified() {
+        let db = Arc::new(MemoryDB::default());
+        let mk_actor = |balance: u64| {
+            ActorState::new(
+                Cid::default(),
+                Cid::default(),
+                TokenAmount::from_atto(balance),
+                0,
+                None,
+            )
+        };
+        let mk_root = |actors: &[(u64, u64)]| {
+            let mut tree = StateTree::new(db.clone(), StateTreeVersion::V5).unwrap();
+            for &(id, balance) in actors {
+                tree.set_actor(&Address::new_id(id), mk_actor(balance))
+                    .unwrap();
+            }
+            tree.flush().unwrap()
+        };
+
+        let old_root = mk_root(&[(100, 1), (101, 2), (102, 3)]);
+        let new_root = mk_root(&[(100, 1), (101, 5), (103, 4)]);
+
+        assert!(changed_actors(&db, &old_root, &old_root)
+            .unwrap()
+            .is_empty());
+        let changes = changed_actors(&db, &old_root, &new_root).unwrap();
+        assert_eq!(
+            changes.into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    Address::new_id(101),
+                    ActorChange {
+                        old: Some(mk_actor(2)),
+                        new: Some(mk_actor(5)),
+                    }
+                ),
+                (
+                    Address::new_id(102),
+                    ActorChange {
+                        old: Some(mk_actor(3)),
+                        new: None,
+                    }
+                ),
+                (
+                    Address::new_id(103),
+                    ActorChange {
+                        old: None,
+                        new: Some(mk_actor(4)),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_actors_skips_shared_subtrees() {
+        /// Counts the blocks read from the inner store.
+        #[derive(Default)]
+        struct CountingDB {
+            db: MemoryDB,
+            reads: AtomicUsize,
+        }
+        impl Blockstore for CountingDB {
+            fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+                self.reads.fetch_add(1, Ordering::Relaxed);
+                self.db.get(k)
+            }
+            fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
+                self.db.put_keyed(k, block)
+            }
+        }
+
+        let db = Arc::new(CountingDB::default());
+        let mk_actor = |balance: u64| {
+            ActorState::new(
+                Cid::default(),
+                Cid::default(),
+                TokenAmount::from_atto(balance),
+                0,
+                None,
+            )
+        };
+        let mut tree = StateTree::new(db.clone(), StateTreeVersion::V5).unwrap();
+        for id in 100..2100 {
+            tree.set_actor(&Address::new_id(id), mk_actor(id)).unwrap();
+        }
+        let old_root = tree.flush().unwrap();
+        tree.set_actor(&Address::new_id(1000), mk_actor(0)).unwrap();
+        let new_root = tree.flush().unwrap();
+
+        let reads = || db.reads.swap(0, Ordering::Relaxed);
+        reads();
+        StateTree::new_from_root(db.clone(), &old_root)
+            .unwrap()
+            .for_each(|_, _| Ok(()))
+            .unwrap();
+        let full_walk_reads = reads();
+
+        let changes = changed_actors(&db, &old_root, &new_root).unwrap();
+        assert_eq!(
+            changes.into_iter().collect::<Vec<_>>(),
+            vec![(
+                Address::new_id(1000),
+                ActorChange {
+                    old: Some(mk_actor(1000)),
+                    new: Some(mk_actor(0)),
+                }
+            )]
+        );
+        assert!(reads() * 4 < full_walk_reads);
+    }
 }
@@ -12,9 +12,10 @@ use crate::key_management::{KeyStore, KeyStoreConfig};
 use crate::lotus_json::HasLotusJson;
 use crate::message::{Message as _, SignedMessage};
 use crate::message_pool::{MessagePool, MpoolRpcProvider};
-use crate::networks::{parse_bootstrap_peers, ChainConfig, NetworkChain};
+use crate::networks::{calibnet, parse_bootstrap_peers, ChainConfig, NetworkChain};
 use crate::rpc::beacon::BeaconGetEntry;
-use crate::rpc::eth::types::{EthAddress, EthBytes};
+use crate::rpc::eth::filter::EthFilterSpec;
+use crate::rpc::eth::types::{EthAddress, EthBytes, EthCallMessage};
 use crate::rpc::gas::GasEstimateGasLimit;
 use crate::rpc::miner::BlockTemplate;
 use crate::rpc::types::{ApiTipsetKey, MessageFilter, MessageLookup, SectorOnChainInfo};
@@ -25,8 +26,10 @@ use crate::shim::{
     address::{Address, Protocol},
     crypto::Signature,
     econ::TokenAmount,
+    executor::Receipt,
     message::{Message, METHOD_SEND},
     state_tree::StateTree,
+    version::NetworkVersion,
 };
 use crate::state_manager::StateManager;
 use crate::utils::UrlFromMultiAddr;
@@ -544,6 +547,14 @@ fn chain_tests_with_tipset<DB: Blockstore>(
         for msg_cid in sample_message_cids(bls_messages.iter(), secp_messages.iter()) {
             tests.extend([RpcTest::identity(ChainGetMessage::request((msg_cid,))?)]);
         }
+
+        for events_root in Receipt::get_receipts(store, block.message_receipts)?
+            .iter()
+            .filter_map(Receipt::events_root)
+            .take(COLLECTION_SAMPLE_SIZE)
+        {
+            tests.extend([RpcTest::identity(ChainGetEvents::request((events_root,))?)]);
+        }
     }
 
     Ok(tests)
@@ -677,10 +688,17 @@ fn state_tests_with_tipset<DB: Blockstore>(
     let mut tests = vec![
         RpcTest::identity(StateNetworkName::request(())?),
         RpcTest::identity(StateGetNetworkParams::request(())?),
+        RpcTest::identity(StateActorCodeCIDs::request((NetworkVersion::V21,))?),
+        RpcTest::identity(StateActorManifestCID::request((NetworkVersion::V21,))?),
         RpcTest::identity(StateGetActor::request((
             Address::SYSTEM_ACTOR,
             tipset.key().into(),
         ))?),
+        RpcTest::identity(StateCompute::request((
+            tipset.epoch(),
+            vec![],
+            tipset.key().into(),
+        ))?),
         RpcTest::identity(StateGetRandomnessFromTickets::request((
             DomainSeparationTag::ElectionProofProduction as i64,
             tipset.epoch(),
@@ -693,6 +711,14 @@ fn state_tests_with_tipset<DB: Blockstore>(
             "dead beef".as_bytes().to_vec(),
             tipset.key().into(),
         ))?),
+        RpcTest::identity(StateGetRandomnessDigestFromTickets::request((
+            tipset.epoch(),
+            tipset.key().into(),
+        ))?),
+        RpcTest::identity(StateGetRandomnessDigestFromBeacon::request((
+            tipset.epoch(),
+            tipset.key().into(),
+        ))?),
         RpcTest::identity(StateReadState::request((
             Address::SYSTEM_ACTOR,
             tipset.key().into(),
@@ -771,6 +797,8 @@ fn state_tests_with_tipset<DB: Blockstore>(
         (deals, deals_map)
     };
 
+    let state = StateTree::new_from_root(store.clone(), tipset.parent_state())?;
+
     // Take 5 deals from each tipset
     for deal in deals.into_iter().take(COLLECTION_SAMPLE_SIZE) {
         tests.push(RpcTest::identity(StateMarketStorageDeal::request((
@@ -789,6 +817,10 @@ fn state_tests_with_tipset<DB: Blockstore>(
                 block.miner_address,
                 tipset.key().into(),
             ))?),
+            RpcTest::identity(StateLookupRobustAddress::request((
+                block.miner_address,
+                tipset.key().into(),
+            ))?),
             validate_sector_on_chain_info_vec(StateMinerSectors::request((
                 block.miner_address,
                 None,
@@ -840,6 +872,10 @@ fn state_tests_with_tipset<DB: Blockstore>(
                 block.miner_address,
                 tipset.key().into(),
             ))?),
+            RpcTest::identity(StateMinerAllocated::request((
+                block.miner_address,
+                tipset.key().into(),
+            ))?),
             RpcTest::identity(StateGetClaims::request((
                 block.miner_address,
                 tipset.key().into(),
@@ -994,6 +1030,26 @@ fn state_tests_with_tipset<DB: Blockstore>(
                 ))?),
                 RpcTest::identity(StateCall::request((msg.clone(), tipset.key().into()))?),
             ]);
+            // Only the parameters of the methods supported by Forest are compared.
+            if let Some(actor) = state.get_actor(&msg.to())? {
+                if let Ok(params) =
+                    rpc::state::params::decode_params(&actor.code, msg.method_num(), msg.params())
+                {
+                    tests.extend([
+                        RpcTest::identity(StateDecodeParams::request((
+                            msg.to(),
+                            msg.method_num(),
+                            msg.params().to_vec(),
+                            tipset.key().into(),
+                        ))?),
+                        RpcTest::identity(StateEncodeParams::request((
+                            actor.code,
+                            msg.method_num(),
+                            params,
+                        ))?),
+                    ]);
+                }
+            }
         }
     }
 
@@ -1043,6 +1099,8 @@ fn eth_tests() -> Vec<RpcTest> {
         RpcTest::identity(EthChainId::request(()).unwrap()),
         // There is randomness in the result of this API
         RpcTest::basic(EthGasPrice::request(()).unwrap()),
+        // There is randomness in the result of this API
+        RpcTest::basic(EthMaxPriorityFeePerGas::request(()).unwrap()),
         RpcTest::basic(EthSyncing::request(()).unwrap()),
         RpcTest::identity(
             EthGetBalance::request((
@@ -1051,6 +1109,13 @@ fn eth_tests() -> Vec<RpcTest> {
             ))
             .unwrap(),
         ),
+        RpcTest::identity(
+            EthGetTransactionCount::request((
+                EthAddress::from_str("0xff38c072f286e3b20b3954ca9f99c05fbecc64aa").unwrap(),
+                BlockNumberOrHash::from_predefined(Predefined::Latest),
+            ))
+            .unwrap(),
+        ),
         RpcTest::identity(
             EthGetBalance::request((
                 EthAddress::from_str("0xff38c072f286e3b20b3954ca9f99c05fbecc64aa").unwrap(),
@@ -1150,6 +1215,32 @@ fn eth_tests_with_tipset(shared_tipset: &Tipset) -> Vec<RpcTest> {
             ))
             .unwrap(),
         ),
+        RpcTest::identity(
+            EthCall::request((
+                EthCallMessage {
+                    // https://filfox.info/en/address/f410fpoidg73f7krlfohnla52dotowde5p2sejxnd4mq
+                    to: Some(
+                        EthAddress::from_str("0x7B90337f65fAA2B2B8ed583ba1Ba6EB0C9D7eA44").unwrap(),
+                    ),
+                    ..EthCallMessage::default()
+                },
+                BlockNumberOrHash::from_block_number(shared_tipset.epoch()),
+            ))
+            .unwrap(),
+        ),
+        RpcTest::basic(
+            EthEstimateGas::request((
+                EthCallMessage {
+                    // https://filfox.info/en/address/f410fpoidg73f7krlfohnla52dotowde5p2sejxnd4mq
+                    to: Some(
+                        EthAddress::from_str("0x7B90337f65fAA2B2B8ed583ba1Ba6EB0C9D7eA44").unwrap(),
+                    ),
+                    ..EthCallMessage::default()
+                },
+                Some(BlockNumberOrHash::from_block_number(shared_tipset.epoch())),
+            ))
+            .unwrap(),
+        ),
         RpcTest::identity(
             EthGetBlockByHash::request((
                 BlockNumberOrHash::from_block_hash(block_hash.clone()),
@@ -1167,6 +1258,66 @@ fn eth_tests_with_tipset(shared_tipset: &Tipset) -> Vec<RpcTest> {
     ]
 }
 
+fn eth_state_tests_with_tipset<DB: Blockstore>(
+    store: &Arc<DB>,
+    shared_tipset: &Tipset,
+    eth_chain_id: u32,
+) -> anyhow::Result<Vec<RpcTest>> {
+    let block_hash: Hash = shared_tipset.key().cid()?.into();
+    let mut tests = vec![
+        RpcTest::identity(EthGetTransactionByBlockHashAndIndex::request((
+            block_hash.clone(),
+            Uint64(0),
+        ))?),
+        RpcTest::identity(EthGetTransactionByBlockNumberAndIndex::request((
+            BlockNumberOrHash::from_block_number(shared_tipset.epoch()),
+            Uint64(0),
+        ))?),
+        RpcTest::identity(EthTraceBlock::request((
+            BlockNumberOrHash::from_block_number(shared_tipset.epoch()),
+        ))?),
+        RpcTest::identity(EthTraceReplayBlockTransactions::request((
+            BlockNumberOrHash::from_block_number(shared_tipset.epoch()),
+            vec!["trace".to_string()],
+        ))?),
+        RpcTest::identity(EthFeeHistory::request((
+            Uint64(10),
+            BlockNumberOrHash::from_block_number(shared_tipset.epoch()),
+            Some(vec![10.0, 50.0, 90.0]),
+        ))?),
+        RpcTest::identity(EthGetLogs::request((EthFilterSpec {
+            block_hash: Some(block_hash),
+            ..Default::default()
+        },))?),
+        RpcTest::identity(EthGetLogs::request((EthFilterSpec {
+            from_block: Some(format!("0x{:x}", shared_tipset.epoch())),
+            to_block: Some(format!("0x{:x}", shared_tipset.epoch())),
+            ..Default::default()
+        },))?),
+    ];
+
+    for block in shared_tipset.block_headers() {
+        let (_, secp_messages) = crate::chain::store::block_messages(store, block)?;
+        for smsg in secp_messages
+            .iter()
+            .filter(|smsg| smsg.is_delegated())
+            .take(COLLECTION_SAMPLE_SIZE)
+        {
+            let tx_hash = eth_tx_from_signed_eth_message(smsg, eth_chain_id)?.eth_hash()?;
+            tests.extend([
+                RpcTest::identity(EthGetMessageCidByTransactionHash::request((
+                    tx_hash.clone(),
+                ))?),
+                RpcTest::identity(EthGetTransactionHashByCid::request((smsg.cid()?,))?),
+                RpcTest::identity(EthGetTransactionByHash::request((tx_hash.clone(),))?),
+                RpcTest::identity(EthGetTransactionReceipt::request((tx_hash,))?),
+            ]);
+        }
+    }
+
+    Ok(tests)
+}
+
 fn gas_tests_with_tipset(shared_tipset: &Tipset) -> Vec<RpcTest> {
     // This is a testnet address with a few FILs. The private key has been
     // discarded. If calibnet is reset, a new address should be created.
@@ -1211,6 +1362,11 @@ fn snapshot_tests(store: Arc<ManyCar>, config: &ApiTestFlags) -> anyhow::Result<
         )?);
         tests.extend(state_tests_with_tipset(&store, &tipset)?);
         tests.extend(eth_tests_with_tipset(&tipset));
+        tests.extend(eth_state_tests_with_tipset(
+            &store,
+            &tipset,
+            calibnet::ETH_CHAIN_ID as u32,
+        )?);
         tests.extend(gas_tests_with_tipset(&tipset));
     }
     Ok(tests)
@@ -1352,6 +1508,7 @@ async fn start_offline_server(
         db.clone(),
         db.clone(),
         db.clone(),
+        db.clone(),
         chain_config.clone(),
         genesis_header.clone(),
     )?);
@@ -1411,6 +1568,8 @@ async fn start_offline_server(
         beacon,
         shutdown,
         tipset_send,
+        eth_filters: Default::default(),
+        gc: None,
     };
     rpc_state.sync_state.write().set_stage(SyncStage::Idle);
     start_offline_rpc(rpc_state, rpc_port, shutdown_recv).await?;
@@ -1,13 +1,27 @@
 // Copyright 2019-2024 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::cmp;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
+use crate::chain::{for_each_prunable_event_key, index::ResolveNullTipset, ChainStore};
 use crate::cli::subcommands::prompt_confirm;
-use crate::cli_shared::{chain_path, read_config};
-use crate::db::db_engine::db_root;
-use crate::networks::NetworkChain;
+use crate::cli_shared::{chain_path, cli::Config, read_config};
+use crate::daemon::db_util::{index_eth_mappings, load_all_forest_cars};
+use crate::db::car::ManyCar;
+use crate::db::db_engine::{db_root, open_db, Db};
+use crate::db::parity_db::DbColumn;
+use crate::db::{EventsStore, MarkAndSweep};
+use crate::genesis::read_genesis_header;
+use crate::networks::{ChainConfig, NetworkChain};
+use crate::shim::clock::ChainEpoch;
+use crate::state_manager::StateManager;
+use crate::utils::db::car_stream::{CarBlock, CarWriter};
 use clap::Subcommand;
+use futures::StreamExt as _;
 use tracing::error;
 
 #[derive(Debug, Subcommand)]
@@ -33,6 +47,38 @@ pub enum DBCommands {
         #[arg(long)]
         chain: Option<NetworkChain>,
     },
+    /// Backfill the Ethereum transaction hash index from the local database, for the given
+    /// epoch range. The node must not be running.
+    BackfillEthMappings {
+        /// Lowest epoch to index
+        #[arg(long)]
+        from: ChainEpoch,
+        /// Highest epoch to index, defaults to the head of the local chain
+        #[arg(long)]
+        to: Option<ChainEpoch>,
+        /// Optional TOML file containing forest daemon configuration
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// Optional chain, will override the chain section of configuration file if used
+        #[arg(long)]
+        chain: Option<NetworkChain>,
+    },
+    /// Report the records the garbage collector would remove from the local database, per
+    /// column. The node must not be running.
+    Gc {
+        /// Run the `mark` and `filter` phases only, without removing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Optional CAR file to write the unreachable graph records to, for inspection
+        #[arg(long, requires = "dry_run")]
+        car: Option<PathBuf>,
+        /// Optional TOML file containing forest daemon configuration
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// Optional chain, will override the chain section of configuration file if used
+        #[arg(long)]
+        chain: Option<NetworkChain>,
+    },
 }
 
 impl DBCommands {
@@ -80,6 +126,172 @@ impl DBCommands {
                     }
                 }
             }
+            Self::BackfillEthMappings {
+                from,
+                to,
+                config,
+                chain,
+            } => {
+                let (_, config) = read_config(config.as_ref(), chain.clone())?;
+
+                let (_, chain_store, chain_config) = load_chain_store(&config).await?;
+                let state_manager = StateManager::new(
+                    Arc::clone(&chain_store),
+                    chain_config,
+                    Arc::new(config.sync.clone()),
+                )?;
+
+                let head = chain_store.heaviest_tipset();
+                let to = to.unwrap_or(head.epoch());
+                anyhow::ensure!(
+                    *from <= to && to <= head.epoch(),
+                    "invalid epoch range [{from}, {to}], the head is at epoch {}",
+                    head.epoch()
+                );
+                let ts = chain_store.chain_index.tipset_by_height(
+                    to,
+                    head,
+                    ResolveNullTipset::TakeOlder,
+                )?;
+                let indexed = index_eth_mappings(&state_manager, &ts, *from)?;
+                println!("Indexed {indexed} tipsets");
+                let ranges = chain_store.eth_mappings_ranges()?;
+                if !ranges.is_empty() {
+                    let ranges = ranges
+                        .iter()
+                        .map(|(lowest, highest)| format!("[{lowest}, {highest}]"))
+                        .collect::<Vec<_>>();
+                    println!("Ethereum mappings cover epochs {}", ranges.join(", "));
+                }
+                Ok(())
+            }
+            Self::Gc {
+                dry_run,
+                car,
+                config,
+                chain,
+            } => {
+                use human_repr::HumanCount;
+
+                anyhow::ensure!(
+                    *dry_run,
+                    "only `--dry-run` is supported offline, use `forest-cli db gc start` to collect garbage on a running node"
+                );
+                let (_, config) = read_config(config.as_ref(), chain.clone())?;
+
+                let (db_writer, chain_store, chain_config) = load_chain_store(&config).await?;
+
+                // Same depth as the garbage collector of the daemon.
+                let depth = cmp::max(
+                    chain_config.policy.chain_finality * 2,
+                    config.sync.recent_state_roots,
+                );
+                let head = chain_store.heaviest_tipset();
+                println!(
+                    "Filtering records reachable from epoch {} with depth {depth}",
+                    head.epoch()
+                );
+                // Same as the garbage collector, events are pruned below the filtered epochs.
+                let events_epoch = head.epoch() - depth;
+                let mut gc = MarkAndSweep::new(
+                    db_writer.clone(),
+                    Box::new(move || chain_store.heaviest_tipset()),
+                    depth,
+                    Duration::from_secs(chain_config.block_delay_secs as u64),
+                );
+                let keys = gc.unreachable_keys().await?;
+
+                let (car_tx, car_handle) = if let Some(car) = car {
+                    let (car_tx, car_rx) = flume::bounded(100);
+                    let roots = head.key().to_cids();
+                    let file = tokio::fs::File::create(car).await?;
+                    let car_handle = tokio::spawn(async move {
+                        car_rx
+                            .into_stream()
+                            .map(Ok)
+                            .forward(CarWriter::new_carv1(roots, file)?)
+                            .await
+                    });
+                    (Some(car_tx), Some(car_handle))
+                } else {
+                    (None, None)
+                };
+
+                let report = tokio::task::spawn_blocking(move || {
+                    let mut report = BTreeMap::<String, (usize, u64)>::new();
+                    db_writer.for_each_marked(&keys, |column, cid, data| {
+                        let (count, bytes) = report.entry(column.to_string()).or_default();
+                        *count += 1;
+                        *bytes += data.len() as u64;
+                        if let Some(car_tx) = &car_tx {
+                            car_tx
+                                .send(CarBlock {
+                                    cid,
+                                    data: data.to_vec(),
+                                })
+                                .map_err(|_| anyhow::anyhow!("the CAR writer has stopped"))?;
+                        }
+                        Ok(())
+                    })?;
+                    for_each_prunable_event_key(db_writer.as_ref(), events_epoch, |key| {
+                        if let Some(data) = EventsStore::read_bin(db_writer.as_ref(), key)? {
+                            let (count, bytes) =
+                                report.entry(DbColumn::Events.to_string()).or_default();
+                            *count += 1;
+                            *bytes += data.len() as u64;
+                        }
+                        Ok(())
+                    })?;
+                    anyhow::Ok(report)
+                })
+                .await??;
+                if let Some(car_handle) = car_handle {
+                    car_handle.await??;
+                }
+
+                let (mut total_count, mut total_bytes) = (0, 0);
+                for (column, (count, bytes)) in report {
+                    println!("{column}: {count} records, {}", bytes.human_count_bytes());
+                    total_count += count;
+                    total_bytes += bytes;
+                }
+                println!(
+                    "Unreachable: {total_count} records, {}",
+                    total_bytes.human_count_bytes()
+                );
+                if let Some(car) = car {
+                    println!("Unreachable records written to {}", car.display());
+                }
+                Ok(())
+            }
         }
     }
 }
+
+/// Opens the local database of a stopped node, with its CAR files, and loads the chain store on top
+/// of it.
+async fn load_chain_store(
+    config: &Config,
+) -> anyhow::Result<(Arc<Db>, Arc<ChainStore<ManyCar<Arc<Db>>>>, Arc<ChainConfig>)> {
+    let db_root_dir = db_root(&chain_path(config))?;
+    let db_writer = Arc::new(open_db(db_root_dir.clone(), config.db_config().clone())?);
+    let db = Arc::new(ManyCar::new(db_writer.clone()));
+    load_all_forest_cars(&db, &db_root_dir.join("car_db"))?;
+
+    let chain_config = Arc::new(ChainConfig::from_chain(&config.chain));
+    let genesis_header = read_genesis_header(
+        config.client.genesis_file.as_ref(),
+        chain_config.genesis_bytes(&db).await?.as_deref(),
+        &db,
+    )
+    .await?;
+    let chain_store = Arc::new(ChainStore::new(
+        Arc::clone(&db),
+        db.writer().clone(),
+        db.writer().clone(),
+        db.writer().clone(),
+        chain_config.clone(),
+        genesis_header,
+    )?);
+    Ok((db_writer, chain_store, chain_config))
+}